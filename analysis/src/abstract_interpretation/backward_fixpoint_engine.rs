@@ -0,0 +1,128 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::{AbstractState, AnalysisResult},
+    PointwiseState,
+};
+use prusti_rustc_interface::{data_structures::fx::FxHashMap, middle::mir, span::def_id::DefId};
+use std::{collections::BTreeSet, iter::FromIterator};
+
+/// Trait to be used to define a backward static analysis of a MIR body, in which the state
+/// before each statement is computed from the state after it.
+///
+/// The result is a `PointwiseState` like the one of a forward analysis: the state on the CFG edge
+/// from a block to one of its successors is the state at the beginning of the successor.
+pub trait BackwardFixpointEngine<'mir, 'tcx: 'mir> {
+    type State: AbstractState;
+
+    /// Return the DefId of the MIR body to be analyzed.
+    fn def_id(&self) -> DefId;
+
+    /// Return the MIR body to be analyzed.
+    fn body(&self) -> &'mir mir::Body<'tcx>;
+
+    /// Creates a new abstract state which corresponds to the bottom element in the lattice
+    fn new_bottom(&self) -> Self::State;
+
+    /// Determines if the number of times a block was traversed by the analyzer given in `counter`
+    /// is large enough to widen the state
+    fn need_to_widen(counter: u32) -> bool;
+
+    /// Modify the state after the statement at `location` into the state before it.
+    ///
+    /// The statement can be extracted using
+    /// `self.mir[location.block].statements[location.statement_index]`.
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()>;
+
+    /// Compute the state before the terminator at `location`, given the state at the beginning
+    /// of each of its successors. For terminators without successors, such as `Return`,
+    /// `states_after` is empty.
+    ///
+    /// The terminator can be extracted using `self.mir[location.block].terminator()`.
+    fn apply_terminator_effect(
+        &self,
+        states_after: &FxHashMap<mir::BasicBlock, Self::State>,
+        location: mir::Location,
+    ) -> AnalysisResult<Self::State>;
+
+    /// Produces an abstract state for every program point in `mir` by iterating over all statements
+    /// in reverse program order until a fixed point is reached.
+    fn run_bwd_analysis(&self) -> AnalysisResult<PointwiseState<'mir, 'tcx, Self::State>> {
+        let mir = self.body();
+        let mut p_state = PointwiseState::new(mir);
+        let mut work_set: BTreeSet<mir::BasicBlock> =
+            BTreeSet::from_iter(mir.basic_blocks.indices());
+
+        let mut counters: FxHashMap<mir::BasicBlock, u32> =
+            FxHashMap::with_capacity_and_hasher(mir.basic_blocks.len(), Default::default());
+
+        // extract the bb with the maximal index, because blocks are mostly numbered in program
+        // order
+        while let Some(&bb) = work_set.iter().next_back() {
+            work_set.remove(&bb);
+
+            let entry_location = mir::Location {
+                block: bb,
+                statement_index: 0,
+            };
+            let prev_entry_state = p_state.lookup_before(entry_location).cloned();
+
+            // if no state is present for a successor: assume bottom
+            let terminator = mir[bb].terminator();
+            let states_after: FxHashMap<_, _> = terminator
+                .successors()
+                .map(|next_bb| {
+                    let next_location = mir::Location {
+                        block: next_bb,
+                        statement_index: 0,
+                    };
+                    let state = p_state
+                        .lookup_before(next_location)
+                        .cloned()
+                        .unwrap_or_else(|| self.new_bottom());
+                    (next_bb, state)
+                })
+                .collect();
+
+            let location = mir.terminator_loc(bb);
+            let mut current_state = self.apply_terminator_effect(&states_after, location)?;
+
+            // widen if needed
+            let counter = counters.entry(bb).or_insert(0);
+            *counter += 1;
+
+            if Self::need_to_widen(*counter) {
+                if let Some(prev_state) = p_state.lookup_before(location) {
+                    current_state.widen(prev_state);
+                }
+            }
+
+            *p_state.lookup_mut_after_block(bb) = states_after;
+            p_state.set_before(location, current_state.clone());
+
+            // statement effects, in reverse order
+            for statement_index in (0..mir[bb].statements.len()).rev() {
+                let location = mir::Location {
+                    block: bb,
+                    statement_index,
+                };
+                self.apply_statement_effect(&mut current_state, location)?;
+                p_state.set_before(location, current_state.clone());
+            }
+
+            if prev_entry_state.as_ref() != Some(&current_state) {
+                // state at the beginning of the block has changed => add predecessors to worklist
+                work_set.extend(mir.basic_blocks.predecessors()[bb].iter().copied());
+            }
+        }
+        AnalysisResult::Ok(p_state)
+    }
+}
@@ -5,11 +5,13 @@
 // https://github.com/rust-lang/rust/blob/master/src/test/run-make-fulldeps/obtain-borrowck/driver.rs
 
 use analysis::{
-    abstract_interpretation::FixpointEngine,
+    abstract_interpretation::{BackwardFixpointEngine, FixpointEngine},
     domains::{
         DefinitelyAccessibleAnalysis, DefinitelyInitializedAnalysis, FramingAnalysis,
-        MaybeBorrowedAnalysis, ReachingDefsAnalysis,
+        IntervalAnalysis, IntervalSummaryAnalysis, LiveVariablesAnalysis, MaybeBorrowedAnalysis,
+        OctagonAnalysis, ReachingDefsAnalysis,
     },
+    summaries::{ControlFlowSummaryAnalysis, Summaries},
 };
 use prusti_rustc_interface::{
     ast::ast,
@@ -101,16 +103,13 @@ mod mir_storage {
     }
 
     #[allow(clippy::needless_lifetimes)] // We want to be very explicit about lifetimes here.
-    pub unsafe fn retrieve_mir_body<'tcx>(
+    pub unsafe fn retrieve_mir_bodies<'tcx>(
         _tcx: ty::TyCtxt<'tcx>,
-        def_id: LocalDefId,
-    ) -> BodyWithBorrowckFacts<'tcx> {
-        let body_with_facts: BodyWithBorrowckFacts<'static> = MIR_BODIES.with(|state| {
-            let mut map = state.borrow_mut();
-            map.remove(&def_id).unwrap()
-        });
+    ) -> HashMap<LocalDefId, BodyWithBorrowckFacts<'tcx>> {
+        let bodies: HashMap<LocalDefId, BodyWithBorrowckFacts<'static>> =
+            MIR_BODIES.with(|state| state.borrow_mut().drain().collect());
         // SAFETY: See the module level comment.
-        std::mem::transmute(body_with_facts)
+        std::mem::transmute(bodies)
     }
 }
 
@@ -179,16 +178,44 @@ impl prusti_rustc_interface::driver::Callbacks for OurCompilerCalls {
                     .span
             });
 
+            // SAFETY: This is safe because we are feeding in the same `tcx`
+            // that was used to store the data.
+            let mut bodies = unsafe { self::mir_storage::retrieve_mir_bodies(tcx) };
+
+            // summarize all functions of the crate, for the interprocedural analyses
+            let interval_summaries =
+                (abstract_domain == "InterproceduralIntervalAnalysis").then(|| {
+                    let bodies = bodies
+                        .iter()
+                        .map(|(local_def_id, body_with_facts)| {
+                            (local_def_id.to_def_id(), &body_with_facts.body)
+                        })
+                        .collect();
+                    Summaries::compute(tcx, &IntervalSummaryAnalysis::new(tcx), &bodies)
+                });
+            let control_flow_summaries = matches!(
+                abstract_domain,
+                "InterproceduralDefinitelyInitializedAnalysis"
+                    | "InterproceduralDefinitelyAccessibleAnalysis"
+                    | "InterproceduralFramingAnalysis"
+            )
+            .then(|| {
+                let bodies = bodies
+                    .iter()
+                    .map(|(local_def_id, body_with_facts)| {
+                        (local_def_id.to_def_id(), &body_with_facts.body)
+                    })
+                    .collect();
+                Summaries::compute(tcx, &ControlFlowSummaryAnalysis::new(tcx), &bodies)
+            });
+
             for &local_def_id in local_def_ids {
                 println!(
                     "Result for function {}():",
                     tcx.item_name(local_def_id.to_def_id())
                 );
 
-                // SAFETY: This is safe because we are feeding in the same `tcx`
-                // that was used to store the data.
-                let mut body_with_facts =
-                    unsafe { self::mir_storage::retrieve_mir_body(tcx, local_def_id) };
+                let mut body_with_facts = bodies.remove(&local_def_id).unwrap();
                 body_with_facts.output_facts = Rc::new(Output::compute(
                     &body_with_facts.input_facts,
                     Algorithm::Naive,
@@ -219,6 +246,21 @@ impl prusti_rustc_interface::driver::Callbacks for OurCompilerCalls {
                             Err(e) => eprintln!("{}", e.to_pretty_str(body)),
                         }
                     }
+                    "InterproceduralDefinitelyInitializedAnalysis" => {
+                        let result = DefinitelyInitializedAnalysis::new_with_summaries(
+                            tcx,
+                            local_def_id.to_def_id(),
+                            body,
+                            control_flow_summaries.clone().unwrap(),
+                        )
+                        .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
                     "RelaxedDefinitelyInitializedAnalysis" => {
                         let result = DefinitelyInitializedAnalysis::new_relaxed(
                             tcx,
@@ -255,6 +297,20 @@ impl prusti_rustc_interface::driver::Callbacks for OurCompilerCalls {
                             Err(e) => eprintln!("{}", e.to_pretty_str(body)),
                         }
                     }
+                    "InterproceduralDefinitelyAccessibleAnalysis" => {
+                        let analyzer = DefinitelyAccessibleAnalysis::new_with_summaries(
+                            tcx,
+                            local_def_id.to_def_id(),
+                            &body_with_facts,
+                            control_flow_summaries.clone().unwrap(),
+                        );
+                        match analyzer.run_analysis() {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap());
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
                     "FramingAnalysis" => {
                         let analyzer =
                             FramingAnalysis::new(tcx, local_def_id.to_def_id(), &body_with_facts);
@@ -265,6 +321,65 @@ impl prusti_rustc_interface::driver::Callbacks for OurCompilerCalls {
                             Err(e) => eprintln!("{}", e.to_pretty_str(body)),
                         }
                     }
+                    "InterproceduralFramingAnalysis" => {
+                        let analyzer = FramingAnalysis::new_with_summaries(
+                            tcx,
+                            local_def_id.to_def_id(),
+                            &body_with_facts,
+                            control_flow_summaries.clone().unwrap(),
+                        );
+                        match analyzer.run_analysis() {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap());
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
+                    "IntervalAnalysis" => {
+                        let result = IntervalAnalysis::new(tcx, local_def_id.to_def_id(), body)
+                            .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
+                    "InterproceduralIntervalAnalysis" => {
+                        let result = IntervalAnalysis::new_with_summaries(
+                            tcx,
+                            local_def_id.to_def_id(),
+                            body,
+                            interval_summaries.clone().unwrap(),
+                        )
+                        .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
+                    "OctagonAnalysis" => {
+                        let result = OctagonAnalysis::new(tcx, local_def_id.to_def_id(), body)
+                            .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
+                    "LiveVariablesAnalysis" => {
+                        let result = LiveVariablesAnalysis::new(local_def_id.to_def_id(), body)
+                            .run_bwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
                     _ => panic!("Unknown domain argument: {}", abstract_domain),
                 }
             }
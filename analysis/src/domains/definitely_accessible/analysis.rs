@@ -11,6 +11,7 @@ use crate::{
         MaybeBorrowedAnalysis, MaybeBorrowedState,
     },
     mir_utils::remove_place_from_set,
+    summaries::{ControlFlowSummary, Summaries},
     PointwiseState,
 };
 use prusti_rustc_interface::{
@@ -19,11 +20,13 @@ use prusti_rustc_interface::{
     middle::{mir, ty::TyCtxt},
     span::def_id::DefId,
 };
+use std::rc::Rc;
 
 pub struct DefinitelyAccessibleAnalysis<'mir, 'tcx: 'mir> {
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
     body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+    summaries: Rc<Summaries<ControlFlowSummary>>,
 }
 
 impl<'mir, 'tcx: 'mir> DefinitelyAccessibleAnalysis<'mir, 'tcx> {
@@ -31,11 +34,23 @@ impl<'mir, 'tcx: 'mir> DefinitelyAccessibleAnalysis<'mir, 'tcx> {
         tcx: TyCtxt<'tcx>,
         def_id: DefId,
         body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+    ) -> Self {
+        Self::new_with_summaries(tcx, def_id, body_with_facts, Rc::new(Summaries::default()))
+    }
+
+    /// An analysis whose definitely initialized places use the summaries of the called
+    /// functions, when they are available.
+    pub fn new_with_summaries(
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+        summaries: Rc<Summaries<ControlFlowSummary>>,
     ) -> Self {
         DefinitelyAccessibleAnalysis {
             tcx,
             def_id,
             body_with_facts,
+            summaries,
         }
     }
 
@@ -43,8 +58,12 @@ impl<'mir, 'tcx: 'mir> DefinitelyAccessibleAnalysis<'mir, 'tcx> {
         &self,
     ) -> AnalysisResult<PointwiseState<'mir, 'tcx, DefinitelyAccessibleState<'tcx>>> {
         let body = &self.body_with_facts.body;
-        let def_init_analysis =
-            DefinitelyInitializedAnalysis::new_relaxed(self.tcx, self.def_id, body);
+        let def_init_analysis = DefinitelyInitializedAnalysis::new_relaxed_with_summaries(
+            self.tcx,
+            self.def_id,
+            body,
+            self.summaries.clone(),
+        );
         let borrowed_analysis = MaybeBorrowedAnalysis::new(self.tcx, self.body_with_facts);
         let def_init = def_init_analysis.run_fwd_analysis()?;
         let borrowed = borrowed_analysis.run_analysis()?;
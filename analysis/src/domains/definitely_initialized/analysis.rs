@@ -7,12 +7,14 @@
 use crate::{
     abstract_interpretation::{AnalysisResult, FixpointEngine},
     domains::DefinitelyInitializedState,
+    summaries::{ControlFlowSummary, Summaries},
 };
 use prusti_rustc_interface::{
     data_structures::fx::FxHashSet,
     middle::{mir, ty::TyCtxt},
     span::def_id::DefId,
 };
+use std::rc::Rc;
 
 pub struct DefinitelyInitializedAnalysis<'mir, 'tcx: 'mir> {
     tcx: TyCtxt<'tcx>,
@@ -20,25 +22,49 @@ pub struct DefinitelyInitializedAnalysis<'mir, 'tcx: 'mir> {
     mir: &'mir mir::Body<'tcx>,
     /// If the place is a Copy type, uninitialise the place iif `move_out_copy_types` is true.
     move_out_copy_types: bool,
+    summaries: Rc<Summaries<ControlFlowSummary>>,
 }
 
 impl<'mir, 'tcx: 'mir> DefinitelyInitializedAnalysis<'mir, 'tcx> {
     pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        Self::new_with_summaries(tcx, def_id, mir, Rc::new(Summaries::default()))
+    }
+
+    /// An analysis that uses the summaries of the called functions, when they are available.
+    pub fn new_with_summaries(
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        mir: &'mir mir::Body<'tcx>,
+        summaries: Rc<Summaries<ControlFlowSummary>>,
+    ) -> Self {
         DefinitelyInitializedAnalysis {
             tcx,
             def_id,
             mir,
             move_out_copy_types: true,
+            summaries,
         }
     }
 
     /// This analysis will not uninitialize Copy types when they are moved.
     pub fn new_relaxed(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        Self::new_relaxed_with_summaries(tcx, def_id, mir, Rc::new(Summaries::default()))
+    }
+
+    /// A relaxed analysis that uses the summaries of the called functions, when they are
+    /// available.
+    pub fn new_relaxed_with_summaries(
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        mir: &'mir mir::Body<'tcx>,
+        summaries: Rc<Summaries<ControlFlowSummary>>,
+    ) -> Self {
         DefinitelyInitializedAnalysis {
             tcx,
             def_id,
             mir,
             move_out_copy_types: false,
+            summaries,
         }
     }
 }
@@ -103,6 +129,24 @@ impl<'mir, 'tcx: 'mir> FixpointEngine<'mir, 'tcx> for DefinitelyInitializedAnaly
         state: &Self::State,
         location: mir::Location,
     ) -> AnalysisResult<Vec<(mir::BasicBlock, Self::State)>> {
-        state.apply_terminator_effect(location, self.move_out_copy_types)
+        let mut res_vec = state.apply_terminator_effect(location, self.move_out_copy_types)?;
+        // The edges of a call that the callee never takes are unreachable
+        if let mir::TerminatorKind::Call {
+            ref func,
+            target,
+            cleanup,
+            ..
+        } = self.mir[location.block].terminator().kind
+        {
+            let summary = ControlFlowSummary::of_call(self.tcx, self.def_id, func, &self.summaries);
+            for (bb, succ_state) in res_vec.iter_mut() {
+                if (Some(*bb) == target && !summary.may_return)
+                    || (Some(*bb) == cleanup && !summary.may_unwind)
+                {
+                    *succ_state = self.new_bottom();
+                }
+            }
+        }
+        Ok(res_vec)
     }
 }
@@ -8,6 +8,7 @@ use crate::{
     abstract_interpretation::AnalysisResult,
     domains::{DefinitelyAccessibleAnalysis, DefinitelyAccessibleState, FramingState},
     mir_utils::{get_blocked_place, remove_place_from_set},
+    summaries::{ControlFlowSummary, Summaries},
     PointwiseState,
 };
 use prusti_rustc_interface::{
@@ -19,11 +20,13 @@ use prusti_rustc_interface::{
     },
     span::def_id::DefId,
 };
+use std::rc::Rc;
 
 pub struct FramingAnalysis<'mir, 'tcx: 'mir> {
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
     body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+    summaries: Rc<Summaries<ControlFlowSummary>>,
 }
 
 impl<'mir, 'tcx: 'mir> FramingAnalysis<'mir, 'tcx> {
@@ -31,17 +34,33 @@ impl<'mir, 'tcx: 'mir> FramingAnalysis<'mir, 'tcx> {
         tcx: TyCtxt<'tcx>,
         def_id: DefId,
         body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+    ) -> Self {
+        Self::new_with_summaries(tcx, def_id, body_with_facts, Rc::new(Summaries::default()))
+    }
+
+    /// An analysis whose accessible places use the summaries of the called functions, when
+    /// they are available.
+    pub fn new_with_summaries(
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        body_with_facts: &'mir BodyWithBorrowckFacts<'tcx>,
+        summaries: Rc<Summaries<ControlFlowSummary>>,
     ) -> Self {
         FramingAnalysis {
             tcx,
             def_id,
             body_with_facts,
+            summaries,
         }
     }
 
     pub fn run_analysis(&self) -> AnalysisResult<PointwiseState<'mir, 'tcx, FramingState<'tcx>>> {
-        let acc_analysis =
-            DefinitelyAccessibleAnalysis::new(self.tcx, self.def_id, self.body_with_facts);
+        let acc_analysis = DefinitelyAccessibleAnalysis::new_with_summaries(
+            self.tcx,
+            self.def_id,
+            self.body_with_facts,
+            self.summaries.clone(),
+        );
         let accessibility = acc_analysis.run_analysis()?;
         let body = &self.body_with_facts.body;
         let mut analysis_state = PointwiseState::default(body);
@@ -0,0 +1,185 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::interval::integer_value;
+use crate::{
+    abstract_interpretation::{AnalysisResult, FixpointEngine},
+    domains::{IntervalState, IntervalSummary},
+    summaries::Summaries,
+};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::{
+        mir::{self, visit::Visitor},
+        ty::TyCtxt,
+    },
+    span::def_id::DefId,
+};
+use std::{collections::BTreeSet, rc::Rc};
+
+/// An analysis that computes an interval of possible values for each integer local.
+pub struct IntervalAnalysis<'mir, 'tcx: 'mir> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    mir: &'mir mir::Body<'tcx>,
+    tracked_locals: Rc<FxHashSet<mir::Local>>,
+    thresholds: Rc<BTreeSet<i128>>,
+    summaries: Rc<Summaries<IntervalSummary>>,
+}
+
+impl<'mir, 'tcx: 'mir> IntervalAnalysis<'mir, 'tcx> {
+    /// An analysis in which the result of a call can be any value of its type.
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        Self::new_with_summaries(tcx, def_id, mir, Rc::new(Summaries::default()))
+    }
+
+    /// An analysis that uses the summaries of the called functions, when they are available.
+    pub fn new_with_summaries(
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        mir: &'mir mir::Body<'tcx>,
+        summaries: Rc<Summaries<IntervalSummary>>,
+    ) -> Self {
+        let collector = LocalsCollector::new(tcx, mir);
+        let tracked_locals = mir
+            .local_decls
+            .iter_enumerated()
+            .filter(|(local, decl)| {
+                (decl.ty.is_integral()
+                    || decl.ty.is_bool()
+                    || collector.checked_results.contains(local))
+                    && !collector.borrowed.contains(local)
+            })
+            .map(|(local, _)| local)
+            .collect();
+        IntervalAnalysis {
+            tcx,
+            def_id,
+            mir,
+            tracked_locals: Rc::new(tracked_locals),
+            thresholds: Rc::new(collector.thresholds),
+            summaries,
+        }
+    }
+}
+
+/// Collects the locals whose address is taken, the locals that store the result of a checked
+/// operation, and the thresholds for widening (the integer constants of the body and their
+/// neighbours).
+pub(crate) struct LocalsCollector<'mir, 'tcx: 'mir> {
+    tcx: TyCtxt<'tcx>,
+    mir: &'mir mir::Body<'tcx>,
+    pub(crate) borrowed: FxHashSet<mir::Local>,
+    pub(crate) checked_results: FxHashSet<mir::Local>,
+    pub(crate) thresholds: BTreeSet<i128>,
+}
+
+impl<'mir, 'tcx: 'mir> LocalsCollector<'mir, 'tcx> {
+    pub(crate) fn new(tcx: TyCtxt<'tcx>, mir: &'mir mir::Body<'tcx>) -> Self {
+        let mut collector = LocalsCollector {
+            tcx,
+            mir,
+            borrowed: FxHashSet::default(),
+            checked_results: FxHashSet::default(),
+            thresholds: BTreeSet::new(),
+        };
+        collector.visit_body(mir);
+        collector
+    }
+}
+
+impl<'mir, 'tcx: 'mir> Visitor<'tcx> for LocalsCollector<'mir, 'tcx> {
+    fn visit_assign(
+        &mut self,
+        place: &mir::Place<'tcx>,
+        rvalue: &mir::Rvalue<'tcx>,
+        location: mir::Location,
+    ) {
+        match rvalue {
+            mir::Rvalue::Ref(_, _, borrowed_place) | mir::Rvalue::AddressOf(_, borrowed_place) => {
+                self.borrowed.insert(borrowed_place.local);
+            }
+            mir::Rvalue::CheckedBinaryOp(..) => {
+                if let Some(local) = place.as_local() {
+                    self.checked_results.insert(local);
+                }
+            }
+            _ => {}
+        }
+        self.super_assign(place, rvalue, location);
+    }
+
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, _location: mir::Location) {
+        let ty = constant.ty();
+        if ty.is_integral() {
+            let param_env = self.tcx.param_env(self.mir.source.def_id());
+            let value = constant
+                .literal
+                .try_eval_bits(self.tcx, param_env, ty)
+                .and_then(|bits| integer_value(self.tcx, bits, ty));
+            if let Some(value) = value {
+                self.thresholds
+                    .extend([value.saturating_sub(1), value, value.saturating_add(1)]);
+            }
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> FixpointEngine<'mir, 'tcx> for IntervalAnalysis<'mir, 'tcx> {
+    type State = IntervalState<'mir, 'tcx>;
+
+    fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    fn body(&self) -> &'mir mir::Body<'tcx> {
+        self.mir
+    }
+
+    /// The bottom element of the lattice represents an unreachable program point
+    fn new_bottom(&self) -> Self::State {
+        IntervalState {
+            reachable: false,
+            intervals: FxHashMap::default(),
+            conditions: FxHashMap::default(),
+            copies: FxHashMap::default(),
+            tracked_locals: self.tracked_locals.clone(),
+            thresholds: self.thresholds.clone(),
+            summaries: self.summaries.clone(),
+            mir: self.mir,
+            tcx: self.tcx,
+        }
+    }
+
+    /// The arguments can have any value of their type
+    fn new_initial(&self) -> Self::State {
+        IntervalState {
+            reachable: true,
+            ..self.new_bottom()
+        }
+    }
+
+    fn need_to_widen(counter: u32) -> bool {
+        // Give simple loops a chance to stabilize before widening
+        counter > 2
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()> {
+        state.apply_statement_effect(location)
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<Vec<(mir::BasicBlock, Self::State)>> {
+        state.apply_terminator_effect(location)
+    }
+}
@@ -0,0 +1,192 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use prusti_rustc_interface::middle::ty::{self, TyCtxt};
+use std::{collections::BTreeSet, fmt};
+
+/// A (possibly unbounded) interval `[lower, upper]` of integer values.
+/// A missing bound means that the interval is unbounded in that direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub lower: Option<i128>,
+    pub upper: Option<i128>,
+}
+
+impl Interval {
+    pub fn new(lower: Option<i128>, upper: Option<i128>) -> Self {
+        Interval { lower, upper }
+    }
+
+    pub fn top() -> Self {
+        Interval::new(None, None)
+    }
+
+    pub fn constant(value: i128) -> Self {
+        Interval::new(Some(value), Some(value))
+    }
+
+    /// The range of values of an integer type, or the top interval for other types.
+    pub fn of_type<'tcx>(tcx: TyCtxt<'tcx>, ty: ty::Ty<'tcx>) -> Self {
+        let pointer_width = tcx.data_layout.pointer_size.bits();
+        match ty.kind() {
+            ty::TyKind::Int(int_ty) => {
+                let width = int_ty.bit_width().unwrap_or(pointer_width);
+                let max = i128::MAX >> (128 - width);
+                Interval::new(Some(-max - 1), Some(max))
+            }
+            ty::TyKind::Uint(uint_ty) => {
+                let width = uint_ty.bit_width().unwrap_or(pointer_width);
+                // The maximum of `u128` does not fit in an `i128`
+                let max = if width < 128 {
+                    Some(((1u128 << width) - 1) as i128)
+                } else {
+                    None
+                };
+                Interval::new(Some(0), max)
+            }
+            _ => Interval::top(),
+        }
+    }
+
+    pub fn is_top(&self) -> bool {
+        self.lower.is_none() && self.upper.is_none()
+    }
+
+    /// Checks whether the interval does not contain any value.
+    pub fn is_empty(&self) -> bool {
+        matches!((self.lower, self.upper), (Some(lower), Some(upper)) if lower > upper)
+    }
+
+    /// Checks whether all values of `self` are also in `other`.
+    pub fn is_included_in(&self, other: &Self) -> bool {
+        let lower_ok = match (self.lower, other.lower) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(a), Some(b)) => a >= b,
+        };
+        let upper_ok = match (self.upper, other.upper) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(a), Some(b)) => a <= b,
+        };
+        lower_ok && upper_ok
+    }
+
+    /// The smallest interval containing both `self` and `other`.
+    pub fn join(&self, other: &Self) -> Self {
+        Interval::new(
+            self.lower.zip(other.lower).map(|(a, b)| a.min(b)),
+            self.upper.zip(other.upper).map(|(a, b)| a.max(b)),
+        )
+    }
+
+    /// The intersection of `self` and `other`, which might be empty.
+    pub fn meet(&self, other: &Self) -> Self {
+        Interval::new(
+            max_bound(self.lower, other.lower),
+            min_bound(self.upper, other.upper),
+        )
+    }
+
+    /// Moves each bound that grew with respect to `previous` to the next threshold, or drops it
+    /// if there is none.
+    pub fn widen(&self, previous: &Self, thresholds: &BTreeSet<i128>) -> Self {
+        let lower = match (self.lower, previous.lower) {
+            (Some(a), Some(b)) if a < b => thresholds.range(..=a).next_back().copied(),
+            (lower, _) => lower,
+        };
+        let upper = match (self.upper, previous.upper) {
+            (Some(a), Some(b)) if a > b => thresholds.range(a..).next().copied(),
+            (upper, _) => upper,
+        };
+        Interval::new(lower, upper)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Interval::new(
+            self.lower
+                .zip(other.lower)
+                .and_then(|(a, b)| a.checked_add(b)),
+            self.upper
+                .zip(other.upper)
+                .and_then(|(a, b)| a.checked_add(b)),
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn neg(&self) -> Self {
+        Interval::new(
+            self.upper.and_then(i128::checked_neg),
+            self.lower.and_then(i128::checked_neg),
+        )
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let bounds = [self.lower, self.upper]
+            .into_iter()
+            .zip([other.lower, other.upper])
+            .chain([(self.lower, other.upper), (self.upper, other.lower)]);
+        let mut products = Vec::with_capacity(4);
+        for (a, b) in bounds {
+            match a.zip(b).and_then(|(a, b)| a.checked_mul(b)) {
+                Some(product) => products.push(product),
+                None => return Interval::top(),
+            }
+        }
+        Interval::new(
+            products.iter().min().copied(),
+            products.iter().max().copied(),
+        )
+    }
+}
+
+/// Interprets the bits of a constant of type `ty` as an integer.
+pub(crate) fn integer_value<'tcx>(tcx: TyCtxt<'tcx>, bits: u128, ty: ty::Ty<'tcx>) -> Option<i128> {
+    match ty.kind() {
+        ty::TyKind::Int(int_ty) => {
+            // Sign-extend the value
+            let width = int_ty
+                .bit_width()
+                .unwrap_or_else(|| tcx.data_layout.pointer_size.bits());
+            let shift = 128 - width;
+            Some(((bits << shift) as i128) >> shift)
+        }
+        ty::TyKind::Uint(_) | ty::TyKind::Bool | ty::TyKind::Char => i128::try_from(bits).ok(),
+        _ => None,
+    }
+}
+
+fn max_bound(a: Option<i128>, b: Option<i128>) -> Option<i128> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+fn min_bound(a: Option<i128>, b: Option<i128>) -> Option<i128> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lower {
+            Some(lower) => write!(f, "[{}", lower)?,
+            None => write!(f, "(-inf")?,
+        }
+        match self.upper {
+            Some(upper) => write!(f, ", {}]", upper),
+            None => write!(f, ", +inf)"),
+        }
+    }
+}
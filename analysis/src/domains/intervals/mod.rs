@@ -0,0 +1,15 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+mod analysis;
+mod interval;
+mod state;
+mod summary;
+
+pub use self::analysis::*;
+pub use interval::*;
+pub use state::*;
+pub use summary::*;
@@ -0,0 +1,631 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::interval::integer_value;
+use crate::{
+    abstract_interpretation::AbstractState,
+    domains::{Interval, IntervalSummary},
+    summaries::{resolve_callee, Summaries},
+    AnalysisError,
+};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::{
+        mir,
+        ty::{self, TyCtxt},
+    },
+};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    rc::Rc,
+};
+
+/// An interval of possible values for each integer local that is tracked by the analysis.
+/// Locals that are not in the map can have any value of their type.
+///
+/// The comparisons stored in boolean locals are remembered, to refine the intervals on the
+/// branches of a `SwitchInt` or `Assert` that uses them.
+#[derive(Clone)]
+pub struct IntervalState<'mir, 'tcx: 'mir> {
+    /// `false` iff the state is the bottom element, i.e. the program point is unreachable.
+    pub(super) reachable: bool,
+    pub(super) intervals: FxHashMap<mir::Local, Interval>,
+    /// Boolean locals that hold the result of a comparison
+    pub(super) conditions: FxHashMap<mir::Local, Comparison>,
+    /// Locals that hold a copy of the value of another local
+    pub(super) copies: FxHashMap<mir::Local, mir::Local>,
+    /// Integer and boolean locals (and results of checked operations) whose address is never
+    /// taken
+    pub(super) tracked_locals: Rc<FxHashSet<mir::Local>>,
+    /// Bounds to which intervals are widened, before giving up on a bound
+    pub(super) thresholds: Rc<BTreeSet<i128>>,
+    /// Summaries of the called functions
+    pub(super) summaries: Rc<Summaries<IntervalSummary>>,
+    pub(super) mir: &'mir mir::Body<'tcx>,
+    pub(super) tcx: TyCtxt<'tcx>,
+}
+
+/// A comparison `left op right` between two integer terms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Comparison {
+    pub op: mir::BinOp,
+    pub left: Term,
+    pub right: Term,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Term {
+    Local(mir::Local),
+    Constant(i128),
+}
+
+impl Comparison {
+    pub(crate) fn negate(self) -> Option<Self> {
+        let op = match self.op {
+            mir::BinOp::Lt => mir::BinOp::Ge,
+            mir::BinOp::Le => mir::BinOp::Gt,
+            mir::BinOp::Gt => mir::BinOp::Le,
+            mir::BinOp::Ge => mir::BinOp::Lt,
+            mir::BinOp::Eq => mir::BinOp::Ne,
+            mir::BinOp::Ne => mir::BinOp::Eq,
+            _ => return None,
+        };
+        Some(Comparison { op, ..self })
+    }
+
+    pub(crate) fn mentions(&self, local: mir::Local) -> bool {
+        self.left == Term::Local(local) || self.right == Term::Local(local)
+    }
+}
+
+impl<'mir, 'tcx: 'mir> fmt::Debug for IntervalState<'mir, 'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ignore tcx & mir
+        f.debug_struct("IntervalState")
+            .field("reachable", &self.reachable)
+            .field("intervals", &self.intervals)
+            .field("conditions", &self.conditions)
+            .field("copies", &self.copies)
+            .finish()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> PartialEq for IntervalState<'mir, 'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignore the `mir` field and the fields shared by all states.
+        self.reachable == other.reachable
+            && self.intervals == other.intervals
+            && self.conditions == other.conditions
+            && self.copies == other.copies
+    }
+}
+impl<'mir, 'tcx: 'mir> Eq for IntervalState<'mir, 'tcx> {}
+
+impl<'mir, 'tcx: 'mir> Serialize for IntervalState<'mir, 'tcx> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        if !self.reachable {
+            return serializer.serialize_str("unreachable");
+        }
+        let mut map = serializer.serialize_map(Some(self.intervals.len()))?;
+        let ordered_intervals: BTreeMap<_, _> = self.intervals.iter().collect();
+        for (local, interval) in ordered_intervals {
+            map.serialize_entry(&format!("{:?}", local), &interval.to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> IntervalState<'mir, 'tcx> {
+    /// The interval of possible values of `local`, if it is tracked and reachable.
+    pub fn get_interval(&self, local: mir::Local) -> Option<Interval> {
+        if !self.reachable || !self.tracked_locals.contains(&local) {
+            return None;
+        }
+        Some(self.local_interval(local))
+    }
+
+    fn local_interval(&self, local: mir::Local) -> Interval {
+        self.intervals
+            .get(&local)
+            .copied()
+            .unwrap_or_else(|| type_interval(self.tcx, self.mir, local))
+    }
+
+    /// Forgets everything that is known about `local`.
+    fn forget(&mut self, local: mir::Local) {
+        self.intervals.remove(&local);
+        self.conditions
+            .retain(|&bool_local, comparison| bool_local != local && !comparison.mentions(local));
+        self.copies
+            .retain(|&copy, original| copy != local && *original != local);
+    }
+
+    fn set_interval(&mut self, local: mir::Local, interval: Interval) {
+        self.forget(local);
+        if self.tracked_locals.contains(&local) {
+            self.update_interval(local, interval);
+        }
+    }
+
+    /// Stores the interval of `local`, unless it does not tell more than the type of `local`.
+    fn update_interval(&mut self, local: mir::Local, interval: Interval) {
+        let type_interval = type_interval(self.tcx, self.mir, local);
+        if type_interval.is_included_in(&interval) {
+            self.intervals.remove(&local);
+        } else {
+            self.intervals.insert(local, interval.meet(&type_interval));
+        }
+    }
+
+    fn set_bottom(&mut self) {
+        self.reachable = false;
+        self.intervals.clear();
+        self.conditions.clear();
+        self.copies.clear();
+    }
+
+    fn eval_constant(&self, constant: &mir::Constant<'tcx>) -> Option<i128> {
+        let ty = constant.ty();
+        let param_env = self.tcx.param_env(self.mir.source.def_id());
+        let bits = constant.literal.try_eval_bits(self.tcx, param_env, ty)?;
+        integer_value(self.tcx, bits, ty)
+    }
+
+    fn operand_term(&self, operand: &mir::Operand<'tcx>) -> Option<Term> {
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let local = place.as_local()?;
+                if !self.tracked_locals.contains(&local) {
+                    return None;
+                }
+                let original = self.copies.get(&local).copied().unwrap_or(local);
+                Some(Term::Local(original))
+            }
+            mir::Operand::Constant(constant) => self.eval_constant(constant).map(Term::Constant),
+        }
+    }
+
+    fn eval_operand(&self, operand: &mir::Operand<'tcx>) -> Interval {
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                if self.tracked_locals.contains(&place.local) {
+                    match place.projection[..] {
+                        [] => return self.local_interval(place.local),
+                        // The value of a checked operation
+                        [mir::ProjectionElem::Field(field, _)] if field.index() == 0 => {
+                            return self.local_interval(place.local)
+                        }
+                        _ => {}
+                    }
+                }
+                Interval::of_type(self.tcx, place.ty(self.mir, self.tcx).ty)
+            }
+            mir::Operand::Constant(constant) => self
+                .eval_constant(constant)
+                .map(Interval::constant)
+                .unwrap_or_else(|| Interval::of_type(self.tcx, constant.ty())),
+        }
+    }
+
+    fn eval_arithmetic(
+        &self,
+        op: mir::BinOp,
+        left: &mir::Operand<'tcx>,
+        right: &mir::Operand<'tcx>,
+    ) -> Interval {
+        let left = self.eval_operand(left);
+        let right = self.eval_operand(right);
+        match op {
+            mir::BinOp::Add => left.add(&right),
+            mir::BinOp::Sub => left.sub(&right),
+            mir::BinOp::Mul => left.mul(&right),
+            _ => Interval::top(),
+        }
+    }
+
+    /// Computes the interval of an rvalue assigned to a local of type `ty`.
+    fn eval_rvalue(&self, rvalue: &mir::Rvalue<'tcx>, ty: ty::Ty<'tcx>) -> Interval {
+        let type_interval = Interval::of_type(self.tcx, ty);
+        let result = match rvalue {
+            mir::Rvalue::Use(operand) => self.eval_operand(operand),
+            mir::Rvalue::BinaryOp(op, box (left, right)) => {
+                let result = self.eval_arithmetic(*op, left, right);
+                // The operation might wrap around
+                if result.is_included_in(&type_interval) {
+                    result
+                } else {
+                    type_interval
+                }
+            }
+            mir::Rvalue::CheckedBinaryOp(op, box (left, right)) => {
+                // The execution only continues if the operation does not overflow
+                let value_ty = match ty.kind() {
+                    ty::TyKind::Tuple(types) => types[0],
+                    _ => unreachable!(),
+                };
+                let value_interval = Interval::of_type(self.tcx, value_ty);
+                let result = self.eval_arithmetic(*op, left, right).meet(&value_interval);
+                if result.is_empty() {
+                    value_interval
+                } else {
+                    result
+                }
+            }
+            mir::Rvalue::UnaryOp(mir::UnOp::Neg, operand) => {
+                let result = self.eval_operand(operand).neg();
+                if result.is_included_in(&type_interval) {
+                    result
+                } else {
+                    type_interval
+                }
+            }
+            mir::Rvalue::Cast(mir::CastKind::IntToInt, operand, _) => {
+                let result = self.eval_operand(operand);
+                if result.is_included_in(&type_interval) {
+                    result
+                } else {
+                    type_interval
+                }
+            }
+            _ => type_interval,
+        };
+        result.meet(&type_interval)
+    }
+
+    /// Restricts the intervals of the terms of `comparison` assuming that it holds.
+    fn assume(&mut self, comparison: Comparison) {
+        let interval = |state: &Self, term: Term| match term {
+            Term::Local(local) => state.local_interval(local),
+            Term::Constant(value) => Interval::constant(value),
+        };
+        let left = interval(self, comparison.left);
+        let right = interval(self, comparison.right);
+        let one = Interval::constant(1);
+        let (new_left, new_right) = match comparison.op {
+            mir::BinOp::Lt => (
+                left.meet(&Interval::new(None, right.sub(&one).upper)),
+                right.meet(&Interval::new(left.add(&one).lower, None)),
+            ),
+            mir::BinOp::Le => (
+                left.meet(&Interval::new(None, right.upper)),
+                right.meet(&Interval::new(left.lower, None)),
+            ),
+            mir::BinOp::Gt => (
+                left.meet(&Interval::new(right.add(&one).lower, None)),
+                right.meet(&Interval::new(None, left.sub(&one).upper)),
+            ),
+            mir::BinOp::Ge => (
+                left.meet(&Interval::new(right.lower, None)),
+                right.meet(&Interval::new(None, left.upper)),
+            ),
+            mir::BinOp::Eq => (left.meet(&right), right.meet(&left)),
+            mir::BinOp::Ne => (exclude(left, right), exclude(right, left)),
+            _ => return,
+        };
+        for (term, new_interval) in [(comparison.left, new_left), (comparison.right, new_right)] {
+            if new_interval.is_empty() {
+                self.set_bottom();
+                return;
+            }
+            if let Term::Local(local) = term {
+                self.update_interval(local, new_interval);
+                // The copies of the local have the same value
+                let copies: Vec<_> = self
+                    .copies
+                    .iter()
+                    .filter(|&(_, &original)| original == local)
+                    .map(|(&copy, _)| copy)
+                    .collect();
+                for copy in copies {
+                    let copy_interval = self.local_interval(copy).meet(&new_interval);
+                    self.update_interval(copy, copy_interval);
+                }
+            }
+        }
+    }
+
+    /// Restricts the intervals assuming that `discr` has the value `value`, or any value
+    /// different from `excluded` if `value` is `None`.
+    fn assume_switch_value(
+        &mut self,
+        discr: &mir::Operand<'tcx>,
+        value: Option<u128>,
+        excluded: &[u128],
+    ) {
+        let local = match discr.place().and_then(|place| place.as_local()) {
+            Some(local) => local,
+            None => return,
+        };
+        let discr_ty = discr.ty(self.mir, self.tcx);
+        if discr_ty.is_bool() {
+            // A boolean is true if it is 1, or if it is not 0
+            let is_true = match value {
+                Some(value) => value != 0,
+                None => excluded.contains(&0),
+            };
+            if let Some(&comparison) = self.conditions.get(&local) {
+                let assumed = if is_true {
+                    Some(comparison)
+                } else {
+                    comparison.negate()
+                };
+                if let Some(assumed) = assumed {
+                    self.assume(assumed);
+                }
+            }
+        } else if let Some(value) = value.and_then(|bits| integer_value(self.tcx, bits, discr_ty)) {
+            if let Some(Term::Local(local)) = self.operand_term(discr) {
+                self.assume(Comparison {
+                    op: mir::BinOp::Eq,
+                    left: Term::Local(local),
+                    right: Term::Constant(value),
+                });
+            }
+        }
+    }
+
+    pub(super) fn apply_statement_effect(
+        &mut self,
+        location: mir::Location,
+    ) -> Result<(), AnalysisError> {
+        if !self.reachable {
+            return Ok(());
+        }
+        let stmt = &self.mir[location.block].statements[location.statement_index];
+        match stmt.kind {
+            mir::StatementKind::Assign(box (ref target, ref rvalue)) => {
+                self.apply_assignment(target, rvalue);
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                self.forget(local);
+            }
+            mir::StatementKind::SetDiscriminant { box ref place, .. }
+            | mir::StatementKind::Deinit(box ref place) => {
+                self.forget(place.local);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn apply_assignment(&mut self, target: &mir::Place<'tcx>, rvalue: &mir::Rvalue<'tcx>) {
+        let local = match target.as_local() {
+            Some(local) => local,
+            None => {
+                // Assigning to a projection of a local changes the local
+                self.forget(target.local);
+                return;
+            }
+        };
+        let ty = self.mir.local_decls[local].ty;
+        let interval = self.eval_rvalue(rvalue, ty);
+        let copied_local = match rvalue {
+            mir::Rvalue::Use(operand) => match self.operand_term(operand) {
+                Some(Term::Local(original)) if original != local => Some(original),
+                _ => None,
+            },
+            _ => None,
+        };
+        let comparison = match rvalue {
+            mir::Rvalue::BinaryOp(
+                op @ (mir::BinOp::Lt
+                | mir::BinOp::Le
+                | mir::BinOp::Gt
+                | mir::BinOp::Ge
+                | mir::BinOp::Eq
+                | mir::BinOp::Ne),
+                box (left, right),
+            ) => self
+                .operand_term(left)
+                .zip(self.operand_term(right))
+                .map(|(left, right)| Comparison {
+                    op: *op,
+                    left,
+                    right,
+                }),
+            _ => None,
+        };
+        self.set_interval(local, interval);
+        if let Some(original) = copied_local {
+            self.copies.insert(local, original);
+        }
+        if let Some(comparison) = comparison {
+            if !comparison.mentions(local) && self.tracked_locals.contains(&local) {
+                self.conditions.insert(local, comparison);
+            }
+        }
+    }
+
+    pub(super) fn apply_terminator_effect(
+        &self,
+        location: mir::Location,
+    ) -> Result<Vec<(mir::BasicBlock, Self)>, AnalysisError> {
+        let terminator = self.mir[location.block].terminator();
+        if !self.reachable {
+            return Ok(terminator
+                .successors()
+                .map(|bb| (bb, self.clone()))
+                .collect());
+        }
+        let mut res_vec = Vec::new();
+        match terminator.kind {
+            mir::TerminatorKind::SwitchInt {
+                ref discr,
+                ref targets,
+                ..
+            } => {
+                let values: Vec<_> = targets.iter().map(|(value, _)| value).collect();
+                for (value, bb) in targets.iter() {
+                    let mut new_state = self.clone();
+                    new_state.assume_switch_value(discr, Some(value), &values);
+                    res_vec.push((bb, new_state));
+                }
+                let mut otherwise_state = self.clone();
+                otherwise_state.assume_switch_value(discr, None, &values);
+                res_vec.push((targets.otherwise(), otherwise_state));
+            }
+            mir::TerminatorKind::Assert {
+                ref cond,
+                expected,
+                target,
+                cleanup,
+                ..
+            } => {
+                let mut new_state = self.clone();
+                new_state.assume_switch_value(cond, Some(expected as u128), &[]);
+                res_vec.push((target, new_state));
+                if let Some(bb) = cleanup {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+            mir::TerminatorKind::DropAndReplace {
+                place,
+                ref value,
+                target,
+                unwind,
+            } => {
+                let mut new_state = self.clone();
+                new_state.apply_assignment(&place, &mir::Rvalue::Use(value.clone()));
+                res_vec.push((target, new_state));
+                if let Some(bb) = unwind {
+                    let mut unwind_state = self.clone();
+                    unwind_state.forget(place.local);
+                    res_vec.push((bb, unwind_state));
+                }
+            }
+            mir::TerminatorKind::Call {
+                ref func,
+                destination,
+                target,
+                cleanup,
+                ..
+            } => {
+                // The result of the call can be any value of its type, unless the callee has a
+                // summary
+                let mut new_state = self.clone();
+                new_state.forget(destination.local);
+                if let Some(bb) = target {
+                    let mut target_state = new_state.clone();
+                    let summary = resolve_callee(self.tcx, self.mir.source.def_id(), func)
+                        .and_then(|callee| self.summaries.get(callee));
+                    match summary.map(|summary| summary.return_interval) {
+                        Some(None) => target_state.set_bottom(),
+                        Some(Some(interval)) => {
+                            if let Some(local) = destination.as_local() {
+                                target_state.set_interval(local, interval);
+                            }
+                        }
+                        None => {}
+                    }
+                    res_vec.push((bb, target_state));
+                }
+                if let Some(bb) = cleanup {
+                    res_vec.push((bb, new_state));
+                }
+            }
+            mir::TerminatorKind::Yield {
+                resume_arg,
+                resume,
+                drop,
+                ..
+            } => {
+                let mut new_state = self.clone();
+                new_state.forget(resume_arg.local);
+                res_vec.push((resume, new_state));
+                if let Some(bb) = drop {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+            mir::TerminatorKind::InlineAsm { .. } => {
+                return Err(AnalysisError::UnsupportedStatement(location));
+            }
+            _ => {
+                // No effect on integer locals
+                for bb in terminator.successors() {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+        }
+        Ok(res_vec)
+    }
+}
+
+/// The interval of all values of the type of `local`. For the result of a checked operation,
+/// this is the interval of the type of the result value.
+fn type_interval<'tcx>(tcx: TyCtxt<'tcx>, mir: &mir::Body<'tcx>, local: mir::Local) -> Interval {
+    let ty = mir.local_decls[local].ty;
+    match ty.kind() {
+        ty::TyKind::Tuple(types) if !types.is_empty() => Interval::of_type(tcx, types[0]),
+        _ => Interval::of_type(tcx, ty),
+    }
+}
+
+/// Removes `excluded` from `interval` if it is a constant at one of its bounds.
+fn exclude(interval: Interval, excluded: Interval) -> Interval {
+    match excluded.lower {
+        Some(value) if excluded.upper == Some(value) => {
+            if interval.lower == Some(value) {
+                Interval::new(Some(value + 1), interval.upper)
+            } else if interval.upper == Some(value) {
+                Interval::new(interval.lower, Some(value - 1))
+            } else {
+                interval
+            }
+        }
+        _ => interval,
+    }
+}
+
+impl<'mir, 'tcx: 'mir> AbstractState for IntervalState<'mir, 'tcx> {
+    fn is_bottom(&self) -> bool {
+        !self.reachable
+    }
+
+    fn join(&mut self, other: &Self) {
+        if !other.reachable {
+            return;
+        }
+        if !self.reachable {
+            self.reachable = true;
+            self.intervals = other.intervals.clone();
+            self.conditions = other.conditions.clone();
+            self.copies = other.copies.clone();
+            return;
+        }
+        let (tcx, mir) = (self.tcx, self.mir);
+        self.intervals
+            .retain(|&local, interval| match other.intervals.get(&local) {
+                Some(other_interval) => {
+                    *interval = interval.join(other_interval);
+                    !type_interval(tcx, mir, local).is_included_in(interval)
+                }
+                None => false,
+            });
+        self.conditions
+            .retain(|local, comparison| other.conditions.get(local) == Some(comparison));
+        self.copies
+            .retain(|local, original| other.copies.get(local) == Some(original));
+    }
+
+    fn widen(&mut self, previous: &Self) {
+        self.join(previous);
+        if !previous.reachable {
+            return;
+        }
+        let (tcx, mir) = (self.tcx, self.mir);
+        let thresholds = self.thresholds.clone();
+        self.intervals.retain(|&local, interval| {
+            if let Some(previous_interval) = previous.intervals.get(&local) {
+                *interval = interval.widen(previous_interval, &thresholds);
+            }
+            let type_interval = type_interval(tcx, mir, local);
+            *interval = interval.meet(&type_interval);
+            !type_interval.is_included_in(interval)
+        });
+    }
+}
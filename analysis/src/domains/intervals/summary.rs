@@ -0,0 +1,66 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::{AnalysisResult, FixpointEngine},
+    domains::{Interval, IntervalAnalysis},
+    summaries::{Summaries, SummaryAnalysis},
+};
+use prusti_rustc_interface::{
+    middle::{mir, ty::TyCtxt},
+    span::def_id::DefId,
+};
+use std::rc::Rc;
+
+/// The effect of a call on the integer locals of the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IntervalSummary {
+    /// The interval of the values that the function can return, or `None` if the function
+    /// never returns. It is the top interval if the function does not return an integer.
+    pub return_interval: Option<Interval>,
+}
+
+/// Summarizes functions by running the interval analysis on their bodies.
+pub struct IntervalSummaryAnalysis<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> IntervalSummaryAnalysis<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        IntervalSummaryAnalysis { tcx }
+    }
+}
+
+impl<'tcx> SummaryAnalysis<'tcx> for IntervalSummaryAnalysis<'tcx> {
+    type Summary = IntervalSummary;
+
+    fn compute_summary(
+        &self,
+        def_id: DefId,
+        body: &mir::Body<'tcx>,
+        summaries: &Rc<Summaries<IntervalSummary>>,
+    ) -> AnalysisResult<IntervalSummary> {
+        let p_state =
+            IntervalAnalysis::new_with_summaries(self.tcx, def_id, body, summaries.clone())
+                .run_fwd_analysis()?;
+        let mut return_interval: Option<Interval> = None;
+        for (bb, block_data) in body.basic_blocks.iter_enumerated() {
+            if let mir::TerminatorKind::Return = block_data.terminator().kind {
+                let state = p_state.lookup_before(body.terminator_loc(bb));
+                if let Some(state) = state.filter(|state| state.reachable) {
+                    let interval = state
+                        .get_interval(mir::RETURN_PLACE)
+                        .unwrap_or_else(Interval::top);
+                    return_interval = Some(match return_interval {
+                        Some(previous) => previous.join(&interval),
+                        None => interval,
+                    });
+                }
+            }
+        }
+        Ok(IntervalSummary { return_interval })
+    }
+}
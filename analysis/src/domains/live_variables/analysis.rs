@@ -0,0 +1,70 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::{AnalysisResult, BackwardFixpointEngine},
+    domains::LiveVariablesState,
+};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::mir,
+    span::def_id::DefId,
+};
+
+/// A backward analysis that computes the locals whose current value might be read later.
+pub struct LiveVariablesAnalysis<'mir, 'tcx: 'mir> {
+    def_id: DefId,
+    mir: &'mir mir::Body<'tcx>,
+}
+
+impl<'mir, 'tcx: 'mir> LiveVariablesAnalysis<'mir, 'tcx> {
+    pub fn new(def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        LiveVariablesAnalysis { def_id, mir }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> BackwardFixpointEngine<'mir, 'tcx> for LiveVariablesAnalysis<'mir, 'tcx> {
+    type State = LiveVariablesState<'mir, 'tcx>;
+
+    fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    fn body(&self) -> &'mir mir::Body<'tcx> {
+        self.mir
+    }
+
+    /// The bottom element of the lattice contains no live locals
+    fn new_bottom(&self) -> Self::State {
+        LiveVariablesState {
+            live_locals: FxHashSet::default(),
+            mir: self.mir,
+        }
+    }
+
+    fn need_to_widen(_counter: u32) -> bool {
+        // the lattice of sets of locals has a finite height
+        false
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()> {
+        state.apply_statement_effect(location)
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        states_after: &FxHashMap<mir::BasicBlock, Self::State>,
+        location: mir::Location,
+    ) -> AnalysisResult<Self::State> {
+        let mut state = self.new_bottom();
+        state.apply_terminator_effect(states_after, location)?;
+        Ok(state)
+    }
+}
@@ -0,0 +1,148 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{abstract_interpretation::AbstractState, AnalysisError};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::mir::{
+        self,
+        visit::{MutatingUseContext, PlaceContext, Visitor},
+    },
+};
+use serde::{Serialize, Serializer};
+use std::{collections::BTreeSet, fmt};
+
+/// The set of locals whose current value might be read later in the execution.
+///
+/// A local is defined when it is completely overwritten, for example by an assignment to the
+/// local (not to one of its fields) or as the destination of a call. All other mentions of the
+/// local, including writes to its fields and drops, are uses.
+/// Reads through a reference do not keep the referenced local live.
+#[derive(Clone)]
+pub struct LiveVariablesState<'mir, 'tcx: 'mir> {
+    pub(super) live_locals: FxHashSet<mir::Local>,
+    pub(super) mir: &'mir mir::Body<'tcx>, // just for context
+}
+
+impl<'mir, 'tcx: 'mir> fmt::Debug for LiveVariablesState<'mir, 'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ignore mir
+        f.debug_struct("LiveVariablesState")
+            .field("live_locals", &self.live_locals)
+            .finish()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> PartialEq for LiveVariablesState<'mir, 'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignore the `mir` field.
+        self.live_locals == other.live_locals
+    }
+}
+impl<'mir, 'tcx: 'mir> Eq for LiveVariablesState<'mir, 'tcx> {}
+
+impl<'mir, 'tcx: 'mir> Serialize for LiveVariablesState<'mir, 'tcx> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let ordered_locals: BTreeSet<_> = self.live_locals.iter().collect();
+        serializer.collect_seq(ordered_locals.iter().map(|local| format!("{:?}", local)))
+    }
+}
+
+impl<'mir, 'tcx: 'mir> LiveVariablesState<'mir, 'tcx> {
+    pub fn get_live_locals(&self) -> &FxHashSet<mir::Local> {
+        &self.live_locals
+    }
+
+    pub fn is_live(&self, local: mir::Local) -> bool {
+        self.live_locals.contains(&local)
+    }
+
+    pub(super) fn apply_statement_effect(
+        &mut self,
+        location: mir::Location,
+    ) -> Result<(), AnalysisError> {
+        let stmt = &self.mir[location.block].statements[location.statement_index];
+        let mut def_use = DefUseCollector::default();
+        def_use.visit_statement(stmt, location);
+        for local in def_use.defs {
+            self.live_locals.remove(&local);
+        }
+        self.live_locals.extend(def_use.uses);
+        Ok(())
+    }
+
+    /// Computes the live locals before the terminator at `location` from the live locals at the
+    /// beginning of its successors, joining them into `self`.
+    pub(super) fn apply_terminator_effect(
+        &mut self,
+        states_after: &FxHashMap<mir::BasicBlock, Self>,
+        location: mir::Location,
+    ) -> Result<(), AnalysisError> {
+        let terminator = self.mir[location.block].terminator();
+        if let mir::TerminatorKind::InlineAsm { .. } = terminator.kind {
+            return Err(AnalysisError::UnsupportedStatement(location));
+        }
+        let mut def_use = DefUseCollector::default();
+        def_use.visit_terminator(terminator, location);
+        for (&bb, state_after) in states_after {
+            // The destination of a call or yield is only assigned when the execution resumes
+            let defined_on_edge = match terminator.kind {
+                mir::TerminatorKind::Call { target, .. } => target == Some(bb),
+                mir::TerminatorKind::Yield { resume, .. } => resume == bb,
+                _ => true,
+            };
+            let mut live_locals = state_after.live_locals.clone();
+            if defined_on_edge {
+                for local in &def_use.defs {
+                    live_locals.remove(local);
+                }
+            }
+            self.live_locals.extend(live_locals);
+        }
+        self.live_locals.extend(def_use.uses);
+        Ok(())
+    }
+}
+
+/// Collects the locals that are defined and used by a statement or terminator.
+#[derive(Default)]
+struct DefUseCollector {
+    defs: Vec<mir::Local>,
+    uses: Vec<mir::Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for DefUseCollector {
+    fn visit_local(&mut self, local: mir::Local, context: PlaceContext, _location: mir::Location) {
+        match context {
+            // The local is completely overwritten; assignments to a field of the local have
+            // the `Projection` context instead
+            PlaceContext::MutatingUse(
+                MutatingUseContext::Store
+                | MutatingUseContext::Deinit
+                | MutatingUseContext::Call
+                | MutatingUseContext::Yield
+                | MutatingUseContext::AsmOutput,
+            ) => self.defs.push(local),
+            PlaceContext::NonUse(_) => {}
+            _ => self.uses.push(local),
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> AbstractState for LiveVariablesState<'mir, 'tcx> {
+    fn is_bottom(&self) -> bool {
+        self.live_locals.is_empty()
+    }
+
+    fn join(&mut self, other: &Self) {
+        self.live_locals.extend(other.live_locals.iter().copied());
+    }
+
+    fn widen(&mut self, _previous: &Self) {
+        // the set of locals is finite => widening should not be needed
+        unimplemented!()
+    }
+}
@@ -8,12 +8,18 @@ mod definitely_accessible;
 mod definitely_allocated;
 mod definitely_initialized;
 mod framing;
+mod intervals;
+mod live_variables;
 mod maybe_borrowed;
+mod octagons;
 mod reaching_definitions;
 
 pub use definitely_accessible::*;
 pub use definitely_allocated::*;
 pub use definitely_initialized::*;
 pub use framing::*;
+pub use intervals::*;
+pub use live_variables::*;
 pub use maybe_borrowed::*;
+pub use octagons::*;
 pub use reaching_definitions::*;
@@ -0,0 +1,128 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::{AnalysisResult, FixpointEngine},
+    domains::{Interval, LocalsCollector, Octagon, OctagonState},
+};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::{mir, ty::TyCtxt},
+    span::def_id::DefId,
+};
+use std::{collections::BTreeSet, rc::Rc};
+
+/// An analysis that computes the relations `±x ± y <= c` between integer locals.
+pub struct OctagonAnalysis<'mir, 'tcx: 'mir> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    mir: &'mir mir::Body<'tcx>,
+    variables: Rc<Vec<mir::Local>>,
+    variable_indices: Rc<FxHashMap<mir::Local, usize>>,
+    boolean_locals: Rc<FxHashSet<mir::Local>>,
+    thresholds: Rc<BTreeSet<i128>>,
+}
+
+impl<'mir, 'tcx: 'mir> OctagonAnalysis<'mir, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        let collector = LocalsCollector::new(tcx, mir);
+        let untracked = |local: &mir::Local| collector.borrowed.contains(local);
+        let variables: Vec<_> = mir
+            .local_decls
+            .iter_enumerated()
+            .filter(|(local, decl)| {
+                (decl.ty.is_integral() || collector.checked_results.contains(local))
+                    && !untracked(local)
+            })
+            .map(|(local, _)| local)
+            .collect();
+        let boolean_locals = mir
+            .local_decls
+            .iter_enumerated()
+            .filter(|(local, decl)| decl.ty.is_bool() && !untracked(local))
+            .map(|(local, _)| local)
+            .collect();
+        let variable_indices = variables
+            .iter()
+            .enumerate()
+            .map(|(k, &local)| (local, k))
+            .collect();
+        OctagonAnalysis {
+            tcx,
+            def_id,
+            mir,
+            variables: Rc::new(variables),
+            variable_indices: Rc::new(variable_indices),
+            boolean_locals: Rc::new(boolean_locals),
+            thresholds: Rc::new(collector.thresholds),
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> FixpointEngine<'mir, 'tcx> for OctagonAnalysis<'mir, 'tcx> {
+    type State = OctagonState<'mir, 'tcx>;
+
+    fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    fn body(&self) -> &'mir mir::Body<'tcx> {
+        self.mir
+    }
+
+    /// The bottom element of the lattice represents an unreachable program point
+    fn new_bottom(&self) -> Self::State {
+        OctagonState {
+            reachable: false,
+            octagon: Octagon::top(self.variables.len()),
+            conditions: FxHashMap::default(),
+            variables: self.variables.clone(),
+            variable_indices: self.variable_indices.clone(),
+            boolean_locals: self.boolean_locals.clone(),
+            thresholds: self.thresholds.clone(),
+            mir: self.mir,
+            tcx: self.tcx,
+        }
+    }
+
+    /// The variables can have any value of their type
+    fn new_initial(&self) -> Self::State {
+        let mut octagon = Octagon::top(self.variables.len());
+        for (k, &local) in self.variables.iter().enumerate() {
+            let ty = self.mir.local_decls[local].ty;
+            if ty.is_integral() {
+                octagon.add_interval(k, Interval::of_type(self.tcx, ty));
+            }
+        }
+        octagon.close();
+        OctagonState {
+            reachable: true,
+            octagon,
+            ..self.new_bottom()
+        }
+    }
+
+    fn need_to_widen(counter: u32) -> bool {
+        // Give simple loops a chance to stabilize before widening
+        counter > 2
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()> {
+        state.apply_statement_effect(location)
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<Vec<(mir::BasicBlock, Self::State)>> {
+        state.apply_terminator_effect(location)
+    }
+}
@@ -0,0 +1,13 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+mod analysis;
+mod octagon;
+mod state;
+
+pub use self::analysis::*;
+pub use octagon::*;
+pub use state::*;
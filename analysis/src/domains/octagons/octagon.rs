@@ -0,0 +1,277 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::domains::Interval;
+use std::collections::BTreeSet;
+
+/// A conjunction of constraints `±x ± y <= c` over the integer variables `0..size`.
+///
+/// The constraints are stored in a difference bound matrix over the `2 * size` signed variables
+/// `v_2k = x_k` and `v_2k+1 = -x_k`: the entry `(i, j)` is an upper bound of `v_j - v_i`, or
+/// `None` if the difference is unbounded.
+/// Each constraint is stored twice, because `v_j - v_i == v_(i^1) - v_(j^1)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Octagon {
+    size: usize,
+    matrix: Vec<Option<i128>>,
+}
+
+/// The index of the signed variable with the opposite sign.
+fn bar(i: usize) -> usize {
+    i ^ 1
+}
+
+fn min_bound(a: Option<i128>, b: Option<i128>) -> Option<i128> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// The sum of two upper bounds. An overflow is approximated by an unbounded result.
+fn add_bounds(a: Option<i128>, b: Option<i128>) -> Option<i128> {
+    a.zip(b).and_then(|(a, b)| a.checked_add(b))
+}
+
+impl Octagon {
+    /// The octagon without constraints.
+    pub fn top(size: usize) -> Self {
+        let dim = 2 * size;
+        let mut matrix = vec![None; dim * dim];
+        for i in 0..dim {
+            matrix[i * dim + i] = Some(0);
+        }
+        Octagon { size, matrix }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn get(&self, i: usize, j: usize) -> Option<i128> {
+        self.matrix[i * 2 * self.size + j]
+    }
+
+    fn set(&mut self, i: usize, j: usize, bound: Option<i128>) {
+        let dim = 2 * self.size;
+        self.matrix[i * dim + j] = bound;
+        self.matrix[bar(j) * dim + bar(i)] = bound;
+    }
+
+    /// Adds the constraint `v_j - v_i <= bound`.
+    fn tighten(&mut self, i: usize, j: usize, bound: i128) {
+        let new_bound = min_bound(self.get(i, j), Some(bound));
+        self.set(i, j, new_bound);
+    }
+
+    /// Adds the constraint `x_k <= bound`.
+    pub fn add_upper_bound(&mut self, k: usize, bound: i128) {
+        match bound.checked_mul(2) {
+            Some(double) => self.tighten(2 * k + 1, 2 * k, double),
+            // The bound is weaker than any bound that can be represented
+            None if bound > 0 => {}
+            None => self.tighten(2 * k + 1, 2 * k, i128::MIN),
+        }
+    }
+
+    /// Adds the constraint `x_k >= bound`.
+    pub fn add_lower_bound(&mut self, k: usize, bound: i128) {
+        match bound.checked_mul(-2) {
+            Some(double) => self.tighten(2 * k, 2 * k + 1, double),
+            None if bound < 0 => {}
+            None => self.tighten(2 * k, 2 * k + 1, i128::MIN),
+        }
+    }
+
+    /// Adds the constraints `lower <= x_k <= upper` of an interval.
+    pub fn add_interval(&mut self, k: usize, interval: Interval) {
+        if let Some(lower) = interval.lower {
+            self.add_lower_bound(k, lower);
+        }
+        if let Some(upper) = interval.upper {
+            self.add_upper_bound(k, upper);
+        }
+    }
+
+    /// Adds the constraint `x_k - x_l <= bound`.
+    pub fn add_difference_bound(&mut self, k: usize, l: usize, bound: i128) {
+        self.tighten(2 * l, 2 * k, bound);
+    }
+
+    /// Adds the constraint `x_k + x_l <= bound`.
+    pub fn add_sum_bound(&mut self, k: usize, l: usize, bound: i128) {
+        self.tighten(2 * l + 1, 2 * k, bound);
+    }
+
+    /// Adds the constraint `-x_k - x_l <= bound`.
+    pub fn add_negated_sum_bound(&mut self, k: usize, l: usize, bound: i128) {
+        self.tighten(2 * l, 2 * k + 1, bound);
+    }
+
+    /// The interval of possible values of `x_k`. The octagon should be closed.
+    pub fn interval(&self, k: usize) -> Interval {
+        Interval::new(
+            self.get(2 * k, 2 * k + 1)
+                .and_then(|bound| bound.div_euclid(2).checked_neg()),
+            self.get(2 * k + 1, 2 * k).map(|bound| bound.div_euclid(2)),
+        )
+    }
+
+    /// The interval of possible values of `x_k - x_l`. The octagon should be closed.
+    pub fn difference(&self, k: usize, l: usize) -> Interval {
+        Interval::new(
+            self.get(2 * k, 2 * l).and_then(i128::checked_neg),
+            self.get(2 * l, 2 * k),
+        )
+    }
+
+    /// The interval of possible values of `x_k + x_l`. The octagon should be closed.
+    pub fn sum(&self, k: usize, l: usize) -> Interval {
+        Interval::new(
+            self.get(2 * l, 2 * k + 1).and_then(i128::checked_neg),
+            self.get(2 * l + 1, 2 * k),
+        )
+    }
+
+    /// Removes all constraints that mention `x_k`.
+    pub fn forget(&mut self, k: usize) {
+        let dim = 2 * self.size;
+        for i in [2 * k, 2 * k + 1] {
+            for j in 0..dim {
+                self.matrix[i * dim + j] = None;
+                self.matrix[j * dim + i] = None;
+            }
+            self.matrix[i * dim + i] = Some(0);
+        }
+    }
+
+    /// Models the assignment `x_k := x_l + constant`.
+    /// If `k != l`, the octagon needs to be closed afterwards.
+    pub fn assign_sum(&mut self, k: usize, l: usize, constant: i128) {
+        if k == l {
+            // Shift all the constraints of `x_k`, which keeps the octagon closed
+            let dim = 2 * self.size;
+            for i in 0..dim {
+                for j in 0..dim {
+                    let mut shift = 0;
+                    for (index, sign) in [(j, 1), (i, -1)] {
+                        if index == 2 * k {
+                            shift += sign;
+                        } else if index == 2 * k + 1 {
+                            shift -= sign;
+                        }
+                    }
+                    if shift != 0 {
+                        let entry = &mut self.matrix[i * dim + j];
+                        *entry = entry
+                            .zip(constant.checked_mul(shift))
+                            .and_then(|(bound, delta)| bound.checked_add(delta));
+                    }
+                }
+            }
+        } else {
+            self.forget(k);
+            self.add_difference_bound(k, l, constant);
+            if let Some(negated) = constant.checked_neg() {
+                self.add_difference_bound(l, k, negated);
+            }
+        }
+    }
+
+    /// Computes the tightest representation of the constraints over the integers, by deriving
+    /// all the constraints that are implied by combining two constraints.
+    /// Returns `false` iff the constraints are unsatisfiable.
+    pub fn close(&mut self) -> bool {
+        let dim = 2 * self.size;
+        // Shortest paths
+        for k in 0..dim {
+            for i in 0..dim {
+                let ik = self.matrix[i * dim + k];
+                if ik.is_none() {
+                    continue;
+                }
+                for j in 0..dim {
+                    let path = add_bounds(ik, self.matrix[k * dim + j]);
+                    let entry = &mut self.matrix[i * dim + j];
+                    *entry = min_bound(*entry, path);
+                }
+            }
+        }
+        // Bounds on `2 * x` of integer variables are even
+        for i in 0..dim {
+            let entry = &mut self.matrix[i * dim + bar(i)];
+            *entry = entry.map(|bound| bound.div_euclid(2) * 2);
+        }
+        // Combine the bounds of `v_i` and `v_j` into a bound of their difference
+        for i in 0..dim {
+            for j in 0..dim {
+                let combined =
+                    add_bounds(self.matrix[i * dim + bar(i)], self.matrix[bar(j) * dim + j])
+                        .map(|bound| bound.div_euclid(2));
+                let entry = &mut self.matrix[i * dim + j];
+                *entry = min_bound(*entry, combined);
+            }
+        }
+        for i in 0..dim {
+            let entry = &mut self.matrix[i * dim + i];
+            if matches!(*entry, Some(bound) if bound < 0) {
+                return false;
+            }
+            *entry = Some(0);
+        }
+        true
+    }
+
+    /// The smallest octagon that contains both `self` and `other`, which should be closed.
+    pub fn join(&self, other: &Self) -> Self {
+        debug_assert_eq!(self.size, other.size);
+        let matrix = self
+            .matrix
+            .iter()
+            .zip(&other.matrix)
+            .map(|(&a, &b)| a.zip(b).map(|(a, b)| a.max(b)))
+            .collect();
+        Octagon {
+            size: self.size,
+            matrix,
+        }
+    }
+
+    /// Moves each bound that grew with respect to `previous` to the next threshold, or drops it
+    /// if there is none. Bounds on `2 * x` use the doubled thresholds.
+    ///
+    /// The result should not be closed before it is widened again, otherwise the widening might
+    /// not terminate.
+    pub fn widen(&self, previous: &Self, thresholds: &BTreeSet<i128>) -> Self {
+        debug_assert_eq!(self.size, previous.size);
+        let dim = 2 * self.size;
+        let mut matrix = previous.matrix.clone();
+        for i in 0..dim {
+            for j in 0..dim {
+                let index = i * dim + j;
+                matrix[index] = match (self.matrix[index], matrix[index]) {
+                    (Some(bound), Some(previous_bound)) if bound > previous_bound => {
+                        if j == bar(i) {
+                            thresholds
+                                .iter()
+                                .filter_map(|threshold| threshold.checked_mul(2))
+                                .find(|&threshold| threshold >= bound)
+                        } else {
+                            thresholds.range(bound..).next().copied()
+                        }
+                    }
+                    (Some(_), previous_bound) => previous_bound,
+                    (None, _) => None,
+                };
+            }
+        }
+        Octagon {
+            size: self.size,
+            matrix,
+        }
+    }
+}
@@ -0,0 +1,665 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::AbstractState,
+    domains::{integer_value, Comparison, Interval, Octagon, Term},
+    AnalysisError,
+};
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    middle::{
+        mir,
+        ty::{self, TyCtxt},
+    },
+};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{collections::BTreeSet, fmt, rc::Rc};
+
+/// The relations `±x ± y <= c` between the integer locals that are tracked by the analysis.
+///
+/// As in the `IntervalState`, the comparisons stored in boolean locals are remembered, to refine
+/// the octagon on the branches of a `SwitchInt` or `Assert` that uses them.
+#[derive(Clone)]
+pub struct OctagonState<'mir, 'tcx: 'mir> {
+    /// `false` iff the state is the bottom element, i.e. the program point is unreachable.
+    pub(super) reachable: bool,
+    /// The octagon over the variables `0..variables.len()`. It is closed, unless the state is
+    /// the result of a widening.
+    pub(super) octagon: Octagon,
+    /// Boolean locals that hold the result of a comparison
+    pub(super) conditions: FxHashMap<mir::Local, Comparison>,
+    /// The integer locals (and results of checked operations) whose address is never taken,
+    /// indexed by their variable in the octagon
+    pub(super) variables: Rc<Vec<mir::Local>>,
+    pub(super) variable_indices: Rc<FxHashMap<mir::Local, usize>>,
+    /// Boolean locals whose address is never taken
+    pub(super) boolean_locals: Rc<FxHashSet<mir::Local>>,
+    /// Bounds to which the octagon is widened, before giving up on a bound
+    pub(super) thresholds: Rc<BTreeSet<i128>>,
+    pub(super) mir: &'mir mir::Body<'tcx>,
+    pub(super) tcx: TyCtxt<'tcx>,
+}
+
+/// A linear expression `variable + constant`, or just a constant.
+#[derive(Clone, Copy, Debug)]
+struct LinearExpr {
+    variable: Option<usize>,
+    constant: i128,
+}
+
+impl<'mir, 'tcx: 'mir> fmt::Debug for OctagonState<'mir, 'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ignore tcx & mir
+        f.debug_struct("OctagonState")
+            .field("reachable", &self.reachable)
+            .field("octagon", &self.octagon)
+            .field("conditions", &self.conditions)
+            .finish()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> PartialEq for OctagonState<'mir, 'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignore the `mir` field and the fields shared by all states.
+        self.reachable == other.reachable
+            && (!self.reachable
+                || (self.octagon == other.octagon && self.conditions == other.conditions))
+    }
+}
+impl<'mir, 'tcx: 'mir> Eq for OctagonState<'mir, 'tcx> {}
+
+impl<'mir, 'tcx: 'mir> Serialize for OctagonState<'mir, 'tcx> {
+    /// Serializes the interval of each variable, and the bounds of the sums and differences of
+    /// two variables that do not follow from their intervals. Intervals that follow from the
+    /// type of the variable are omitted.
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        if !self.reachable {
+            return serializer.serialize_str("unreachable");
+        }
+        let mut closed = self.octagon.clone();
+        closed.close();
+        let mut entries = vec![];
+        let mut ordered_variables: Vec<_> = self.variables.iter().copied().enumerate().collect();
+        ordered_variables.sort_by_key(|&(_, local)| local);
+        for (position, &(k, local)) in ordered_variables.iter().enumerate() {
+            let interval = closed.interval(k);
+            if !self.type_interval(local).is_included_in(&interval) {
+                entries.push((format!("{:?}", local), interval));
+            }
+            for &(l, other_local) in &ordered_variables[position + 1..] {
+                let other_interval = closed.interval(l);
+                let difference = closed.difference(k, l);
+                if !interval.sub(&other_interval).is_included_in(&difference) {
+                    entries.push((format!("{:?} - {:?}", local, other_local), difference));
+                }
+                let sum = closed.sum(k, l);
+                if !interval.add(&other_interval).is_included_in(&sum) {
+                    entries.push((format!("{:?} + {:?}", local, other_local), sum));
+                }
+            }
+        }
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, interval) in entries {
+            map.serialize_entry(&key, &interval.to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> OctagonState<'mir, 'tcx> {
+    /// The interval of possible values of `local`, if it is tracked and reachable.
+    pub fn get_interval(&self, local: mir::Local) -> Option<Interval> {
+        let &k = self.variable_indices.get(&local)?;
+        if !self.reachable {
+            return None;
+        }
+        let mut closed = self.octagon.clone();
+        closed.close();
+        Some(closed.interval(k))
+    }
+
+    /// The interval of possible values of `left - right`, if both locals are tracked and the
+    /// state is reachable.
+    pub fn get_difference(&self, left: mir::Local, right: mir::Local) -> Option<Interval> {
+        let &k = self.variable_indices.get(&left)?;
+        let &l = self.variable_indices.get(&right)?;
+        if !self.reachable {
+            return None;
+        }
+        let mut closed = self.octagon.clone();
+        closed.close();
+        Some(closed.difference(k, l))
+    }
+
+    fn type_interval(&self, local: mir::Local) -> Interval {
+        let ty = self.mir.local_decls[local].ty;
+        match ty.kind() {
+            ty::TyKind::Tuple(types) if !types.is_empty() => Interval::of_type(self.tcx, types[0]),
+            _ => Interval::of_type(self.tcx, ty),
+        }
+    }
+
+    /// Closes the octagon, and marks the state as unreachable if it is empty.
+    fn close(&mut self) {
+        if !self.octagon.close() {
+            self.set_bottom();
+        }
+    }
+
+    fn set_bottom(&mut self) {
+        self.reachable = false;
+        self.octagon = Octagon::top(self.variables.len());
+        self.conditions.clear();
+    }
+
+    /// Forgets everything that is known about `local`, except the range of its type.
+    /// The comparisons that mention `local` are kept if another local has the same value.
+    fn forget(&mut self, local: mir::Local) {
+        let replacement = self.equal_variable(local);
+        self.conditions.retain(|&bool_local, comparison| {
+            if bool_local == local {
+                return false;
+            }
+            if let Some(replacement) = replacement {
+                for term in [&mut comparison.left, &mut comparison.right] {
+                    if *term == Term::Local(local) {
+                        *term = Term::Local(replacement);
+                    }
+                }
+            }
+            !comparison.mentions(local)
+        });
+        if let Some(&k) = self.variable_indices.get(&local) {
+            self.octagon.forget(k);
+            self.octagon.add_interval(k, self.type_interval(local));
+        }
+    }
+
+    /// Another local that is known to have the same value as `local`.
+    fn equal_variable(&self, local: mir::Local) -> Option<mir::Local> {
+        let &k = self.variable_indices.get(&local)?;
+        let l = (0..self.variables.len())
+            .find(|&l| l != k && self.octagon.difference(k, l) == Interval::constant(0))?;
+        Some(self.variables[l])
+    }
+
+    fn eval_constant(&self, constant: &mir::Constant<'tcx>) -> Option<i128> {
+        let ty = constant.ty();
+        let param_env = self.tcx.param_env(self.mir.source.def_id());
+        let bits = constant.literal.try_eval_bits(self.tcx, param_env, ty)?;
+        integer_value(self.tcx, bits, ty)
+    }
+
+    /// The variable that stores the value of `operand`. For the result of a checked operation,
+    /// this is its first field.
+    fn operand_variable(&self, operand: &mir::Operand<'tcx>) -> Option<usize> {
+        let place = operand.place()?;
+        let &k = self.variable_indices.get(&place.local)?;
+        let is_checked_result = !self.mir.local_decls[place.local].ty.is_integral();
+        match place.projection[..] {
+            [] if !is_checked_result => Some(k),
+            [mir::ProjectionElem::Field(field, _)] if is_checked_result && field.index() == 0 => {
+                Some(k)
+            }
+            _ => None,
+        }
+    }
+
+    fn operand_term(&self, operand: &mir::Operand<'tcx>) -> Option<Term> {
+        match operand {
+            mir::Operand::Copy(_) | mir::Operand::Move(_) => self
+                .operand_variable(operand)
+                .map(|k| Term::Local(self.variables[k])),
+            mir::Operand::Constant(constant) => self.eval_constant(constant).map(Term::Constant),
+        }
+    }
+
+    fn eval_operand(&self, operand: &mir::Operand<'tcx>) -> Interval {
+        if let Some(k) = self.operand_variable(operand) {
+            return self.octagon.interval(k);
+        }
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                Interval::of_type(self.tcx, place.ty(self.mir, self.tcx).ty)
+            }
+            mir::Operand::Constant(constant) => self
+                .eval_constant(constant)
+                .map(Interval::constant)
+                .unwrap_or_else(|| Interval::of_type(self.tcx, constant.ty())),
+        }
+    }
+
+    fn linear_operand(&self, operand: &mir::Operand<'tcx>) -> Option<LinearExpr> {
+        if let Some(k) = self.operand_variable(operand) {
+            return Some(LinearExpr {
+                variable: Some(k),
+                constant: 0,
+            });
+        }
+        match operand {
+            mir::Operand::Constant(constant) => {
+                self.eval_constant(constant).map(|constant| LinearExpr {
+                    variable: None,
+                    constant,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Expresses the result of `left op right` as a `LinearExpr`, if possible.
+    fn linear_binary_op(
+        &self,
+        op: mir::BinOp,
+        left: &mir::Operand<'tcx>,
+        right: &mir::Operand<'tcx>,
+    ) -> Option<LinearExpr> {
+        let left = self.linear_operand(left)?;
+        let right = self.linear_operand(right)?;
+        match (op, left.variable, right.variable) {
+            (mir::BinOp::Add, _, None) | (mir::BinOp::Add, None, _) => Some(LinearExpr {
+                variable: left.variable.or(right.variable),
+                constant: left.constant.checked_add(right.constant)?,
+            }),
+            (mir::BinOp::Sub, _, None) => Some(LinearExpr {
+                variable: left.variable,
+                constant: left.constant.checked_sub(right.constant)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn eval_arithmetic(
+        &self,
+        op: mir::BinOp,
+        left: &mir::Operand<'tcx>,
+        right: &mir::Operand<'tcx>,
+    ) -> Interval {
+        let left = self.eval_operand(left);
+        let right = self.eval_operand(right);
+        match op {
+            mir::BinOp::Add => left.add(&right),
+            mir::BinOp::Sub => left.sub(&right),
+            mir::BinOp::Mul => left.mul(&right),
+            _ => Interval::top(),
+        }
+    }
+
+    /// Models the assignment of `rvalue` to the variable `k`, which stores a value of type `ty`.
+    fn assign_variable(&mut self, k: usize, rvalue: &mir::Rvalue<'tcx>, ty: ty::Ty<'tcx>) {
+        let type_interval = Interval::of_type(self.tcx, ty);
+        // The result as a linear expression, which is only used if it cannot wrap around
+        let (linear, interval) = match rvalue {
+            mir::Rvalue::Use(operand) => (self.linear_operand(operand), self.eval_operand(operand)),
+            mir::Rvalue::BinaryOp(op, box (left, right)) => {
+                let interval = self.eval_arithmetic(*op, left, right);
+                if interval.is_included_in(&type_interval) {
+                    (self.linear_binary_op(*op, left, right), interval)
+                } else {
+                    (None, type_interval)
+                }
+            }
+            // The execution only continues if the operation does not overflow
+            mir::Rvalue::CheckedBinaryOp(op, box (left, right)) => (
+                self.linear_binary_op(*op, left, right),
+                self.eval_arithmetic(*op, left, right),
+            ),
+            mir::Rvalue::UnaryOp(mir::UnOp::Neg, operand) => {
+                let interval = self.eval_operand(operand).neg();
+                if interval.is_included_in(&type_interval) {
+                    (None, interval)
+                } else {
+                    (None, type_interval)
+                }
+            }
+            mir::Rvalue::Cast(mir::CastKind::IntToInt, operand, _) => {
+                let interval = self.eval_operand(operand);
+                if interval.is_included_in(&type_interval) {
+                    (self.linear_operand(operand), interval)
+                } else {
+                    (None, type_interval)
+                }
+            }
+            _ => (None, type_interval),
+        };
+        if let Some(LinearExpr {
+            variable: Some(l),
+            constant,
+        }) = linear
+        {
+            let mut octagon = self.octagon.clone();
+            octagon.assign_sum(k, l, constant);
+            octagon.add_interval(k, type_interval);
+            if octagon.close() {
+                self.octagon = octagon;
+                return;
+            }
+            // The checked operation always overflows
+        }
+        self.octagon.forget(k);
+        let interval = interval.meet(&type_interval);
+        self.octagon.add_interval(
+            k,
+            if interval.is_empty() {
+                type_interval
+            } else {
+                interval
+            },
+        );
+        self.close();
+    }
+
+    /// Restricts the octagon assuming that `left - right <= bound`.
+    fn assume_difference(&mut self, left: Term, right: Term, bound: i128) {
+        match (left, right) {
+            (Term::Local(left), Term::Local(right)) => {
+                let k = self.variable_indices[&left];
+                let l = self.variable_indices[&right];
+                self.octagon.add_difference_bound(k, l, bound);
+            }
+            (Term::Local(left), Term::Constant(value)) => match value.checked_add(bound) {
+                Some(upper) => self
+                    .octagon
+                    .add_upper_bound(self.variable_indices[&left], upper),
+                None if bound < 0 => self.set_bottom(),
+                None => {}
+            },
+            (Term::Constant(value), Term::Local(right)) => match value.checked_sub(bound) {
+                Some(lower) => self
+                    .octagon
+                    .add_lower_bound(self.variable_indices[&right], lower),
+                None if bound > 0 => self.set_bottom(),
+                None => {}
+            },
+            (Term::Constant(left), Term::Constant(right)) => {
+                if matches!(left.checked_sub(right), Some(difference) if difference > bound) {
+                    self.set_bottom();
+                }
+            }
+        }
+    }
+
+    /// Restricts the octagon assuming that `comparison` holds.
+    fn assume(&mut self, comparison: Comparison) {
+        let Comparison { op, left, right } = comparison;
+        match op {
+            mir::BinOp::Lt => self.assume_difference(left, right, -1),
+            mir::BinOp::Le => self.assume_difference(left, right, 0),
+            mir::BinOp::Gt => self.assume_difference(right, left, -1),
+            mir::BinOp::Ge => self.assume_difference(right, left, 0),
+            mir::BinOp::Eq => {
+                self.assume_difference(left, right, 0);
+                self.assume_difference(right, left, 0);
+            }
+            mir::BinOp::Ne => {
+                // Only a constant at the bound of the interval of a local can be excluded
+                let (local, value) = match (left, right) {
+                    (Term::Local(local), Term::Constant(value))
+                    | (Term::Constant(value), Term::Local(local)) => (local, value),
+                    _ => return,
+                };
+                let k = self.variable_indices[&local];
+                let interval = self.octagon.interval(k);
+                if interval.lower == Some(value) {
+                    self.octagon.add_lower_bound(k, value + 1);
+                } else if interval.upper == Some(value) {
+                    self.octagon.add_upper_bound(k, value - 1);
+                }
+            }
+            _ => return,
+        }
+        if self.reachable {
+            self.close();
+        }
+    }
+
+    /// Restricts the octagon assuming that `discr` has the value `value`, or any value
+    /// different from `excluded` if `value` is `None`.
+    fn assume_switch_value(
+        &mut self,
+        discr: &mir::Operand<'tcx>,
+        value: Option<u128>,
+        excluded: &[u128],
+    ) {
+        let discr_ty = discr.ty(self.mir, self.tcx);
+        if discr_ty.is_bool() {
+            let local = match discr.place().and_then(|place| place.as_local()) {
+                Some(local) => local,
+                None => return,
+            };
+            // A boolean is true if it is 1, or if it is not 0
+            let is_true = match value {
+                Some(value) => value != 0,
+                None => excluded.contains(&0),
+            };
+            if let Some(&comparison) = self.conditions.get(&local) {
+                let assumed = if is_true {
+                    Some(comparison)
+                } else {
+                    comparison.negate()
+                };
+                if let Some(assumed) = assumed {
+                    self.assume(assumed);
+                }
+            }
+        } else if let Some(value) = value.and_then(|bits| integer_value(self.tcx, bits, discr_ty)) {
+            if let Some(Term::Local(local)) = self.operand_term(discr) {
+                self.assume(Comparison {
+                    op: mir::BinOp::Eq,
+                    left: Term::Local(local),
+                    right: Term::Constant(value),
+                });
+            }
+        }
+    }
+
+    pub(super) fn apply_statement_effect(
+        &mut self,
+        location: mir::Location,
+    ) -> Result<(), AnalysisError> {
+        if !self.reachable {
+            return Ok(());
+        }
+        let stmt = &self.mir[location.block].statements[location.statement_index];
+        match stmt.kind {
+            mir::StatementKind::Assign(box (ref target, ref rvalue)) => {
+                self.apply_assignment(target, rvalue);
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                self.forget(local);
+                self.close();
+            }
+            mir::StatementKind::SetDiscriminant { box ref place, .. }
+            | mir::StatementKind::Deinit(box ref place) => {
+                self.forget(place.local);
+                self.close();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn apply_assignment(&mut self, target: &mir::Place<'tcx>, rvalue: &mir::Rvalue<'tcx>) {
+        let local = match target.as_local() {
+            Some(local) => local,
+            None => {
+                // Assigning to a projection of a local changes the local
+                self.forget(target.local);
+                self.close();
+                return;
+            }
+        };
+        let comparison = match rvalue {
+            mir::Rvalue::BinaryOp(
+                op @ (mir::BinOp::Lt
+                | mir::BinOp::Le
+                | mir::BinOp::Gt
+                | mir::BinOp::Ge
+                | mir::BinOp::Eq
+                | mir::BinOp::Ne),
+                box (left, right),
+            ) => self
+                .operand_term(left)
+                .zip(self.operand_term(right))
+                .map(|(left, right)| Comparison {
+                    op: *op,
+                    left,
+                    right,
+                }),
+            _ => None,
+        };
+        self.conditions
+            .retain(|&bool_local, comparison| bool_local != local && !comparison.mentions(local));
+        if let Some(&k) = self.variable_indices.get(&local) {
+            let ty = match self.mir.local_decls[local].ty.kind() {
+                ty::TyKind::Tuple(types) => types[0],
+                _ => self.mir.local_decls[local].ty,
+            };
+            self.assign_variable(k, rvalue, ty);
+        }
+        if let Some(comparison) = comparison {
+            if !comparison.mentions(local) && self.boolean_locals.contains(&local) {
+                self.conditions.insert(local, comparison);
+            }
+        }
+    }
+
+    pub(super) fn apply_terminator_effect(
+        &self,
+        location: mir::Location,
+    ) -> Result<Vec<(mir::BasicBlock, Self)>, AnalysisError> {
+        let terminator = self.mir[location.block].terminator();
+        if !self.reachable {
+            return Ok(terminator
+                .successors()
+                .map(|bb| (bb, self.clone()))
+                .collect());
+        }
+        let mut res_vec = Vec::new();
+        match terminator.kind {
+            mir::TerminatorKind::SwitchInt {
+                ref discr,
+                ref targets,
+                ..
+            } => {
+                let values: Vec<_> = targets.iter().map(|(value, _)| value).collect();
+                for (value, bb) in targets.iter() {
+                    let mut new_state = self.clone();
+                    new_state.assume_switch_value(discr, Some(value), &values);
+                    res_vec.push((bb, new_state));
+                }
+                let mut otherwise_state = self.clone();
+                otherwise_state.assume_switch_value(discr, None, &values);
+                res_vec.push((targets.otherwise(), otherwise_state));
+            }
+            mir::TerminatorKind::Assert {
+                ref cond,
+                expected,
+                target,
+                cleanup,
+                ..
+            } => {
+                let mut new_state = self.clone();
+                new_state.assume_switch_value(cond, Some(expected as u128), &[]);
+                res_vec.push((target, new_state));
+                if let Some(bb) = cleanup {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+            mir::TerminatorKind::DropAndReplace {
+                place,
+                ref value,
+                target,
+                unwind,
+            } => {
+                let mut new_state = self.clone();
+                new_state.apply_assignment(&place, &mir::Rvalue::Use(value.clone()));
+                res_vec.push((target, new_state));
+                if let Some(bb) = unwind {
+                    let mut unwind_state = self.clone();
+                    unwind_state.forget(place.local);
+                    unwind_state.close();
+                    res_vec.push((bb, unwind_state));
+                }
+            }
+            mir::TerminatorKind::Call {
+                destination,
+                target,
+                cleanup,
+                ..
+            } => {
+                // The result of the call can be any value of its type
+                let mut new_state = self.clone();
+                new_state.forget(destination.local);
+                new_state.close();
+                if let Some(bb) = target {
+                    res_vec.push((bb, new_state.clone()));
+                }
+                if let Some(bb) = cleanup {
+                    res_vec.push((bb, new_state));
+                }
+            }
+            mir::TerminatorKind::Yield {
+                resume_arg,
+                resume,
+                drop,
+                ..
+            } => {
+                let mut new_state = self.clone();
+                new_state.forget(resume_arg.local);
+                new_state.close();
+                res_vec.push((resume, new_state));
+                if let Some(bb) = drop {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+            mir::TerminatorKind::InlineAsm { .. } => {
+                return Err(AnalysisError::UnsupportedStatement(location));
+            }
+            _ => {
+                // No effect on integer locals
+                for bb in terminator.successors() {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+        }
+        Ok(res_vec)
+    }
+}
+
+impl<'mir, 'tcx: 'mir> AbstractState for OctagonState<'mir, 'tcx> {
+    fn is_bottom(&self) -> bool {
+        !self.reachable
+    }
+
+    fn join(&mut self, other: &Self) {
+        if !other.reachable {
+            return;
+        }
+        if !self.reachable {
+            self.reachable = true;
+            self.octagon = other.octagon.clone();
+            self.conditions = other.conditions.clone();
+            return;
+        }
+        self.octagon = self.octagon.join(&other.octagon);
+        self.conditions
+            .retain(|local, comparison| other.conditions.get(local) == Some(comparison));
+    }
+
+    fn widen(&mut self, previous: &Self) {
+        self.join(previous);
+        if !previous.reachable {
+            return;
+        }
+        self.octagon = self.octagon.widen(&previous.octagon, &self.thresholds);
+        // The range of the types is known without widening
+        for (k, &local) in self.variables.iter().enumerate() {
+            self.octagon.add_interval(k, self.type_interval(local));
+        }
+    }
+}
@@ -12,6 +12,7 @@ mod analysis_error;
 pub mod domains;
 pub mod mir_utils;
 mod pointwise_state;
+pub mod summaries;
 
 pub use analysis_error::AnalysisError;
 pub use pointwise_state::PointwiseState;
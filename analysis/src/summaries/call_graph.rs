@@ -0,0 +1,87 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use prusti_rustc_interface::{
+    data_structures::{
+        fx::FxHashMap,
+        graph::{scc::Sccs, vec_graph::VecGraph},
+    },
+    middle::{
+        mir,
+        ty::{self, TyCtxt},
+    },
+    span::def_id::DefId,
+};
+
+/// The call graph between the functions whose MIR bodies are available, partitioned into
+/// strongly connected components (SCCs).
+///
+/// An edge `f -> g` is added whenever the body of `f` contains a call that is statically
+/// resolved to `g`. Calls that cannot be resolved, such as calls of trait methods on generic or
+/// `dyn` receivers, do not add edges.
+pub struct CallGraph {
+    /// The members of each SCC, ordered such that callees come before their callers.
+    sccs: Vec<Vec<DefId>>,
+}
+
+impl CallGraph {
+    pub fn new<'tcx>(tcx: TyCtxt<'tcx>, bodies: &FxHashMap<DefId, &mir::Body<'tcx>>) -> Self {
+        let mut nodes: Vec<DefId> = bodies.keys().copied().collect();
+        // sort to ensure a deterministic order of the SCCs
+        nodes.sort_unstable();
+        let indices: FxHashMap<DefId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, &def_id)| (def_id, index))
+            .collect();
+
+        let mut edges = Vec::new();
+        for (caller_index, &caller) in nodes.iter().enumerate() {
+            for block_data in bodies[&caller].basic_blocks.iter() {
+                if let mir::TerminatorKind::Call { ref func, .. } = block_data.terminator().kind {
+                    if let Some(&callee_index) =
+                        resolve_callee(tcx, caller, func).and_then(|callee| indices.get(&callee))
+                    {
+                        edges.push((caller_index, callee_index));
+                    }
+                }
+            }
+        }
+
+        // SCCs are numbered in post-order, so the callees of an SCC have smaller indices
+        let graph = VecGraph::new(nodes.len(), edges);
+        let components: Sccs<usize, usize> = Sccs::new(&graph);
+        let mut sccs = vec![Vec::new(); components.num_sccs()];
+        for (node, &def_id) in nodes.iter().enumerate() {
+            sccs[components.scc(node)].push(def_id);
+        }
+        CallGraph { sccs }
+    }
+
+    /// The SCCs of the call graph, such that the callees of each function are in the same SCC
+    /// or in an earlier one.
+    pub fn bottom_up_sccs(&self) -> &[Vec<DefId>] {
+        &self.sccs
+    }
+}
+
+/// The function that is executed by a call of `func` in the body of `caller`, if the call
+/// can be resolved statically to a function item.
+pub fn resolve_callee<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    caller: DefId,
+    func: &mir::Operand<'tcx>,
+) -> Option<DefId> {
+    let (called_def_id, call_substs) = func.const_fn_def()?;
+    let param_env = tcx.param_env(caller);
+    match ty::Instance::resolve(tcx, param_env, called_def_id, call_substs) {
+        Ok(Some(ty::Instance {
+            def: ty::InstanceDef::Item(item),
+            ..
+        })) => Some(item.did),
+        _ => None,
+    }
+}
@@ -0,0 +1,113 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::AnalysisResult,
+    summaries::{resolve_callee, Summaries, SummaryAnalysis},
+};
+use prusti_rustc_interface::{
+    index::bit_set::BitSet,
+    middle::{mir, ty::TyCtxt},
+    span::def_id::DefId,
+};
+use std::rc::Rc;
+
+/// How calls of a function can give the control back to the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ControlFlowSummary {
+    /// `false` if the function never returns, so that the target of its calls is unreachable.
+    pub may_return: bool,
+    /// `false` if the function never unwinds, so that the cleanup of its calls is unreachable.
+    pub may_unwind: bool,
+}
+
+impl ControlFlowSummary {
+    /// The summary of a call whose callee is not known.
+    pub fn unknown() -> Self {
+        ControlFlowSummary {
+            may_return: true,
+            may_unwind: true,
+        }
+    }
+
+    /// The summary of the callee of `func`, in the body of `caller`.
+    pub fn of_call<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        caller: DefId,
+        func: &mir::Operand<'tcx>,
+        summaries: &Summaries<ControlFlowSummary>,
+    ) -> Self {
+        resolve_callee(tcx, caller, func)
+            .and_then(|callee| summaries.get(callee))
+            .copied()
+            .unwrap_or_else(Self::unknown)
+    }
+}
+
+/// Summarizes functions by computing which blocks of their bodies are reachable, without
+/// following the edges of calls that the summaries of the callees exclude.
+pub struct ControlFlowSummaryAnalysis<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> ControlFlowSummaryAnalysis<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        ControlFlowSummaryAnalysis { tcx }
+    }
+}
+
+impl<'tcx> SummaryAnalysis<'tcx> for ControlFlowSummaryAnalysis<'tcx> {
+    type Summary = ControlFlowSummary;
+
+    fn compute_summary(
+        &self,
+        def_id: DefId,
+        body: &mir::Body<'tcx>,
+        summaries: &Rc<Summaries<ControlFlowSummary>>,
+    ) -> AnalysisResult<ControlFlowSummary> {
+        let mut summary = ControlFlowSummary {
+            may_return: false,
+            may_unwind: false,
+        };
+        let mut visited = BitSet::new_empty(body.basic_blocks.len());
+        let mut worklist = vec![mir::START_BLOCK];
+        while let Some(bb) = worklist.pop() {
+            if !visited.insert(bb) {
+                continue;
+            }
+            let terminator = body[bb].terminator();
+            match terminator.kind {
+                mir::TerminatorKind::Return => summary.may_return = true,
+                mir::TerminatorKind::Resume => summary.may_unwind = true,
+                mir::TerminatorKind::Call {
+                    ref func,
+                    target,
+                    cleanup,
+                    ..
+                } => {
+                    let callee = ControlFlowSummary::of_call(self.tcx, def_id, func, summaries);
+                    worklist.extend(target.filter(|_| callee.may_return));
+                    if callee.may_unwind {
+                        match cleanup {
+                            Some(cleanup) => worklist.push(cleanup),
+                            // the callee unwinds directly into the caller of this function
+                            None => summary.may_unwind = true,
+                        }
+                    }
+                }
+                _ => {
+                    // A terminator that can panic without a cleanup block, such as an assertion,
+                    // unwinds directly into the caller of this function
+                    if terminator.unwind() == Some(&None) {
+                        summary.may_unwind = true;
+                    }
+                    worklist.extend(terminator.successors());
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
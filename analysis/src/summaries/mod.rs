@@ -0,0 +1,100 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Summaries of the effect of functions on their callers, which intraprocedural analyses can
+//! consult at `Call` terminators instead of assuming that a call can have any effect.
+//!
+//! The interval analysis uses an `IntervalSummary` of the values returned by the callee. The
+//! definitely initialized, definitely accessible and framing analyses use a
+//! `ControlFlowSummary`, because a callee can only affect the initialization of the caller's
+//! places by never returning or never unwinding. The maybe borrowed analysis needs no summary:
+//! the Polonius facts that it reads already account for the loans that flow through a call,
+//! as described by the signature of the callee.
+//!
+//! Prusti's encoder keeps using the intraprocedural results of these analyses. Pruning the
+//! edges of a call is sound for the analyses themselves, but the encoder still encodes the
+//! successors of the call, because it only knows the specification of the callee (e.g. of a
+//! trusted stub whose body panics). The state of the pruned blocks would be bottom, by which
+//! every place is initialized, and the encoder would then omit the permissions of the places
+//! that are actually uninitialized there.
+
+mod call_graph;
+mod control_flow;
+
+pub use call_graph::*;
+pub use control_flow::*;
+
+use crate::abstract_interpretation::AnalysisResult;
+use prusti_rustc_interface::{
+    data_structures::fx::FxHashMap,
+    middle::{mir, ty::TyCtxt},
+    span::def_id::DefId,
+};
+use std::rc::Rc;
+
+/// Trait to be used to compute the summary of a function from its MIR body.
+pub trait SummaryAnalysis<'tcx> {
+    type Summary: Clone;
+
+    /// Compute the summary of the function `def_id`. The calls in `body` should use the
+    /// summary of the callee in `summaries` if there is one, and be treated conservatively
+    /// otherwise.
+    fn compute_summary(
+        &self,
+        def_id: DefId,
+        body: &mir::Body<'tcx>,
+        summaries: &Rc<Summaries<Self::Summary>>,
+    ) -> AnalysisResult<Self::Summary>;
+}
+
+/// The summaries that have been computed, by function.
+#[derive(Clone, Debug)]
+pub struct Summaries<S> {
+    summaries: FxHashMap<DefId, S>,
+}
+
+impl<S> Default for Summaries<S> {
+    fn default() -> Self {
+        Summaries {
+            summaries: FxHashMap::default(),
+        }
+    }
+}
+
+impl<S: Clone> Summaries<S> {
+    /// Computes the summaries of the functions in `bodies`, bottom-up over the call graph, so
+    /// that the summaries of the callees are available when a function is summarized.
+    ///
+    /// The functions of a recursive SCC are summarized without the summaries of each other.
+    /// Functions whose analysis fails get no summary.
+    pub fn compute<'tcx, A: SummaryAnalysis<'tcx, Summary = S>>(
+        tcx: TyCtxt<'tcx>,
+        analysis: &A,
+        bodies: &FxHashMap<DefId, &mir::Body<'tcx>>,
+    ) -> Rc<Self> {
+        let call_graph = CallGraph::new(tcx, bodies);
+        let mut summaries = Rc::new(Summaries::default());
+        for scc in call_graph.bottom_up_sccs() {
+            let scc_summaries: Vec<_> = scc
+                .iter()
+                .filter_map(|&def_id| {
+                    analysis
+                        .compute_summary(def_id, bodies[&def_id], &summaries)
+                        .ok()
+                        .map(|summary| (def_id, summary))
+                })
+                .collect();
+            // The analyses of the SCC no longer hold a reference, so this does not clone
+            Rc::make_mut(&mut summaries).summaries.extend(scc_summaries);
+        }
+        summaries
+    }
+
+    /// The summary of the function `def_id`, if it has been computed.
+    pub fn get(&self, def_id: DefId) -> Option<&S> {
+        self.summaries.get(&def_id)
+    }
+}
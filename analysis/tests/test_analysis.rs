@@ -51,6 +51,11 @@ fn test_runner(_tests: &[&()]) {
         "tests/test_cases/relaxed_definitely_initialized",
         vec!["--analysis=RelaxedDefinitelyInitializedAnalysis".into()],
     );
+    run_tests(
+        "ui",
+        "tests/test_cases/interprocedural_definitely_initialized",
+        vec!["--analysis=InterproceduralDefinitelyInitializedAnalysis".into()],
+    );
     run_tests(
         "ui",
         "tests/test_cases/maybe_borrowed",
@@ -61,9 +66,34 @@ fn test_runner(_tests: &[&()]) {
         "tests/test_cases/definitely_accessible",
         vec!["--analysis=DefinitelyAccessibleAnalysis".into()],
     );
+    run_tests(
+        "ui",
+        "tests/test_cases/interprocedural_definitely_accessible",
+        vec!["--analysis=InterproceduralDefinitelyAccessibleAnalysis".into()],
+    );
     run_tests(
         "ui",
         "tests/test_cases/framing",
         vec!["--analysis=FramingAnalysis".into()],
     );
+    run_tests(
+        "ui",
+        "tests/test_cases/intervals",
+        vec!["--analysis=IntervalAnalysis".into()],
+    );
+    run_tests(
+        "ui",
+        "tests/test_cases/interprocedural_intervals",
+        vec!["--analysis=InterproceduralIntervalAnalysis".into()],
+    );
+    run_tests(
+        "ui",
+        "tests/test_cases/octagons",
+        vec!["--analysis=OctagonAnalysis".into()],
+    );
+    run_tests(
+        "ui",
+        "tests/test_cases/live_variables",
+        vec!["--analysis=LiveVariablesAnalysis".into()],
+    );
 }
@@ -0,0 +1,17 @@
+fn main() {
+    let _ = first(vec![1]);
+}
+
+fn fail() -> u32 {
+    loop {}
+}
+
+#[analyzer::run]
+fn first(v: Vec<u32>) -> u32 {
+    let w = v;
+    if w.is_empty() {
+        fail()
+    } else {
+        w[0]
+    }
+}
@@ -0,0 +1,13 @@
+fn main() {
+    let _ = wrap(3);
+}
+
+fn tag(x: u32) -> (u32, bool) {
+    (x, true)
+}
+
+#[analyzer::run]
+fn wrap(x: u32) -> Vec<(u32, bool)> {
+    let t = tag(x);
+    vec![t]
+}
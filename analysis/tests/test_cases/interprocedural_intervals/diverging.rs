@@ -0,0 +1,16 @@
+fn main() {
+    let _ = checked_index(3);
+}
+
+fn fail() -> usize {
+    loop {}
+}
+
+#[analyzer::run]
+fn checked_index(i: usize) -> usize {
+    if i < 10 {
+        i
+    } else {
+        fail()
+    }
+}
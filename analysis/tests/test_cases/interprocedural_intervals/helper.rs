@@ -0,0 +1,20 @@
+fn main() {
+    let _ = total(3, 4);
+}
+
+fn clamp(x: i32) -> i32 {
+    if x < 0 {
+        0
+    } else if x > 100 {
+        100
+    } else {
+        x
+    }
+}
+
+#[analyzer::run]
+fn total(a: i32, b: i32) -> i32 {
+    let p = clamp(a);
+    let q = clamp(b);
+    p + q
+}
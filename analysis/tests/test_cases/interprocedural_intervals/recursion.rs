@@ -0,0 +1,17 @@
+fn main() {
+    let _ = digits(1234);
+}
+
+fn count_digits(n: u32) -> u32 {
+    if n < 10 {
+        1
+    } else {
+        1 + count_digits(n / 10)
+    }
+}
+
+#[analyzer::run]
+fn digits(n: u32) -> u32 {
+    let d = count_digits(n);
+    d + 1
+}
@@ -0,0 +1,14 @@
+fn main() {
+    let _ = clamp(7);
+}
+
+#[analyzer::run]
+fn clamp(x: i32) -> i32 {
+    let mut res = x;
+    if x < -5 {
+        res = -5;
+    } else if x > 5 {
+        res = 5;
+    }
+    res
+}
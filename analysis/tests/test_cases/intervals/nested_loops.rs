@@ -0,0 +1,13 @@
+#[analyzer::run]
+fn main() {
+    let mut total: u32 = 0;
+    let mut i = 0;
+    while i < 4 {
+        let mut j = 0;
+        while j < i {
+            total += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+}
@@ -0,0 +1,11 @@
+fn id(x: i32) -> i32 {
+    x
+}
+
+#[analyzer::run]
+fn main() {
+    let mut x = 1;
+    let y = x;
+    x = id(y);
+    let z = x;
+}
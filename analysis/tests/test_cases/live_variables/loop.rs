@@ -0,0 +1,14 @@
+#[analyzer::run]
+fn sum(n: u32) -> u32 {
+    let mut i = 0;
+    let mut s = 0;
+    while i < n {
+        s += i;
+        i += 1;
+    }
+    s
+}
+
+fn main() {
+    sum(3);
+}
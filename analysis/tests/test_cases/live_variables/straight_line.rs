@@ -0,0 +1,8 @@
+#[analyzer::run]
+fn main() {
+    let a = 1;
+    let b = a + 2;
+    let mut c = b;
+    c = 5;
+    let d = c;
+}
@@ -0,0 +1,12 @@
+#[analyzer::run]
+fn count(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+fn main() {
+    count(10);
+}
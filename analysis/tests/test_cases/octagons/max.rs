@@ -0,0 +1,12 @@
+#[analyzer::run]
+fn max(a: i32, b: i32) -> i32 {
+    if a < b {
+        b
+    } else {
+        a
+    }
+}
+
+fn main() {
+    max(1, 2);
+}
@@ -0,0 +1,10 @@
+#[analyzer::run]
+fn main() {
+    let mut i = 0;
+    let mut j = 10;
+    while i < 10 {
+        i += 1;
+        j -= 1;
+    }
+    let k = i + j;
+}
@@ -410,13 +410,12 @@ impl<'v> ToViper<'v, viper::Expr<'v>> for Expr {
                 ),
             Expr::UnaryOp(op, ref expr, ref pos) => match expr.get_type() {
                 Type::Float(float_ty) => {
-                    let size = match float_ty {
-                        Float::F32 => viper::FloatSizeViper::F32,
-                        Float::F64 => viper::FloatSizeViper::F64,
-                    };
+                    let size = lower_float_size(*float_ty);
                     let op_kind = match op {
                         UnaryOpKind::Minus => viper::UnOpFloat::Neg,
                         UnaryOpKind::IsNaN => viper::UnOpFloat::IsNan,
+                        UnaryOpKind::IsInfinite => viper::UnOpFloat::IsInfinite,
+                        UnaryOpKind::Abs => viper::UnOpFloat::Abs,
                         _ => unreachable!("illegal unary operation for floats: {}", op),
                     };
                     ast.float_unop(op_kind, size, expr.to_viper(context, ast))
@@ -441,11 +440,15 @@ impl<'v> ToViper<'v, viper::Expr<'v>> for Expr {
                 },
             },
             Expr::BinOp(op, ref left, ref right, ref pos) => match left.get_maybe_type() {
+                // The SMT theory of floats has no disequality, and `!=` is not
+                // the negation of `<` or `>` in the presence of NaN.
+                Some(Type::Float(_)) if *op == BinaryOpKind::NeCmp => ast.not_with_pos(
+                    Expr::BinOp(BinaryOpKind::EqCmp, left.clone(), right.clone(), *pos)
+                        .to_viper(context, ast),
+                    pos.to_viper(context, ast),
+                ),
                 Some(Type::Float(float_ty)) => {
-                    let size = match float_ty {
-                        Float::F32 => viper::FloatSizeViper::F32,
-                        Float::F64 => viper::FloatSizeViper::F64,
-                    };
+                    let size = lower_float_size(*float_ty);
                     let float_op_kind = match op {
                         BinaryOpKind::Add => viper::BinOpFloat::Add,
                         BinaryOpKind::Sub => viper::BinOpFloat::Sub,
@@ -747,6 +750,38 @@ impl<'v> ToViper<'v, viper::Expr<'v>> for Expr {
                     let size = lower_bitvector_signed_size(*size);
                     ast.int_to_backend_bv(size, base.to_viper(context, ast))
                 }
+                CastKind::IntIntoFloat(size, float) => {
+                    let signed = matches!(size, BitVector::Signed(_));
+                    let size = lower_bitvector_signed_size(*size);
+                    let bv = ast.int_to_backend_bv(size, base.to_viper(context, ast));
+                    ast.bv_to_float(size, signed, lower_float_size(*float), bv)
+                }
+                CastKind::FloatIntoInt(float, signed_size) => match signed_size {
+                    BitVector::Signed(size) => {
+                        let bv = ast.float_to_bv(
+                            lower_float_size(*float),
+                            true,
+                            lower_bitvector_size(*size),
+                            base.to_viper(context, ast),
+                        );
+                        backend_bv_to_signed_int(context, ast, *size, bv, *position)
+                    }
+                    BitVector::Unsigned(size) => {
+                        let size = lower_bitvector_size(*size);
+                        let bv = ast.float_to_bv(
+                            lower_float_size(*float),
+                            false,
+                            size,
+                            base.to_viper(context, ast),
+                        );
+                        ast.backend_bv_to_int(size, bv)
+                    }
+                },
+                CastKind::FloatIntoFloat(from, to) => ast.float_to_float(
+                    lower_float_size(*from),
+                    lower_float_size(*to),
+                    base.to_viper(context, ast),
+                ),
             },
         };
         if config::simplify_encoding() {
@@ -1188,6 +1223,13 @@ fn block_to_viper<'a>(
     ast.seqn(&stmts, &[])
 }
 
+fn lower_float_size(float: Float) -> viper::FloatSizeViper {
+    match float {
+        Float::F32 => viper::FloatSizeViper::F32,
+        Float::F64 => viper::FloatSizeViper::F64,
+    }
+}
+
 fn lower_bitvector_size(size: BitVectorSize) -> viper::BvSize {
     match size {
         BitVectorSize::BV8 => viper::BvSize::BV8,
@@ -1230,11 +1272,23 @@ fn unsigned_bv_to_signed_int<'v>(
     size: BitVectorSize,
     value: &Expr,
     pos: Position,
+) -> viper::Expr<'v> {
+    let value = value.to_viper(context, ast);
+    backend_bv_to_signed_int(context, ast, size, value, pos)
+}
+
+/// Interprets a backend bitvector as a signed integer in two's complement.
+fn backend_bv_to_signed_int<'v>(
+    context: Context,
+    ast: &AstFactory<'v>,
+    size: BitVectorSize,
+    value: viper::Expr<'v>,
+    pos: Position,
 ) -> viper::Expr<'v> {
     let viper_size = lower_bitvector_size(size);
     let signed_max_int: Expr = signed_max_for_size(size).into();
     let unsigned_max_int: Expr = unsigned_max_for_size(size).into();
-    let value = ast.backend_bv_to_int(viper_size, value.to_viper(context, ast));
+    let value = ast.backend_bv_to_int(viper_size, value);
     let one: Expr = 1u32.into();
     ast.cond_exp_with_pos(
         ast.lt_cmp_with_pos(
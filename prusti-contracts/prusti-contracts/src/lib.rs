@@ -323,6 +323,18 @@ pub fn exists<T, F>(_trigger_set: T, _closure: F) -> bool {
     true
 }
 
+/// Specification entailment.
+///
+/// This is a Prusti-internal representation of the `|=` syntax.
+pub fn specification_entailment<T: ?Sized, P, Q>(
+    _once: bool,
+    _callable: &T,
+    _preconditions: P,
+    _postconditions: Q,
+) -> bool {
+    true
+}
+
 /// Creates an owned copy of a reference. This should only be used from within
 /// ghost code, as it circumvents the borrow checker.
 pub fn snap<T>(_x: &T) -> T {
@@ -338,4 +350,11 @@ pub fn snapshot_equality<T>(_l: T, _r: T) -> bool {
     true
 }
 
+/// The items produced so far by the iterator of a `for` loop, including the
+/// current one. This can only be used in the `body_invariant!` of a `for`
+/// loop over a range, a slice or a vector.
+pub fn visited<'a, T>() -> &'a [T] {
+    unimplemented!()
+}
+
 pub use private::*;
@@ -47,18 +47,17 @@ pub fn generate(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult
 }
 
 fn generate_where_clause_for_spec(
-    trait_bounds: &syn::PredicateType,
+    trait_bounds: &[syn::PredicateType],
     existing_where_clause: Option<&syn::WhereClause>,
 ) -> syn::WhereClause {
-    let span = trait_bounds.span();
-    if let Some(mut where_clause) = existing_where_clause.cloned() {
+    let mut where_clause = existing_where_clause
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote! { where });
+    for trait_bound in trait_bounds {
+        let span = trait_bound.span();
         where_clause.predicates.push(parse_quote_spanned! {span=>
-            #trait_bounds
+            #trait_bound
         });
-        where_clause
-    } else {
-        parse_quote_spanned! {span=>
-            where #trait_bounds
-        }
     }
+    where_clause
 }
@@ -255,17 +255,18 @@ fn generate_for_terminates(mut attr: TokenStream, item: &untyped::AnyFnItem) ->
     }
 
     let mut rewriter = rewriter::AstRewriter::new();
-    let spec_id = rewriter.generate_spec_id();
-    let spec_id_str = spec_id.to_string();
-    let spec_item =
-        rewriter.process_assertion(rewriter::SpecItemType::Termination, spec_id, attr, item)?;
-
-    Ok((
-        vec![spec_item],
-        vec![parse_quote_spanned! {item.span()=>
+    let mut spec_items = vec![];
+    let mut spec_attrs = vec![];
+    // The order of the attributes is the lexicographic order of the components.
+    for (spec_id, spec_item) in rewriter.process_termination_measure(attr, item)? {
+        let spec_id_str = spec_id.to_string();
+        spec_items.push(spec_item);
+        spec_attrs.push(parse_quote_spanned! {item.span()=>
             #[prusti::terminates_spec_id_ref = #spec_id_str]
-        }],
-    ))
+        });
+    }
+
+    Ok((spec_items, spec_attrs))
 }
 
 /// Generate spec items and attributes to typecheck and later retrieve "pure" annotations.
@@ -394,7 +395,11 @@ fn generate_for_trusted_for_types(attr: TokenStream, item: &syn::DeriveInput) ->
 }
 
 pub fn body_variant(tokens: TokenStream) -> TokenStream {
-    generate_expression_closure(&AstRewriter::process_loop_variant, tokens)
+    // Each component of a lexicographic variant gets its own specification
+    // block, in the same way as several loop invariants.
+    let mut rewriter = rewriter::AstRewriter::new();
+    let closures = handle_result!(rewriter.process_loop_variant(tokens));
+    closures.into_iter().map(wrap_expression_closure).collect()
 }
 
 pub fn body_invariant(tokens: TokenStream) -> TokenStream {
@@ -418,6 +423,11 @@ fn generate_expression_closure(
     let mut rewriter = rewriter::AstRewriter::new();
     let spec_id = rewriter.generate_spec_id();
     let closure = handle_result!(fun(&mut rewriter, spec_id, tokens));
+    wrap_expression_closure(closure)
+}
+
+/// Puts a specification closure into a block that is never executed
+fn wrap_expression_closure(closure: TokenStream) -> TokenStream {
     let callsite_span = Span::call_site();
     quote_spanned! {callsite_span=>
         #[allow(unused_must_use, unused_variables, unused_braces, unused_parens)]
@@ -888,7 +898,7 @@ fn generate_for_model(attr: TokenStream, item: &mut syn::DeriveInput) -> Generat
                     match result.first() {
                         Some(syn::Item::Struct(new_item)) => {
                             *item = syn::DeriveInput::from(new_item.clone()); //the internal model replaces the original struct
-                            Ok((vec![result[1].clone(), result[2].clone()], vec![]))
+                            Ok((result[1..].to_vec(), vec![]))
                         }
                         _ => unreachable!(),
                     }
@@ -2,7 +2,9 @@ use crate::{
     common::HasSignature,
     specifications::{
         common::{SpecificationId, SpecificationIdGenerator},
-        preparser::{parse_prusti, parse_prusti_assert_pledge, parse_prusti_pledge},
+        preparser::{
+            parse_prusti, parse_prusti_assert_pledge, parse_prusti_measure, parse_prusti_pledge,
+        },
         untyped,
     },
 };
@@ -178,24 +180,47 @@ impl AstRewriter {
         Ok((lhs_item, rhs_item))
     }
 
-    /// Parse a loop invariant into a Rust expression
-    pub fn process_loop_variant(
+    /// Parse a termination measure into one spec item per component of the
+    /// (lexicographic) measure, the most significant first
+    pub fn process_termination_measure<T: HasSignature + Spanned>(
         &mut self,
-        spec_id: SpecificationId,
         tokens: TokenStream,
-    ) -> syn::Result<TokenStream> {
-        let expr = parse_prusti(tokens)?;
-        let spec_id_str = spec_id.to_string();
-        Ok(quote_spanned! {expr.span()=>
-            {
-                #[prusti::spec_only]
-                #[prusti::loop_body_variant_spec]
-                #[prusti::spec_id = #spec_id_str]
-                || -> Int {
-                    #expr
-                };
-            }
-        })
+        item: &T,
+    ) -> syn::Result<Vec<(SpecificationId, syn::Item)>> {
+        parse_prusti_measure(tokens)?
+            .into_iter()
+            .map(|component| {
+                let spec_id = self.generate_spec_id();
+                let spec_item = self.generate_spec_item_fn(
+                    SpecItemType::Termination,
+                    spec_id,
+                    component,
+                    item,
+                )?;
+                Ok((spec_id, spec_item))
+            })
+            .collect()
+    }
+
+    /// Parse a loop variant into Rust expressions, one per component of the
+    /// (lexicographic) variant, the most significant first
+    pub fn process_loop_variant(&mut self, tokens: TokenStream) -> syn::Result<Vec<TokenStream>> {
+        parse_prusti_measure(tokens)?
+            .into_iter()
+            .map(|expr| {
+                let spec_id_str = self.generate_spec_id().to_string();
+                Ok(quote_spanned! {expr.span()=>
+                    {
+                        #[prusti::spec_only]
+                        #[prusti::loop_body_variant_spec]
+                        #[prusti::spec_id = #spec_id_str]
+                        || -> Int {
+                            #expr
+                        };
+                    }
+                })
+            })
+            .collect()
     }
 
     /// Parse a loop invariant into a Rust expression
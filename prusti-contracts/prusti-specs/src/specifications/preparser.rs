@@ -50,6 +50,17 @@ pub fn parse_prusti_assert_pledge(tokens: TokenStream) -> syn::Result<(TokenStre
     Ok((lhs, rhs))
 }
 
+/// Parses a termination measure or a loop variant. A tuple `(a, b, ...)` is a
+/// lexicographic measure and is returned as one expression per component, the
+/// most significant first. Any other expression is a single component.
+pub fn parse_prusti_measure(tokens: TokenStream) -> syn::Result<Vec<TokenStream>> {
+    let components = PrustiTokenStream::new(tokens).parse_measure()?;
+    for component in &components {
+        syn::parse2::<syn::Expr>(component.clone())?;
+    }
+    Ok(components)
+}
+
 pub fn parse_ghost_constraint(tokens: TokenStream) -> syn::Result<GhostConstraint> {
     syn::parse2(tokens)
 }
@@ -238,6 +249,23 @@ impl PrustiTokenStream {
         }
     }
 
+    /// Processes a Prusti token stream for a termination measure, in the form
+    /// `(a, b, ...)` or just `a`.
+    fn parse_measure(self) -> syn::Result<Vec<TokenStream>> {
+        if self.tokens.len() == 1 {
+            if let Some(PrustiToken::Group(_, Delimiter::Parenthesis, group)) = self.tokens.front() {
+                let components = group.as_ref().clone().split(PrustiBinaryOp::Rust(RustOp::Comma), true);
+                if components.len() > 1 {
+                    return components
+                        .into_iter()
+                        .map(|mut component| component.expr_bp(0))
+                        .collect();
+                }
+            }
+        }
+        Ok(vec![self.parse()?])
+    }
+
     /// The core of the Pratt parser algorithm. [self.tokens] is the source of
     /// "lexemes". [min_bp] is the minimum binding power we need to see when
     /// identifying a binary operator.
@@ -343,7 +371,8 @@ impl PrustiTokenStream {
                     continue;
                 }
 
-                Some(PrustiToken::CallDesc(..)) => todo!("call desc"),
+                Some(PrustiToken::CallDesc(span, _)) =>
+                    return err(*span, "call descriptions are not supported yet"),
 
                 Some(PrustiToken::BinOp(span, op)) => (*span, *op),
                 Some(PrustiToken::Outer(span)) =>
@@ -493,16 +522,26 @@ impl PrustiTokenStream {
 
 #[derive(Debug)]
 pub struct GhostConstraint {
-    pub trait_bounds: syn::PredicateType,
+    pub trait_bounds: Vec<syn::PredicateType>,
     pub comma: syn::token::Comma,
     pub specs: Vec<NestedSpec<TokenStream>>,
 }
 
 impl Parse for GhostConstraint {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut trait_bounds = vec![parse_trait_bounds(input)?];
+        let mut comma: syn::token::Comma = input.parse().map_err(with_ghost_constraint_example)?;
+        // Further trait bounds are separated by commas until the nested specifications
+        while !input.is_empty()
+            && !input.peek(syn::token::Bracket)
+            && !input.peek(syn::token::Brace)
+        {
+            trait_bounds.push(parse_trait_bounds(input)?);
+            comma = input.parse().map_err(with_ghost_constraint_example)?;
+        }
         Ok(GhostConstraint {
-            trait_bounds: parse_trait_bounds(input)?,
-            comma: input.parse().map_err(with_ghost_constraint_example)?,
+            trait_bounds,
+            comma,
             specs: PrustiTokenStream::new(input.parse().expect("Failed to parse GhostConstraint"))
                 .parse_rest(|pts| pts.pop_group_of_nested_specs(input.span()))?,
         })
@@ -547,7 +586,7 @@ fn validate_trait_bounds(trait_bounds: &syn::PredicateType) -> syn::Result<()> {
 }
 
 fn with_ghost_constraint_example(mut err: syn::Error) -> syn::Error {
-    err.combine(error(err.span(), "expected a trait bound and specifications in brackets, e.g.: `ghost_constraint(T: A + B + ..., [requires(...), ...])`"));
+    err.combine(error(err.span(), "expected trait bounds and specifications in brackets, e.g.: `ghost_constraint(T: A + B + ..., U: C, [requires(...), ...])`"));
     err
 }
 
@@ -606,13 +645,18 @@ fn translate_spec_ent(
         .collect::<Vec<_>>();
     let extract_args_decl = extract_args.iter()
         .zip(generics_args.iter())
-        .map(|(ident, arg_type)| quote_spanned! { span =>
-            #[prusti::spec_only]
-            fn #ident<
-                #(#generics_args),* ,
-                #generic_res,
-                F: FnOnce( #(#generics_args),* ) -> #generic_res
-            >(_f: &F) -> #arg_type { unreachable!() }
+        .enumerate()
+        .map(|(arg_index, (ident, arg_type))| {
+            let arg_index = arg_index.to_string();
+            quote_spanned! { span =>
+                #[prusti::spec_only]
+                #[prusti::spec_ent_arg = #arg_index]
+                fn #ident<
+                    #(#generics_args),* ,
+                    #generic_res,
+                    F: FnOnce( #(#generics_args),* ) -> #generic_res
+                >(_f: &F) -> #arg_type { unreachable!() }
+            }
         })
         .collect::<Vec<_>>();
 
@@ -635,6 +679,7 @@ fn translate_spec_ent(
         let __cl_ref = & #cl_expr;
         #(#extract_args_decl)*
         #[prusti::spec_only]
+        #[prusti::spec_ent_result]
         fn __extract_res<
             #(#generics_args),* ,
             #generic_res,
@@ -961,6 +1006,27 @@ mod tests {
             parse_prusti("assert!(a === b ==> b)".parse().unwrap()).unwrap().to_string(),
             "assert ! ((! (snapshot_equality (& a , & b)) || (b)))",
         );
+        assert_error!(
+            parse_prusti("f ~> |x: i32| { x > 0 } { true }".parse().unwrap()),
+            "call descriptions are not supported yet"
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let parse = |tokens: &str| {
+            parse_prusti_measure(tokens.parse().unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|component| component.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parse("n"), ["n"]);
+        assert_eq!(parse("(n + 1)"), ["(n + 1)"]);
+        assert_eq!(parse("(m, n)"), ["m", "n"]);
+        assert_eq!(parse("(m, n + 1, f(a, b),)"), ["m", "n + 1", "f (a , b)"]);
+        assert_eq!(parse("(m,)"), ["(m ,)"]);
+        assert_eq!(parse("(a ==> b, c)"), ["(! (a) || (b))", "c"]);
     }
 
     mod ghost_constraints {
@@ -984,7 +1050,8 @@ mod tests {
         fn multiple_bounds_multiple_specs() {
             let constraint = parse_ghost_constraint(quote!{ T: A+B+Foo<i32>, [requires(true), ensures(false), pure]}).unwrap();
 
-            assert_bounds_eq(constraint.trait_bounds, quote!{ T : A + B + Foo < i32 > });
+            assert_bounds_eq(&constraint.trait_bounds[0], quote!{ T : A + B + Foo < i32 > });
+            assert_eq!(constraint.trait_bounds.len(), 1);
             match &constraint.specs[0] {
                 NestedSpec::Requires(ts) => assert_eq!(ts.to_string(), "true"),
                 _ => panic!(),
@@ -1000,14 +1067,24 @@ mod tests {
         #[test]
         fn no_specs() {
             let constraint = parse_ghost_constraint(quote!{ T: A, []}).unwrap();
-            assert_bounds_eq(constraint.trait_bounds, quote!{ T : A });
+            assert_bounds_eq(&constraint.trait_bounds[0], quote!{ T : A });
             assert!(constraint.specs.is_empty());
         }
 
         #[test]
         fn fully_qualified_trait_path() {
             let constraint = parse_ghost_constraint(quote!{ T: path::to::A, [requires(true)]}).unwrap();
-            assert_bounds_eq(constraint.trait_bounds, quote!{ T : path :: to :: A });
+            assert_bounds_eq(&constraint.trait_bounds[0], quote!{ T : path :: to :: A });
+        }
+
+        #[test]
+        fn multiple_type_params() {
+            let constraint = parse_ghost_constraint(quote!{ K: A, V: B + C, [ensures(true)]}).unwrap();
+            assert_eq!(constraint.trait_bounds.len(), 2);
+            assert_bounds_eq(&constraint.trait_bounds[0], quote!{ K : A });
+            assert_bounds_eq(&constraint.trait_bounds[1], quote!{ V : B + C });
+            assert_eq!(constraint.specs.len(), 1);
+            assert_error!(parse_ghost_constraint(quote!{K: A, V: B }), "expected `,`");
         }
         
         #[test]
@@ -1019,8 +1096,8 @@ mod tests {
             assert!(parse_ghost_constraint(quote!{ T: Fn<(i32, bool,)>, []}).is_ok());
         }
         
-        fn assert_bounds_eq(parsed: syn::PredicateType, quote: TokenStream) {
-            assert_eq!(syn::WherePredicate::Type(parsed), syn::parse_quote!{ #quote });
+        fn assert_bounds_eq(parsed: &syn::PredicateType, quote: TokenStream) {
+            assert_eq!(syn::WherePredicate::Type(parsed.clone()), syn::parse_quote!{ #quote });
         }
     }
 }
@@ -2,13 +2,19 @@
 //!
 //! Usage documentation can be found in the corresponding macro definition.
 //!
-//! Given a `#[model]` attributed type `T`, this logic creates the following three items:
+//! Given a `#[model]` attributed type `T`, this logic creates the following items:
 //! * A struct `M` which holds the model's fields
+//! * Implementations of `Copy` and `Clone` for `M`
 //! * A trait which provides a `model` method to be used in specifications
 //! * An implementation of the aforementioned trait for `T`.
 //!   The implementation is `unimplemented!()`, `#[pure]` and `#[trusted]`
 //!
-//! The model struct `M` must be copyable.
+//! The model struct `M` must be copyable. It is `Copy` whenever its fields are,
+//! so the generic parameters that do not appear in the fields (e.g. the
+//! allocator of a `Vec`) do not need to be `Copy`.
+//!
+//! The struct `M` and the trait get the visibility of `T`, so that a crate can
+//! export the models of the types it specifies (e.g. `prusti-std`).
 //!
 //! # Note
 //! This macro always generates a trait with a `model` method on the fly for every modelled type.
@@ -31,7 +37,13 @@ pub fn rewrite(item_struct: syn::ItemStruct) -> syn::Result<Vec<syn::Item>> {
     let res = rewrite_internal(item_struct);
     match res {
         Ok(result) => {
-            Ok(vec![syn::Item::Struct(result.model_struct), syn::Item::Trait(result.to_model_trait), syn::Item::Impl(result.model_impl)])
+            Ok(vec![
+                syn::Item::Struct(result.model_struct),
+                syn::Item::Impl(result.copy_impl),
+                syn::Item::Impl(result.clone_impl),
+                syn::Item::Trait(result.to_model_trait),
+                syn::Item::Impl(result.model_impl),
+            ])
         },
         Err(err) => Err(err.into()),
     }
@@ -45,9 +57,12 @@ fn rewrite_internal(item_struct: syn::ItemStruct) -> TypeModelGenerationResult<T
     let model_struct = ModelStruct::create(&item_struct, &idents)?;
     let to_model_trait = ToModelTrait::create(&item_struct, &model_struct, &idents);
     let model_impl = create_model_impl(&item_struct, &model_struct, &to_model_trait)?;
+    let (copy_impl, clone_impl) = create_copy_impls(&model_struct);
 
     Ok(TypeModel {
         model_struct: model_struct.item,
+        copy_impl,
+        clone_impl,
         to_model_trait: to_model_trait.item,
         model_impl,
     })
@@ -73,10 +88,10 @@ impl ModelStruct {
 
         let model_struct_ident = &idents.model_struct_ident;
         let mut model_struct: syn::ItemStruct = parse_quote_spanned! {item_struct.span()=>
-            #[derive(Copy, Clone)]
             #[allow(non_camel_case_types)]
             struct #model_struct_ident {}
         };
+        model_struct.vis = item_struct.vis.clone();
 
         let params = item_struct
             .parse_user_annotated_type_params()
@@ -137,9 +152,10 @@ impl ToModelTrait {
         let model_path = &model_struct.path;
         
         let to_model_trait_ident = &idents.to_model_trait_ident;
+        let vis = &item_struct.vis;
         let item = parse_quote_spanned! {item_struct.span()=>
             #[allow(non_camel_case_types)]
-            trait #to_model_trait_ident<#(#generic_params),*> {
+            #vis trait #to_model_trait_ident<#(#generic_params),*> {
                 #[pure]
                 #[trusted]
                 #[prusti::type_models_to_model_fn]
@@ -155,6 +171,37 @@ impl ToModelTrait {
     }
 }
 
+/// Implements `Copy` and `Clone` for the model struct, provided that its
+/// fields are `Copy`. Unlike `#[derive(Copy, Clone)]`, this does not require
+/// every generic parameter to be `Copy`.
+fn create_copy_impls(model_struct: &ModelStruct) -> (syn::ItemImpl, syn::ItemImpl) {
+    let generic_params: Vec<&syn::GenericParam> =
+        model_struct.item.generics.params.iter().collect();
+    let field_types: Vec<&syn::Type> = model_struct
+        .item
+        .fields
+        .iter()
+        .map(|field| &field.ty)
+        .collect();
+    let model_struct_path = &model_struct.path;
+    let span = model_struct.item.span();
+
+    let copy_impl = parse_quote_spanned! {span=>
+        impl<#(#generic_params),*> ::core::marker::Copy for #model_struct_path
+        where #(#field_types: ::core::marker::Copy),* {}
+    };
+    let clone_impl = parse_quote_spanned! {span=>
+        impl<#(#generic_params),*> ::core::clone::Clone for #model_struct_path
+        where #(#field_types: ::core::marker::Copy),*
+        {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+    };
+    (copy_impl, clone_impl)
+}
+
 fn create_model_impl(
     item_struct: &syn::ItemStruct,
     model_struct: &ModelStruct,
@@ -269,6 +316,12 @@ struct TypeModel {
     /// The struct which represents the model
     model_struct: syn::ItemStruct,
 
+    /// The implementation of `Copy` for the [TypeModel::model_struct]
+    copy_impl: syn::ItemImpl,
+
+    /// The implementation of `Clone` for the [TypeModel::model_struct]
+    clone_impl: syn::ItemImpl,
+
     /// A trait which will be implemented on the modelled type
     /// to return the [TypeModel::model_struct]
     to_model_trait: syn::ItemTrait,
@@ -281,6 +334,8 @@ impl ToTokens for TypeModel {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.to_model_trait.to_tokens(tokens);
         self.model_struct.to_tokens(tokens);
+        self.copy_impl.to_tokens(tokens);
+        self.clone_impl.to_tokens(tokens);
         self.model_impl.to_tokens(tokens);
     }
 }
@@ -297,11 +352,15 @@ mod tests {
         let model_struct: syn::ItemStruct = parse_quote!(
             struct Foo {}
         );
+        let copy_impl: syn::ItemImpl = parse_quote!(impl Copy for Foo {});
+        let clone_impl: syn::ItemImpl = parse_quote!(impl Clone for Foo {});
         let trait_impl: syn::ItemImpl = parse_quote!(impl ToModel for Foo {});
 
         let rewritten_model = TypeModel {
             to_model_trait: to_model_trait.clone(),
             model_struct: model_struct.clone(),
+            copy_impl: copy_impl.clone(),
+            clone_impl: clone_impl.clone(),
             model_impl: trait_impl.clone(),
         };
         let actual_ts = rewritten_model.into_token_stream();
@@ -309,6 +368,8 @@ mod tests {
         let mut expected_ts = TokenStream::new();
         to_model_trait.to_tokens(&mut expected_ts);
         model_struct.to_tokens(&mut expected_ts);
+        copy_impl.to_tokens(&mut expected_ts);
+        clone_impl.to_tokens(&mut expected_ts);
         trait_impl.to_tokens(&mut expected_ts);
 
         assert_eq!(expected_ts.to_string(), actual_ts.to_string());
@@ -339,7 +400,6 @@ mod tests {
 
         let model_ident = check_model_ident(&model, "PrustiFooModel");
         let expected: syn::ItemStruct = syn::parse_quote!(
-            #[derive(Copy, Clone)]
             #[allow(non_camel_case_types)]
             struct #model_ident {
                 fld1: usize,
@@ -360,7 +420,6 @@ mod tests {
         let model_ident = check_model_ident(&model, "PrustiFooModel");
 
         let expected: syn::ItemStruct = parse_quote!(
-            #[derive(Copy, Clone)]
             #[allow(non_camel_case_types)]
             struct #model_ident(i32, u32, usize);
         );
@@ -406,7 +465,6 @@ mod tests {
         let trait_ident_str = trait_ident.to_string();
 
         let expected_struct: syn::ItemStruct = parse_quote!(
-            #[derive(Copy, Clone)]
             #[allow(non_camel_case_types)]
             struct #model_ident(i32, u32, usize);
         );
@@ -439,7 +497,6 @@ mod tests {
         let trait_ident_str = trait_ident.to_string();
 
         let expected_struct: syn::ItemStruct = parse_quote!(
-            #[derive(Copy, Clone)]
             #[allow(non_camel_case_types)]
             struct #model_ident<T, U> (i32,::core::marker::PhantomData<T> , ::core::marker::PhantomData<U>);
         );
@@ -472,6 +529,81 @@ mod tests {
         assert_eq_tokenizable(model.model_impl, expected_impl);
     }
 
+    #[test]
+    fn ok_copy_impls_do_not_require_copy_params() {
+        let input: syn::ItemStruct = parse_quote!(
+            struct Foo<#[generic] T: Copy, #[generic] A> {
+                elements: Seq<T>,
+            }
+        );
+        let model = expect_ok(rewrite_internal(input));
+
+        let model_ident = check_model_ident(&model, "PrustiFooTAModel");
+        let phantom_types: Vec<syn::Type> = model
+            .model_struct
+            .fields
+            .iter()
+            .skip(1)
+            .map(|field| field.ty.clone())
+            .collect();
+        assert_eq!(phantom_types.len(), 2);
+
+        let expected_copy_impl: syn::ItemImpl = parse_quote!(
+            impl<T: Copy, A> ::core::marker::Copy for #model_ident<T, A>
+            where
+                Seq<T>: ::core::marker::Copy,
+                #(#phantom_types: ::core::marker::Copy),*
+            {}
+        );
+        let expected_clone_impl: syn::ItemImpl = parse_quote!(
+            impl<T: Copy, A> ::core::clone::Clone for #model_ident<T, A>
+            where
+                Seq<T>: ::core::marker::Copy,
+                #(#phantom_types: ::core::marker::Copy),*
+            {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+        );
+
+        assert_eq_tokenizable(model.copy_impl, expected_copy_impl);
+        assert_eq_tokenizable(model.clone_impl, expected_clone_impl);
+    }
+
+    #[test]
+    fn ok_propagates_visibility() {
+        let input: syn::ItemStruct = parse_quote!(
+            pub struct Foo {
+                pub fld: i32,
+            }
+        );
+
+        let model = expect_ok(rewrite_internal(input));
+
+        let model_ident = check_model_ident(&model, "PrustiFooModel");
+        let trait_ident = check_trait_ident(&model, "PrustiFooToModel");
+
+        let expected_struct: syn::ItemStruct = parse_quote!(
+            #[allow(non_camel_case_types)]
+            pub struct #model_ident {
+                pub fld: i32,
+            }
+        );
+        let expected_trait: syn::ItemTrait = parse_quote!(
+            #[allow(non_camel_case_types)]
+            pub trait #trait_ident {
+                #[pure]
+                #[trusted]
+                #[prusti::type_models_to_model_fn]
+                fn model(&self) -> #model_ident<>;
+            }
+        );
+
+        assert_eq_tokenizable(model.model_struct, expected_struct);
+        assert_eq_tokenizable(model.to_model_trait, expected_trait);
+    }
+
     #[test]
     fn ok_defines_to_model_trait() {
         let input: syn::ItemStruct = parse_quote!(
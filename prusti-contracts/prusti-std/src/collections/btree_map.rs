@@ -0,0 +1,61 @@
+use prusti_contracts::*;
+#[cfg(feature = "prusti")]
+use std::alloc::Allocator;
+
+#[extern_spec]
+impl<K, V> ::std::collections::btree_map::BTreeMap<K, V> {
+    #[ensures(result.is_empty())]
+    pub fn new() -> ::std::collections::btree_map::BTreeMap<K, V>;
+}
+
+#[extern_spec]
+impl<K, V, A: Allocator + Clone> ::std::collections::btree_map::BTreeMap<K, V, A> {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.is_empty())]
+    pub fn clear(&mut self);
+
+    #[pure]
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: ::core::borrow::Borrow<Q> + Ord,
+        Q: Ord;
+
+    #[ensures(result.is_some() == self.contains_key(key))]
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: ::core::borrow::Borrow<Q> + Ord,
+        Q: Ord;
+
+    #[ensures(self.contains_key(&key))]
+    #[ensures(result.is_some() == old(self.contains_key(&key)))]
+    #[ensures(old(self.contains_key(&key)) ==> self.len() == old(self.len()))]
+    #[ensures(!old(self.contains_key(&key)) ==> self.len() == old(self.len()) + 1)]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V>
+    where
+        K: Ord;
+
+    #[ensures(!self.contains_key(key))]
+    #[ensures(result.is_some() == old(self.contains_key(key)))]
+    #[ensures(old(self.contains_key(key)) ==> self.len() == old(self.len()) - 1)]
+    #[ensures(!old(self.contains_key(key)) ==> self.len() == old(self.len()))]
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: ::core::borrow::Borrow<Q> + Ord,
+        Q: Ord;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn first_key_value(&self) -> Option<(&K, &V)>
+    where
+        K: Ord;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn last_key_value(&self) -> Option<(&K, &V)>
+    where
+        K: Ord;
+}
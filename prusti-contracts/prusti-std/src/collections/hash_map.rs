@@ -0,0 +1,75 @@
+#[cfg(feature = "prusti")]
+use crate::model::*;
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<K, V> ::std::collections::hash_map::HashMap<K, V> {
+    #[ensures(result.is_empty())]
+    #[ghost_constraint(K: Copy, V: Copy, [
+        ensures(result.model().entries === Map::empty())
+    ])]
+    pub fn new() -> ::std::collections::hash_map::HashMap<K, V>;
+
+    #[ensures(result.is_empty())]
+    #[ghost_constraint(K: Copy, V: Copy, [
+        ensures(result.model().entries === Map::empty())
+    ])]
+    pub fn with_capacity(capacity: usize) -> ::std::collections::hash_map::HashMap<K, V>;
+}
+
+#[extern_spec]
+impl<K, V, S> ::std::collections::hash_map::HashMap<K, V, S> {
+    #[pure]
+    #[ghost_constraint(K: Copy, V: Copy, [
+        ensures(Int::new_usize(result) == self.model().entries.len())
+    ])]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.is_empty())]
+    #[ghost_constraint(K: Copy, V: Copy, [
+        ensures(self.model().entries === Map::empty())
+    ])]
+    pub fn clear(&mut self);
+}
+
+#[extern_spec]
+impl<K, V, S> ::std::collections::hash_map::HashMap<K, V, S>
+where
+    K: Eq + ::core::hash::Hash,
+    S: ::std::hash::BuildHasher,
+{
+    #[pure]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: ::core::borrow::Borrow<Q>,
+        Q: ::core::hash::Hash + Eq;
+
+    #[ensures(result.is_some() == self.contains_key(k))]
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: ::core::borrow::Borrow<Q>,
+        Q: ::core::hash::Hash + Eq;
+
+    #[ensures(self.contains_key(&k))]
+    #[ensures(result.is_some() == old(self.contains_key(&k)))]
+    #[ensures(old(self.contains_key(&k)) ==> self.len() == old(self.len()))]
+    #[ensures(!old(self.contains_key(&k)) ==> self.len() == old(self.len()) + 1)]
+    #[ghost_constraint(K: Copy, V: Copy, [
+        ensures(self.model().entries === old(self.model().entries).insert(k, v)),
+        ensures(old(self.contains_key(&k)) == old(self.model().entries.contains(k)))
+    ])]
+    pub fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+    #[ensures(!self.contains_key(k))]
+    #[ensures(result.is_some() == old(self.contains_key(k)))]
+    #[ensures(old(self.contains_key(k)) ==> self.len() == old(self.len()) - 1)]
+    #[ensures(!old(self.contains_key(k)) ==> self.len() == old(self.len()))]
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        K: ::core::borrow::Borrow<Q>,
+        Q: ::core::hash::Hash + Eq;
+}
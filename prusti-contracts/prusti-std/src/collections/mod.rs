@@ -0,0 +1,3 @@
+pub mod btree_map;
+pub mod hash_map;
+pub mod vec_deque;
@@ -0,0 +1,51 @@
+use prusti_contracts::*;
+#[cfg(feature = "prusti")]
+use std::alloc::Allocator;
+
+#[extern_spec]
+impl<T> ::std::collections::vec_deque::VecDeque<T> {
+    #[ensures(result.is_empty())]
+    pub fn new() -> ::std::collections::vec_deque::VecDeque<T>;
+
+    #[ensures(result.is_empty())]
+    pub fn with_capacity(capacity: usize) -> ::std::collections::vec_deque::VecDeque<T>;
+}
+
+#[extern_spec]
+impl<T, A: Allocator> ::std::collections::vec_deque::VecDeque<T, A> {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn push_back(&mut self, value: T);
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn push_front(&mut self, value: T);
+
+    #[ensures(result.is_some() == (old(self.len()) > 0))]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) == 0 ==> self.is_empty())]
+    pub fn pop_back(&mut self) -> Option<T>;
+
+    #[ensures(result.is_some() == (old(self.len()) > 0))]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) == 0 ==> self.is_empty())]
+    pub fn pop_front(&mut self) -> Option<T>;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn front(&self) -> Option<&T>;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn back(&self) -> Option<&T>;
+
+    #[ensures(self.is_empty())]
+    pub fn clear(&mut self);
+
+    #[ensures(self.len() == old(self.len() + other.len()))]
+    #[ensures(other.is_empty())]
+    pub fn append(&mut self, other: &mut Self);
+}
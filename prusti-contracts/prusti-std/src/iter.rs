@@ -0,0 +1,70 @@
+//! The adapters of an `ExactSizeIterator` keep track of its length. These
+//! specifications use ghost constraints, so they are active only when
+//! `enable_ghost_constraints` is set.
+//!
+//! `ExactSizeIterator::len` is only pure for the iterators of `std` that
+//! implement it, since other implementations might have side effects. The
+//! iterators that use the default implementation (e.g. `Skip`, `Take` and
+//! `vec::IntoIter`) cannot be specified, because an external specification of
+//! a trait implementation cannot refer to a default method.
+
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<'a, T> ExactSizeIterator for ::core::slice::Iter<'a, T> {
+    #[pure]
+    fn len(&self) -> usize;
+}
+
+#[extern_spec]
+impl<I> ExactSizeIterator for ::core::iter::Enumerate<I>
+where
+    I: ExactSizeIterator,
+{
+    #[pure]
+    fn len(&self) -> usize;
+}
+
+#[extern_spec]
+impl<B, I, F> ExactSizeIterator for ::core::iter::Map<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(I::Item) -> B,
+{
+    #[pure]
+    fn len(&self) -> usize;
+}
+
+#[extern_spec]
+impl<I> ExactSizeIterator for ::core::iter::Rev<I>
+where
+    I: ExactSizeIterator + DoubleEndedIterator,
+{
+    #[pure]
+    fn len(&self) -> usize;
+}
+
+#[extern_spec]
+trait Iterator {
+    #[ghost_constraint(Self: ExactSizeIterator, [
+        ensures(result.len() == self.len())
+    ])]
+    fn enumerate(self) -> ::core::iter::Enumerate<Self>
+    where
+        Self: Sized;
+
+    #[ghost_constraint(Self: ExactSizeIterator, [
+        ensures(result.len() == self.len())
+    ])]
+    fn map<B, F>(self, f: F) -> ::core::iter::Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> B;
+
+    #[ghost_constraint(Self: ExactSizeIterator, [
+        ensures(result.len() == self.len())
+    ])]
+    fn rev(self) -> ::core::iter::Rev<Self>
+    where
+        Self: Sized + DoubleEndedIterator;
+}
@@ -1,14 +1,20 @@
-use prusti_contracts::*;
+//! External specifications for the Rust standard library.
+//!
+//! The specifications are imported by adding `extern crate prusti_std;` to the
+//! root of a crate. Most of them describe collections through their length
+//! (e.g. `Vec::len`, `HashMap::contains_key`), which every encoding of Prusti
+//! supports. The [`model`] module additionally provides `Seq`/`Map` models of
+//! the collections, which can be used in the specifications of code verified
+//! with `unsafe_core_proof`.
+#![cfg_attr(feature = "prusti", feature(allocator_api))]
 
-#[extern_spec]
-impl<K, V, S> ::std::collections::hash_map::HashMap<K, V, S>
-where
-    K: Eq + ::core::hash::Hash,
-    S: ::std::hash::BuildHasher,
-{
-    #[pure]
-    pub fn contains_key<Q>(&self, k: &Q) -> bool
-    where
-        K: ::core::borrow::Borrow<Q>,
-        Q: ::core::hash::Hash + Eq;
-}
+pub mod collections;
+pub mod iter;
+pub mod model;
+pub mod option;
+pub mod slice;
+pub mod string;
+pub mod vec;
+
+#[cfg(feature = "prusti")]
+pub use model::*;
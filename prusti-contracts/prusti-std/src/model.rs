@@ -0,0 +1,48 @@
+//! `Seq`/`Map` models of the standard collections, supported by the
+//! `unsafe_core_proof` encoding. The external specifications of `Vec` and
+//! `HashMap` describe their methods in terms of the models when the elements
+//! are `Copy`; these specifications use ghost constraints, so they are active
+//! only when `enable_ghost_constraints` is set. The other models can be used in
+//! the specifications of trusted functions that wrap the collections (or of
+//! user-written external specifications).
+//!
+//! ```ignore
+//! use prusti_std::*;
+//!
+//! #[requires(vec.model().elements === seq![1, 2])]
+//! #[ensures(vec.model().elements === seq![1, 2, 3])]
+//! fn push_three(vec: &mut Vec<u32>) {
+//!     vec.push(3)
+//! }
+//! ```
+//!
+//! `HashMap::get`, `contains_key` and `remove` take a borrowed key `&Q`, which
+//! cannot be related to the keys of the model. Instead, the specification of
+//! `insert` relates `contains_key` of the inserted key to the model.
+
+use prusti_contracts::*;
+#[cfg(feature = "prusti")]
+use std::{
+    alloc::Allocator,
+    collections::{BTreeMap, HashMap, VecDeque},
+};
+
+#[model]
+pub struct Vec<#[generic] T: Copy, #[generic] A: Allocator> {
+    pub elements: Seq<T>,
+}
+
+#[model]
+pub struct VecDeque<#[generic] T: Copy> {
+    pub elements: Seq<T>,
+}
+
+#[model]
+pub struct HashMap<#[generic] K: Copy, #[generic] V: Copy, #[generic] S> {
+    pub entries: Map<K, V>,
+}
+
+#[model]
+pub struct BTreeMap<#[generic] K: Copy, #[generic] V: Copy> {
+    pub entries: Map<K, V>,
+}
@@ -0,0 +1,62 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> ::core::option::Option<T> {
+    #[pure]
+    #[ensures(result == matches!(self, Some(_)))]
+    pub fn is_some(&self) -> bool;
+
+    #[pure]
+    #[ensures(result == matches!(self, None))]
+    pub fn is_none(&self) -> bool;
+
+    #[requires(self.is_some())]
+    pub fn unwrap(self) -> T;
+
+    #[requires(self.is_some())]
+    pub fn expect(self, msg: &str) -> T;
+
+    #[ensures(self.is_none() ==> result === default)]
+    pub fn unwrap_or(self, default: T) -> T;
+
+    #[ensures(result.is_some() == self.is_some())]
+    pub fn as_ref(&self) -> Option<&T>;
+
+    #[ensures(result.is_some() == old(self.is_some()))]
+    #[ensures(self.is_none())]
+    pub fn take(&mut self) -> Option<T>;
+
+    #[ensures(result.is_some() == old(self.is_some()))]
+    #[ensures(self.is_some())]
+    pub fn replace(&mut self, value: T) -> Option<T>;
+
+    #[ensures(result.is_some() == self.is_some())]
+    pub fn map<U, F>(self, f: F) -> Option<U>
+    where
+        F: FnOnce(T) -> U;
+
+    #[ensures(self.is_none() ==> result.is_none())]
+    pub fn filter<P>(self, predicate: P) -> Option<T>
+    where
+        P: FnOnce(&T) -> bool;
+
+    #[ensures(result.is_ok() == self.is_some())]
+    pub fn ok_or<E>(self, err: E) -> Result<T, E>;
+
+    #[ensures(result.is_ok() == self.is_some())]
+    pub fn ok_or_else<E, F>(self, err: F) -> Result<T, E>
+    where
+        F: FnOnce() -> E;
+
+    #[ensures(result.is_some() == (self.is_some() && optb.is_some()))]
+    pub fn and<U>(self, optb: Option<U>) -> Option<U>;
+
+    #[ensures(result.is_some() == (self.is_some() || optb.is_some()))]
+    pub fn or(self, optb: Option<T>) -> Option<T>;
+
+    #[ensures(result.is_some() == (self.is_some() != optb.is_some()))]
+    pub fn xor(self, optb: Option<T>) -> Option<T>;
+
+    #[ensures(result.is_some() == (self.is_some() && other.is_some()))]
+    pub fn zip<U>(self, other: Option<U>) -> Option<(T, U)>;
+}
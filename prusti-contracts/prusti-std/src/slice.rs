@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> [T] {
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn first(&self) -> Option<&T>;
+
+    #[ensures(result.is_some() == !self.is_empty())]
+    pub fn last(&self) -> Option<&T>;
+
+    #[requires(a < self.len() && b < self.len())]
+    #[ensures(self.len() == old(self.len()))]
+    pub fn swap(&mut self, a: usize, b: usize);
+
+    #[ensures(self.len() == old(self.len()))]
+    pub fn reverse(&mut self);
+
+    #[requires(mid <= self.len())]
+    #[ensures(result.0.len() == mid)]
+    #[ensures(result.1.len() == self.len() - mid)]
+    pub fn split_at(&self, mid: usize) -> (&[T], &[T]);
+
+    #[ensures(result.len() == self.len())]
+    pub fn iter(&self) -> ::core::slice::Iter<'_, T>;
+
+    #[ensures(self.is_empty() ==> !result)]
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq;
+}
@@ -0,0 +1,46 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl String {
+    #[ensures(result.is_empty())]
+    pub fn new() -> String;
+
+    #[ensures(result.is_empty())]
+    pub fn with_capacity(capacity: usize) -> String;
+
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == old(self.len()) + string.len())]
+    pub fn push_str(&mut self, string: &str);
+
+    #[ensures(self.len() > old(self.len()))]
+    pub fn push(&mut self, ch: char);
+
+    #[ensures(result.is_some() == !old(self.is_empty()))]
+    #[ensures(self.len() <= old(self.len()))]
+    pub fn pop(&mut self) -> Option<char>;
+
+    #[ensures(new_len <= old(self.len()) ==> self.len() == new_len)]
+    pub fn truncate(&mut self, new_len: usize);
+
+    #[ensures(self.is_empty())]
+    pub fn clear(&mut self);
+
+    #[ensures(result.len() == self.len())]
+    pub fn as_str(&self) -> &str;
+}
+
+#[extern_spec]
+impl str {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+}
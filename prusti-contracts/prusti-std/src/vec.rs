@@ -0,0 +1,99 @@
+#[cfg(feature = "prusti")]
+use crate::model::*;
+use prusti_contracts::*;
+#[cfg(feature = "prusti")]
+use std::alloc::Allocator;
+
+#[extern_spec]
+impl<T> Vec<T> {
+    #[ensures(result.is_empty())]
+    #[ghost_constraint(T: Copy, [
+        ensures(result.model().elements === Seq::empty())
+    ])]
+    pub fn new() -> Vec<T>;
+
+    #[ensures(result.is_empty())]
+    #[ghost_constraint(T: Copy, [
+        ensures(result.model().elements === Seq::empty())
+    ])]
+    pub fn with_capacity(capacity: usize) -> Vec<T>;
+}
+
+#[extern_spec]
+impl<T, A: Allocator> Vec<T, A> {
+    #[pure]
+    #[ghost_constraint(T: Copy, [
+        ensures(Int::new_usize(result) == self.model().elements.len())
+    ])]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ghost_constraint(T: Copy, [
+        ensures(self.model().elements === old(self.model().elements).concat(seq![value]))
+    ])]
+    pub fn push(&mut self, value: T);
+
+    #[ensures(result.is_some() == (old(self.len()) > 0))]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) == 0 ==> self.is_empty())]
+    #[ghost_constraint(T: Copy, [
+        ensures(match result {
+            Some(value) => old(self.model().elements) === self.model().elements.concat(seq![value]),
+            None => self.model().elements === Seq::empty(),
+        })
+    ])]
+    pub fn pop(&mut self) -> Option<T>;
+
+    #[requires(index <= self.len())]
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn insert(&mut self, index: usize, element: T);
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    pub fn remove(&mut self, index: usize) -> T;
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    pub fn swap_remove(&mut self, index: usize) -> T;
+
+    #[ensures(len < old(self.len()) ==> self.len() == len)]
+    #[ensures(len >= old(self.len()) ==> self.len() == old(self.len()))]
+    pub fn truncate(&mut self, len: usize);
+
+    #[ensures(self.is_empty())]
+    #[ghost_constraint(T: Copy, [
+        ensures(self.model().elements === Seq::empty())
+    ])]
+    pub fn clear(&mut self);
+
+    #[ensures(self.len() == old(self.len() + other.len()))]
+    #[ensures(other.is_empty())]
+    #[ghost_constraint(T: Copy, [
+        ensures(self.model().elements === old(self.model().elements.concat(other.model().elements))),
+        ensures(other.model().elements === Seq::empty())
+    ])]
+    pub fn append(&mut self, other: &mut Vec<T, A>);
+
+    #[requires(at <= self.len())]
+    #[ensures(self.len() == at)]
+    #[ensures(result.len() == old(self.len()) - at)]
+    pub fn split_off(&mut self, at: usize) -> Vec<T, A>
+    where
+        A: Clone;
+
+    #[ensures(result.len() == self.len())]
+    pub fn as_slice(&self) -> &[T];
+}
+
+#[extern_spec]
+impl<T: Clone, A: Allocator> Vec<T, A> {
+    #[ensures(self.len() == new_len)]
+    pub fn resize(&mut self, new_len: usize, value: T);
+
+    #[ensures(self.len() == old(self.len()) + other.len())]
+    pub fn extend_from_slice(&mut self, other: &[T]);
+}
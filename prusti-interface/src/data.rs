@@ -7,7 +7,9 @@
 //! This module defines data structures exchanged between a verifier and
 //! its environment.
 
+use prusti_common::report::verification::ItemStatus;
 use prusti_rustc_interface::{hir::def_id::DefId, middle::ty};
+use std::time::Duration;
 
 /// A unique identifier of the Rust procedure.
 pub type ProcedureDefId = DefId;
@@ -30,3 +32,13 @@ pub enum VerificationResult {
     /// the verifier.
     Failure,
 }
+
+/// The outcome of the verification of a single procedure, which is used for
+/// the verification report.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ProcedureVerificationResult {
+    pub procedure: ProcedureDefId,
+    pub status: ItemStatus,
+    /// The time spent verifying the programs that encode the procedure.
+    pub duration: Duration,
+}
@@ -0,0 +1,179 @@
+//! The call graph of the local crate and its strongly connected components,
+//! used to find the functions that might (mutually) recurse into each other.
+
+use crate::specs::is_spec_fn;
+use log::trace;
+use prusti_rustc_interface::{
+    data_structures::{
+        fx::{FxHashMap, FxHashSet},
+        graph::{scc::Sccs, vec_graph::VecGraph},
+    },
+    hir::def::DefKind,
+    middle::ty::{self, TyCtxt},
+    span::def_id::{DefId, LocalDefId},
+};
+
+/// The call graph of the local functions and methods, partitioned into strongly
+/// connected components (SCCs).
+///
+/// An edge `f -> g` is added whenever the body of `f` contains a call that
+/// might execute `g`, possibly through local closures called by `f`. Calls
+/// that cannot be resolved statically (trait methods called on generic or
+/// `dyn` receivers) are over-approximated by edges to all local
+/// implementations of the called method.
+pub struct CallGraph {
+    /// The position of each node of the graph.
+    indices: FxHashMap<LocalDefId, usize>,
+    /// The SCC of each node, indexed by the position of the node.
+    scc_of: Vec<usize>,
+    /// The members of each SCC.
+    sccs: Vec<Vec<LocalDefId>>,
+    /// Whether an SCC contains a cycle, i.e. it has several members or its
+    /// only member calls itself.
+    recursive: Vec<bool>,
+}
+
+impl CallGraph {
+    pub(super) fn new(tcx: TyCtxt<'_>) -> Self {
+        let nodes: Vec<LocalDefId> = tcx
+            .hir()
+            .body_owners()
+            .filter(|&def_id| {
+                matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+                    && !is_spec_fn(tcx, def_id.to_def_id())
+            })
+            .collect();
+        let indices: FxHashMap<LocalDefId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, &def_id)| (def_id, index))
+            .collect();
+
+        let mut edges = Vec::new();
+        for (caller_index, &caller) in nodes.iter().enumerate() {
+            for callee in callees(tcx, caller) {
+                if let Some(&callee_index) = callee.as_local().and_then(|id| indices.get(&id)) {
+                    edges.push((caller_index, callee_index));
+                }
+            }
+        }
+
+        let graph = VecGraph::new(nodes.len(), edges.clone());
+        let components: Sccs<usize, usize> = Sccs::new(&graph);
+        let scc_of: Vec<usize> = (0..nodes.len()).map(|node| components.scc(node)).collect();
+        let mut sccs = vec![Vec::new(); components.num_sccs()];
+        for (node, &scc) in scc_of.iter().enumerate() {
+            sccs[scc].push(nodes[node]);
+        }
+        let mut recursive: Vec<bool> = sccs.iter().map(|members| members.len() > 1).collect();
+        for (caller, callee) in edges {
+            if caller == callee {
+                recursive[scc_of[caller]] = true;
+            }
+        }
+        trace!("call graph sccs: {:?}", sccs);
+
+        CallGraph {
+            indices,
+            scc_of,
+            sccs,
+            recursive,
+        }
+    }
+
+    /// Returns the members of the SCC containing `def_id`, or `None` if
+    /// `def_id` is not a local function.
+    pub fn scc(&self, def_id: DefId) -> Option<&[LocalDefId]> {
+        let index = self.index(def_id)?;
+        Some(&self.sccs[self.scc_of[index]])
+    }
+
+    /// Returns whether `def_id` might call itself, directly or through other
+    /// functions.
+    pub fn is_recursive(&self, def_id: DefId) -> bool {
+        self.index(def_id)
+            .map_or(false, |index| self.recursive[self.scc_of[index]])
+    }
+
+    /// Returns whether `first` and `second` are local functions in the same
+    /// SCC, i.e. whether they might call each other.
+    pub fn in_same_scc(&self, first: DefId, second: DefId) -> bool {
+        match (self.index(first), self.index(second)) {
+            (Some(first), Some(second)) => {
+                self.scc_of[first] == self.scc_of[second] && self.recursive[self.scc_of[first]]
+            }
+            _ => false,
+        }
+    }
+
+    fn index(&self, def_id: DefId) -> Option<usize> {
+        self.indices.get(&def_id.as_local()?).copied()
+    }
+}
+
+/// Returns the functions that might be executed by the calls in the body of
+/// `caller`. The calls of the local closures called by `caller` are included.
+fn callees(tcx: TyCtxt<'_>, caller: LocalDefId) -> Vec<DefId> {
+    let param_env = tcx.param_env(caller);
+    let mut callees = Vec::new();
+    let mut bodies = vec![caller];
+    let mut visited_closures = FxHashSet::default();
+    while let Some(body) = bodies.pop() {
+        let instance = ty::InstanceDef::Item(ty::WithOptConstParam::unknown(body.to_def_id()));
+        for &(called_def_id, call_substs) in tcx.mir_inliner_callees(instance) {
+            let resolved = match tcx.resolve_instance(param_env.and((called_def_id, call_substs))) {
+                Ok(Some(ty::Instance {
+                    def: ty::InstanceDef::Item(item),
+                    ..
+                })) => vec![item.did],
+                Ok(Some(ty::Instance {
+                    def: ty::InstanceDef::Virtual(..),
+                    ..
+                }))
+                | Ok(None)
+                | Err(_) => implementations(tcx, called_def_id),
+                // Shims generated by the compiler, e.g. for calling a closure
+                // through `FnOnce`.
+                Ok(Some(instance)) => {
+                    let mut resolved = vec![instance.def_id()];
+                    resolved.extend(instance.substs.types().filter_map(|ty| match ty.kind() {
+                        ty::TyKind::Closure(closure_def_id, _) => Some(*closure_def_id),
+                        _ => None,
+                    }));
+                    resolved
+                }
+            };
+            for callee in resolved {
+                match callee.as_local() {
+                    Some(closure) if tcx.is_closure(callee) => {
+                        if visited_closures.insert(closure) {
+                            bodies.push(closure);
+                        }
+                    }
+                    _ => callees.push(callee),
+                }
+            }
+        }
+    }
+    callees
+}
+
+/// Returns the called method together with all local implementations of it,
+/// if it is a trait method.
+fn implementations(tcx: TyCtxt<'_>, called_def_id: DefId) -> Vec<DefId> {
+    let mut implementations = vec![called_def_id];
+    if let Some(trait_def_id) = tcx.trait_of_item(called_def_id) {
+        for impl_def_id in tcx
+            .all_impls(trait_def_id)
+            .filter(|impl_def_id| impl_def_id.is_local())
+        {
+            if let Some(&implementation) = tcx
+                .impl_item_implementor_ids(impl_def_id)
+                .get(&called_def_id)
+            {
+                implementations.push(implementation);
+            }
+        }
+    }
+    implementations
+}
@@ -63,6 +63,9 @@ impl<T> AnalysisResult<T> {
 /// The result of the definitely initialized analysis.
 pub type DefinitelyInitializedAnalysisResult<'tcx> = AnalysisResult<PlaceSet<'tcx>>;
 
+/// Computes the definitely initialized places of `body` without the summaries of the called
+/// functions: the encoder reaches the successors of a call through the specification of the
+/// callee, even if its body never returns.
 pub fn compute_definitely_initialized<'a, 'tcx: 'a>(
     def_id: DefId,
     body: &'a mir::Body<'tcx>,
@@ -0,0 +1,158 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module infers simple loop invariants using the interval analysis.
+//!
+//!
+//! Inferred loop invariants:
+//!
+//! For each loop, the invariants are bounds on the values of the integer
+//! variables that are assigned in the loop. They hold at the end of the block
+//! after which the loop invariant is checked, on the edges that stay in the
+//! loop. Bounds that follow from the type of a variable are omitted.
+
+use crate::environment::ProcedureLoops;
+use analysis::{
+    abstract_interpretation::{AbstractState, FixpointEngine},
+    domains::{Interval, IntervalAnalysis},
+};
+use log::debug;
+use prusti_common::Stopwatch;
+use prusti_rustc_interface::{
+    data_structures::fx::{FxHashMap, FxHashSet},
+    hir::def_id::DefId,
+    middle::{mir, ty::TyCtxt},
+};
+
+/// A bound on the value of a user variable that holds whenever the loop
+/// invariant is checked.
+#[derive(Debug, Clone)]
+pub struct InferredBound {
+    pub local: mir::Local,
+    pub name: String,
+    /// The bounds of the variable; a missing bound is implied by the type.
+    pub interval: Interval,
+}
+
+impl InferredBound {
+    /// The bound as a Rust expression, as it could be written in a
+    /// `body_invariant!(..)`.
+    pub fn to_rust_expr(&self) -> String {
+        match (self.interval.lower, self.interval.upper) {
+            (Some(lower), Some(upper)) if lower == upper => format!("{} == {}", self.name, lower),
+            (Some(lower), Some(upper)) => {
+                format!("{} <= {} && {} <= {}", lower, self.name, self.name, upper)
+            }
+            (Some(lower), None) => format!("{} >= {}", self.name, lower),
+            (None, Some(upper)) => format!("{} <= {}", self.name, upper),
+            (None, None) => "true".to_string(),
+        }
+    }
+}
+
+/// Infers bounds on integer variables for each loop, given the block at whose
+/// end the invariant of each loop head holds.
+pub fn infer_loop_invariants<'a, 'tcx: 'a>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &'a mir::Body<'tcx>,
+    loops: &ProcedureLoops,
+    invariant_blocks: &FxHashMap<mir::BasicBlock, mir::BasicBlock>,
+) -> FxHashMap<mir::BasicBlock, Vec<InferredBound>> {
+    let stopwatch = Stopwatch::start_debug("prusti-client", "loop invariant inference");
+    let analysis = IntervalAnalysis::new(tcx, def_id, body);
+    let pointwise_state = match analysis.run_fwd_analysis() {
+        Ok(pointwise_state) => pointwise_state,
+        Err(e) => {
+            // The inference is best-effort
+            debug!(
+                "Could not infer loop invariants of {:?}: {}",
+                def_id,
+                e.to_pretty_str(body)
+            );
+            return FxHashMap::default();
+        }
+    };
+
+    let user_variables: Vec<_> = body
+        .var_debug_info
+        .iter()
+        .filter_map(|info| match info.value {
+            mir::VarDebugInfoContents::Place(place) => place
+                .as_local()
+                .map(|local| (local, info.name.to_ident_string())),
+            _ => None,
+        })
+        .collect();
+
+    let mut result = FxHashMap::default();
+    for (&loop_head, &invariant_block) in invariant_blocks {
+        // The state on the edges that stay in the loop
+        let mut state = analysis.new_bottom();
+        if let Some(states_after_block) = pointwise_state.lookup_after_block(invariant_block) {
+            for (&successor, successor_state) in states_after_block {
+                if loops.is_block_in_loop(loop_head, successor) {
+                    state.join(successor_state);
+                }
+            }
+        }
+        if state.is_bottom() {
+            continue;
+        }
+
+        let assigned_locals = assigned_in_loop(body, loops, loop_head);
+        let mut bounds = vec![];
+        for (local, name) in &user_variables {
+            if !assigned_locals.contains(local) || !body.local_decls[*local].ty.is_integral() {
+                continue;
+            }
+            if let Some(interval) = state.get_interval(*local) {
+                let type_interval = Interval::of_type(tcx, body.local_decls[*local].ty);
+                let bound = InferredBound {
+                    local: *local,
+                    name: name.clone(),
+                    interval: Interval::new(
+                        interval
+                            .lower
+                            .filter(|&lower| Some(lower) != type_interval.lower),
+                        interval
+                            .upper
+                            .filter(|&upper| Some(upper) != type_interval.upper),
+                    ),
+                };
+                if !bound.interval.is_top() {
+                    bounds.push(bound);
+                }
+            }
+        }
+        if !bounds.is_empty() {
+            result.insert(loop_head, bounds);
+        }
+    }
+    stopwatch.finish();
+    result
+}
+
+/// The locals that are directly assigned in the body of the loop.
+fn assigned_in_loop(
+    body: &mir::Body,
+    loops: &ProcedureLoops,
+    loop_head: mir::BasicBlock,
+) -> FxHashSet<mir::Local> {
+    let mut assigned = FxHashSet::default();
+    for &bb in loops.get_loop_body(loop_head) {
+        let bb_data = &body[bb];
+        for stmt in &bb_data.statements {
+            if let mir::StatementKind::Assign(box (place, _)) = &stmt.kind {
+                assigned.insert(place.local);
+            }
+        }
+        if let mir::TerminatorKind::Call { destination, .. } = &bb_data.terminator().kind {
+            assigned.insert(destination.local);
+        }
+    }
+    assigned
+}
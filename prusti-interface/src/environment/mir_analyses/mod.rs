@@ -9,3 +9,4 @@
 
 pub mod allocation;
 pub mod initialization;
+pub mod loop_invariants;
@@ -14,11 +14,12 @@ mod slice_or_array_ref;
 mod split_aggregate_assignment;
 mod statement_as_assign;
 mod statement_at;
+mod trait_object_ref;
 mod tuple_items_for_ty;
 mod ty_as_ty_ref;
 
 pub use self::{
     all_places::*, args_for_mir::*, mir_place::*, real_edges::*, slice_or_array_ref::*,
-    split_aggregate_assignment::*, statement_as_assign::*, statement_at::*, tuple_items_for_ty::*,
-    ty_as_ty_ref::*,
+    split_aggregate_assignment::*, statement_as_assign::*, statement_at::*, trait_object_ref::*,
+    tuple_items_for_ty::*, ty_as_ty_ref::*,
 };
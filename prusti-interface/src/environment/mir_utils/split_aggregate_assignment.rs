@@ -1,4 +1,4 @@
-use super::{SliceOrArrayRef, TupleItemsForTy};
+use super::{SliceOrArrayRef, TraitObjectRef, TupleItemsForTy};
 use prusti_rustc_interface::{
     index::vec::Idx,
     middle::{mir, ty},
@@ -61,12 +61,12 @@ impl<'tcx> SplitAggregateAssignment<'tcx> for mir::Statement<'tcx> {
                     .collect()
             }
             mir::Rvalue::Use(_) | mir::Rvalue::Ref(_, _, _) => vec![(lhs, rhs)],
-            // slice creation is ok
+            // slice and trait object creation is ok
             mir::Rvalue::Cast(
                 mir::CastKind::Pointer(ty::adjustment::PointerCast::Unsize),
                 _,
                 cast_ty,
-            ) if cast_ty.is_slice_ref() => vec![(lhs, rhs)],
+            ) if cast_ty.is_slice_ref() || cast_ty.is_trait_object_ref() => vec![(lhs, rhs)],
             _ => unreachable!("Rvalue {:?} is not supported", rhs),
         };
 
@@ -0,0 +1,21 @@
+use prusti_rustc_interface::middle::ty::{Ty, TyKind};
+
+pub trait TraitObjectRef<'tcx> {
+    /// Checks whether the type is a reference to a trait object `dyn Trait`.
+    fn is_trait_object_ref(&self) -> bool;
+    /// Checks whether the type is a box of a trait object `dyn Trait`.
+    fn is_trait_object_box(&self) -> bool;
+}
+
+impl<'tcx> TraitObjectRef<'tcx> for Ty<'tcx> {
+    fn is_trait_object_ref(&self) -> bool {
+        match self.kind() {
+            TyKind::Ref(_, ty, _) => ty.is_trait(),
+            _ => false,
+        }
+    }
+
+    fn is_trait_object_box(&self) -> bool {
+        self.is_box() && self.boxed_ty().is_trait()
+    }
+}
@@ -10,6 +10,7 @@ use prusti_rustc_interface::middle::ty::{self, TyCtxt};
 
 pub mod body;
 pub mod borrowck;
+mod call_graph;
 mod collect_closure_defs_visitor;
 mod collect_prusti_spec_visitor;
 pub mod debug_utils;
@@ -30,6 +31,7 @@ mod query;
 
 pub use self::{
     body::EnvBody,
+    call_graph::CallGraph,
     diagnostic::EnvDiagnostic,
     loops::{LoopAnalysisError, PlaceAccess, PlaceAccessKind, ProcedureLoops},
     loops_utils::*,
@@ -46,6 +48,7 @@ use self::{
 };
 use crate::data::ProcedureDefId;
 use rustc_middle::ty::SubstsRef;
+use std::{cell::RefCell, rc::Rc};
 
 /// Facade to the Rust compiler.
 pub struct Environment<'tcx> {
@@ -54,6 +57,7 @@ pub struct Environment<'tcx> {
     pub diagnostic: EnvDiagnostic<'tcx>,
     pub name: EnvName<'tcx>,
     pub query: EnvQuery<'tcx>,
+    call_graph: RefCell<Option<Rc<CallGraph>>>,
 }
 
 impl<'tcx> Environment<'tcx> {
@@ -65,6 +69,7 @@ impl<'tcx> Environment<'tcx> {
             diagnostic: EnvDiagnostic::new(tcx),
             name: EnvName::new(tcx),
             query: EnvQuery::new(tcx),
+            call_graph: RefCell::new(None),
         }
     }
 
@@ -134,6 +139,18 @@ impl<'tcx> Environment<'tcx> {
             .unwrap()
     }
 
+    /// Returns the call graph of the local crate. It is computed on first use.
+    pub fn call_graph(&self) -> Rc<CallGraph> {
+        self.call_graph
+            .borrow_mut()
+            .get_or_insert_with(|| Rc::new(CallGraph::new(self.tcx())))
+            .clone()
+    }
+
+    /// Returns whether the called function might call the caller back, i.e.
+    /// whether both are in the same strongly connected component of the call
+    /// graph. Calls that cannot be resolved statically are assumed to reach the
+    /// caller.
     pub fn callee_reaches_caller(
         &self,
         caller_def_id: ProcedureDefId,
@@ -149,8 +166,15 @@ impl<'tcx> Environment<'tcx> {
                 .resolve_instance(param_env.and((called_def_id, call_substs)))
                 .unwrap()
             {
-                self.tcx()
-                    .mir_callgraph_reachable((instance, caller_def_id.expect_local()))
+                let call_graph = self.call_graph();
+                if call_graph.scc(caller_def_id).is_some() {
+                    instance.def_id() == caller_def_id
+                        || call_graph.in_same_scc(caller_def_id, instance.def_id())
+                } else {
+                    // Closures are not nodes of the call graph.
+                    self.tcx()
+                        .mir_callgraph_reachable((instance, caller_def_id.expect_local()))
+                }
             } else {
                 true
             }
@@ -18,7 +18,7 @@ use crate::{
         },
         mir_utils::{
             AllPlaces, RealEdges, SliceOrArrayRef, SplitAggregateAssignment, StatementAsAssign,
-            StatementAt,
+            StatementAt, TraitObjectRef,
         },
         polonius_info::facts::AllInputFacts,
     },
@@ -584,12 +584,13 @@ fn get_borrowed_places<'a, 'tcx: 'a>(
                     })
                     .collect()),
 
-                // slice creation involves an unsize pointer cast like &[i32; 3] -> &[i32]
+                // slice creation involves an unsize pointer cast like &[i32; 3] -> &[i32],
+                // and trait object creation one like &S -> &dyn Trait
                 &mir::Rvalue::Cast(
                     mir::CastKind::Pointer(ty::adjustment::PointerCast::Unsize),
                     ref operand,
                     ref cast_ty,
-                ) if cast_ty.is_slice_ref() => {
+                ) if cast_ty.is_slice_ref() || cast_ty.is_trait_object_ref() => {
                     trace!("slice: operand={:?}, ty={:?}", operand, cast_ty);
                     Ok(match operand {
                         mir::Operand::Copy(ref place) | mir::Operand::Move(ref place) => {
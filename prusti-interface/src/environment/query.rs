@@ -171,6 +171,72 @@ impl<'tcx> EnvQuery<'tcx> {
         self.resolve_assoc_types(sig, caller_def_id.into_param())
     }
 
+    /// Computes the signature and the closure kind of a callable type: a
+    /// function pointer, a closure, or a type parameter bounded by one of
+    /// the `Fn*` traits in the given `param_env`. Returns `None` if the
+    /// signature cannot be determined.
+    pub fn get_callable_sig(
+        self,
+        callable_ty: ty::Ty<'tcx>,
+        param_env: impl IntoParamTcx<'tcx, ParamEnv<'tcx>>,
+    ) -> Option<(ty::FnSig<'tcx>, ty::ClosureKind)> {
+        match callable_ty.kind() {
+            ty::TyKind::FnPtr(sig) => {
+                return Some((self.tcx.erase_late_bound_regions(*sig), ty::ClosureKind::Fn));
+            }
+            ty::TyKind::Closure(_, substs) => {
+                // closure signatures take their arguments as a single tuple
+                let cl_substs = substs.as_closure();
+                let sig = self.tcx.erase_late_bound_regions(cl_substs.sig());
+                let sig = self.tcx.mk_fn_sig(
+                    sig.inputs()[0].tuple_fields().iter(),
+                    sig.output(),
+                    false,
+                    prusti_rustc_interface::hir::Unsafety::Normal,
+                    prusti_rustc_interface::target::spec::abi::Abi::Rust,
+                );
+                return Some((sig, cl_substs.kind()));
+            }
+            _ => {}
+        }
+
+        // look for `Fn*<Args>` and `FnOnce<Args>::Output` bounds
+        let mut inputs = None;
+        let mut output = None;
+        let mut kind: Option<ty::ClosureKind> = None;
+        for predicate in param_env.into_param(self.tcx).caller_bounds() {
+            match self.tcx.erase_late_bound_regions(predicate.kind()) {
+                ty::PredicateKind::Trait(trait_pred) if trait_pred.self_ty() == callable_ty => {
+                    if let Some(trait_kind) =
+                        self.tcx.fn_trait_kind_from_lang_item(trait_pred.def_id())
+                    {
+                        inputs = Some(trait_pred.trait_ref.substs.type_at(1));
+                        // keep the most restrictive kind, e.g. `Fn` over `FnOnce`
+                        if kind.map_or(true, |kind| trait_kind.extends(kind)) {
+                            kind = Some(trait_kind);
+                        }
+                    }
+                }
+                ty::PredicateKind::Projection(projection)
+                    if projection.projection_ty.self_ty() == callable_ty
+                        && Some(projection.projection_ty.item_def_id)
+                            == self.tcx.lang_items().fn_once_output() =>
+                {
+                    output = projection.term.ty();
+                }
+                _ => {}
+            }
+        }
+        let sig = self.tcx.mk_fn_sig(
+            inputs?.tuple_fields().iter(),
+            output?,
+            false,
+            prusti_rustc_interface::hir::Unsafety::Normal,
+            prusti_rustc_interface::target::spec::abi::Abi::Rust,
+        );
+        Some((sig, kind?))
+    }
+
     /// Returns true iff `def_id` is a closure.
     pub fn is_closure(self, def_id: impl IntoParam<DefId>) -> bool {
         self.tcx.is_closure(def_id.into_param())
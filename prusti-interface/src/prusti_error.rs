@@ -7,7 +7,10 @@
 use crate::environment::EnvDiagnostic;
 use ::log::warn;
 use prusti_common::config;
-use prusti_rustc_interface::{errors::MultiSpan, span::Span};
+use prusti_rustc_interface::{
+    errors::MultiSpan,
+    span::{Span, DUMMY_SP},
+};
 
 /// The Prusti message that will be reported to the user.
 ///
@@ -110,6 +113,15 @@ impl PrustiError {
         )
     }
 
+    /// Report an invalid configuration of Prusti (e.g. flags that cannot be combined)
+    pub fn invalid_configuration<S: ToString>(message: S) -> Self {
+        check_message(message.to_string());
+        PrustiError::new(
+            format!("[Prusti: invalid configuration] {}", message.to_string()),
+            DUMMY_SP.into(),
+        )
+    }
+
     /// Report a non-fatal issue
     pub fn warning<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
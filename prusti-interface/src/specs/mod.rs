@@ -121,6 +121,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             let mut spec = SpecGraph::new(ProcedureSpecification::empty(local_id.to_def_id()));
 
             let mut kind = refs.into();
+            let mut terminates = vec![];
 
             for spec_id_ref in &refs.spec_id_refs {
                 match spec_id_ref {
@@ -148,11 +149,15 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
                         ));
                     }
                     SpecIdRef::Terminates(spec_id) => {
-                        spec.set_terminates(*self.spec_functions.get(spec_id).unwrap());
+                        terminates.push(*self.spec_functions.get(spec_id).unwrap());
                     }
                 }
             }
 
+            if !terminates.is_empty() {
+                spec.set_terminates(terminates);
+            }
+
             spec.set_trusted(refs.trusted);
 
             // We do not want to create an empty kind.
@@ -78,8 +78,8 @@ impl DefSpecificationMap {
                 if let Some(posts) = spec.posts.extract_with_selective_replacement() {
                     specs.extend(posts);
                 }
-                if let Some(Some(term)) = spec.terminates.extract_with_selective_replacement() {
-                    specs.push(term.to_def_id());
+                if let Some(Some(terms)) = spec.terminates.extract_with_selective_replacement() {
+                    specs.extend(terms.iter().map(|term| term.to_def_id()));
                 }
                 if let Some(pledges) = spec.pledges.extract_with_selective_replacement() {
                     specs.extend(pledges.iter().filter_map(|pledge| pledge.lhs));
@@ -202,7 +202,9 @@ pub struct ProcedureSpecification {
     pub posts: SpecificationItem<Vec<DefId>>,
     pub pledges: SpecificationItem<Vec<Pledge>>,
     pub trusted: SpecificationItem<bool>,
-    pub terminates: SpecificationItem<Option<LocalDefId>>,
+    /// The components of the termination measure, the most significant first.
+    /// `None` if the procedure is not known to terminate.
+    pub terminates: SpecificationItem<Option<Vec<LocalDefId>>>,
 }
 
 impl ProcedureSpecification {
@@ -452,12 +454,12 @@ impl SpecGraph<ProcedureSpecification> {
             .for_each(|s| s.trusted.set(trusted));
     }
 
-    /// Sets the termination flag for the base spec and all constrained specs.
-    pub fn set_terminates(&mut self, terminates: LocalDefId) {
-        self.base_spec.terminates.set(Some(terminates));
+    /// Sets the termination measure for the base spec and all constrained specs.
+    pub fn set_terminates(&mut self, terminates: Vec<LocalDefId>) {
+        self.base_spec.terminates.set(Some(terminates.clone()));
         self.specs_with_constraints
             .values_mut()
-            .for_each(|s| s.terminates.set(Some(terminates)));
+            .for_each(|s| s.terminates.set(Some(terminates.clone())));
     }
 
     /// Sets the [ProcedureSpecificationKind] for the base spec and all constrained specs.
@@ -6,7 +6,11 @@
 #![feature(let_chains)]
 #![feature(option_result_contains)]
 
-use prusti_utils::{config, launch};
+use prusti_utils::{
+    config, launch,
+    report::verification::{CrateReport, ItemReport, ItemStatus},
+};
+use serde::Serialize;
 use std::{env, fs, io, path::PathBuf, process::Command};
 
 fn main() {
@@ -31,7 +35,7 @@ where
     let args = args.skip_while(|arg| arg == "prusti");
     // Remove the "-- -Pflag" arguments since these won't apply to `cargo check`.
     // They have already been loaded (and the Category B flags are used below).
-    let args = args.take_while(|arg| arg != "--");
+    let args: Vec<_> = args.take_while(|arg| arg != "--").collect();
 
     // Category B flags (see dev-guide flags table):
     let cargo_path = config::cargo_path();
@@ -44,7 +48,23 @@ where
     };
     let cargo_target = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
     let cargo_target: PathBuf = [cargo_target, "verify".to_string()].into_iter().collect();
-    let exit_status = Command::new(cargo_path)
+    // With a JSON message format, the drivers write a report per crate into
+    // this directory, which is aggregated once cargo is done.
+    let report_dir = has_json_message_format(&args).then(|| {
+        // Cargo runs the drivers in different working directories.
+        let report_dir = env::current_dir()
+            .expect("current working directory invalid")
+            .join(&cargo_target)
+            .join("reports")
+            .join(std::process::id().to_string());
+        fs::remove_dir_all(&report_dir).ok();
+        report_dir
+    });
+    let mut cargo = Command::new(cargo_path);
+    if let Some(report_dir) = &report_dir {
+        cargo.env("PRUSTI_VERIFICATION_REPORT_DIR", report_dir);
+    }
+    let exit_status = cargo
         .arg(&command)
         .args(features)
         .args(args)
@@ -62,10 +82,17 @@ where
         .env("DEFAULT_PRUSTI_QUIET", "true")
         .env("DEFAULT_PRUSTI_FULL_COMPILATION", "true")
         .env("DEFAULT_PRUSTI_LOG_DIR", cargo_target.join("log"))
-        .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache.bin"))
+        .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache"))
         .status()
         .expect("could not run cargo");
 
+    if let Some(report_dir) = report_dir {
+        let reports =
+            CrateReport::read_all(&report_dir).expect("could not read the verification reports");
+        print_verification_report(&reports);
+        fs::remove_dir_all(&report_dir).ok();
+    }
+
     if exit_status.success() {
         if command == "build" {
             copy_exported_specs(cargo_target).ok();
@@ -76,6 +103,117 @@ where
     }
 }
 
+/// Whether cargo was asked for JSON messages, e.g. with `--message-format=json`.
+fn has_json_message_format(args: &[String]) -> bool {
+    let is_json = |format: &str| format.starts_with("json");
+    args.iter().enumerate().any(|(index, arg)| {
+        if let Some(format) = arg.strip_prefix("--message-format=") {
+            is_json(format)
+        } else {
+            arg == "--message-format" && args.get(index + 1).map_or(false, |format| is_json(format))
+        }
+    })
+}
+
+/// A verified item, printed as a JSON line among the messages of cargo.
+#[derive(Serialize)]
+struct ItemMessage<'a> {
+    reason: &'static str,
+    #[serde(rename = "crate")]
+    crate_name: &'a str,
+    #[serde(flatten)]
+    item: &'a ItemReport,
+}
+
+#[derive(Serialize, Default)]
+struct SummaryRow {
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    crate_name: Option<String>,
+    items: usize,
+    verified: usize,
+    failed: usize,
+    #[serde(rename = "timed-out")]
+    timed_out: usize,
+    skipped: usize,
+    trusted: usize,
+    duration_ms: u64,
+}
+
+impl SummaryRow {
+    fn add(&mut self, report: &CrateReport) {
+        self.items += report.items.len();
+        self.verified += report.count(ItemStatus::Verified);
+        self.failed += report.count(ItemStatus::Failed);
+        self.timed_out += report.count(ItemStatus::TimedOut);
+        self.skipped += report.count(ItemStatus::Skipped);
+        self.trusted += report.count(ItemStatus::Trusted);
+        self.duration_ms += report.duration_ms;
+    }
+}
+
+/// The summary of the whole workspace, which is the last JSON line.
+#[derive(Serialize)]
+struct SummaryMessage {
+    reason: &'static str,
+    crates: Vec<SummaryRow>,
+    total: SummaryRow,
+}
+
+/// Prints every item and the summary as JSON lines on stdout, and the summary
+/// as a table on stderr.
+fn print_verification_report(reports: &[CrateReport]) {
+    let mut crates: Vec<SummaryRow> = Vec::new();
+    let mut total = SummaryRow::default();
+    for report in reports {
+        for item in &report.items {
+            let message = ItemMessage {
+                reason: "prusti-item",
+                crate_name: &report.crate_name,
+                item,
+            };
+            println!("{}", serde_json::to_string(&message).unwrap());
+        }
+        // A crate can be compiled several times, e.g. as a library and a binary.
+        match crates.last_mut() {
+            Some(row) if row.crate_name.as_ref() == Some(&report.crate_name) => row.add(report),
+            _ => {
+                let mut row = SummaryRow {
+                    crate_name: Some(report.crate_name.clone()),
+                    ..Default::default()
+                };
+                row.add(report);
+                crates.push(row);
+            }
+        }
+        total.add(report);
+    }
+
+    eprintln!(
+        "{:<30} {:>8} {:>8} {:>8} {:>9} {:>8} {:>8} {:>10}",
+        "crate", "items", "verified", "failed", "timed-out", "skipped", "trusted", "time (s)"
+    );
+    for row in crates.iter().chain([&total]) {
+        eprintln!(
+            "{:<30} {:>8} {:>8} {:>8} {:>9} {:>8} {:>8} {:>10.1}",
+            row.crate_name.as_deref().unwrap_or("total"),
+            row.items,
+            row.verified,
+            row.failed,
+            row.timed_out,
+            row.skipped,
+            row.trusted,
+            row.duration_ms as f64 / 1000.0
+        );
+    }
+
+    let message = SummaryMessage {
+        reason: "prusti-summary",
+        crates,
+        total,
+    };
+    println!("{}", serde_json::to_string(&message).unwrap());
+}
+
 /// Copy specs from '{cargo_target}/*/deps/*.specs' to '{cargo_target}/*/*.specs'
 fn copy_exported_specs(cargo_target: PathBuf) -> io::Result<()> {
     for de in fs::read_dir(cargo_target)? {
@@ -0,0 +1,168 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use log::info;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tokio::runtime::Builder;
+use viper::{Cache, PersistentCache, VerificationResult, RESULT_CACHE_VERSION};
+use warp::{http::StatusCode, hyper::body::Bytes, Filter, Reply};
+
+/// The largest verification result the cache server accepts.
+const MAX_ENTRY_SIZE: u64 = 16 << 20;
+
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Serves the entries of `cache` to `RemoteCache` clients on the given address
+/// and port. This is a simple stand-in for a shared cache: any server that
+/// implements the protocol described in `RemoteCache` can be used instead.
+///
+/// Anyone who can reach the server can read the cached results. Results can
+/// only be uploaded with the bearer token `upload_token`; without a token, the
+/// server is read-only.
+pub fn start_cache_server(
+    address: IpAddr,
+    port: u16,
+    cache: PersistentCache,
+    upload_token: Option<String>,
+) -> ! {
+    listen_with_address_callback(address, port, cache, upload_token, |_| ())
+}
+
+/// Starts a cache server on a free local port in a new thread and returns its
+/// address.
+pub fn spawn_cache_server_thread(
+    cache: PersistentCache,
+    upload_token: Option<String>,
+) -> SocketAddr {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        listen_with_address_callback(
+            Ipv4Addr::LOCALHOST.into(),
+            0,
+            cache,
+            upload_token,
+            move |address| sender.send(address).unwrap(),
+        );
+    });
+    receiver.recv().unwrap()
+}
+
+fn listen_with_address_callback<F>(
+    address: IpAddr,
+    port: u16,
+    cache: PersistentCache,
+    upload_token: Option<String>,
+    address_callback: F,
+) -> !
+where
+    F: FnOnce(SocketAddr),
+{
+    let cache = Arc::new(Mutex::new(cache));
+
+    let get_cache = cache.clone();
+    let get_entry =
+        warp::get()
+            .and(warp::path!(u64 / String))
+            .map(move |version: u64, key: String| {
+                let mut cache = get_cache.lock().unwrap();
+                match parse_key(version, &key).and_then(|key| (&mut *cache).get(key)) {
+                    Some(result) => bincode::serialize(&result)
+                        .expect("could not encode verification result")
+                        .into_response(),
+                    None => StatusCode::NOT_FOUND.into_response(),
+                }
+            });
+
+    let put_cache = cache.clone();
+    let put_entry = warp::put()
+        .and(warp::path!(u64 / String))
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::content_length_limit(MAX_ENTRY_SIZE))
+        .and(warp::body::bytes())
+        .map(
+            move |version: u64, key: String, auth: Option<String>, body: Bytes| {
+                if !is_authorized(upload_token.as_deref(), auth.as_deref()) {
+                    return StatusCode::FORBIDDEN;
+                }
+                store_entry(&put_cache, version, &key, &body)
+            },
+        );
+
+    let endpoints = get_entry.or(put_entry);
+
+    let runtime = Builder::new_current_thread()
+        .thread_name("prusti-cache-server")
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime");
+
+    runtime.block_on(async {
+        // Regularly evict entries to keep the cache directory within its size
+        // limit. Doing this on every upload would scan the directory too often.
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+            loop {
+                interval.tick().await;
+                cache.lock().unwrap().save();
+            }
+        });
+
+        let (address, server_loop) = warp::serve(endpoints).bind_ephemeral((address, port));
+
+        println!("port: {}", address.port());
+        address_callback(address);
+
+        info!("Prusti Cache Server listening on {}", address);
+        server_loop.await
+    });
+
+    unreachable!("The cache server unexpectedly stopped.");
+}
+
+/// Stores an uploaded entry in the cache.
+fn store_entry(cache: &Mutex<PersistentCache>, version: u64, key: &str, body: &[u8]) -> StatusCode {
+    let Some(key) = parse_key(version, key) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    let Ok(result) = bincode::deserialize::<VerificationResult>(body) else {
+        info!("Rejecting invalid cache entry {}", key);
+        return StatusCode::BAD_REQUEST;
+    };
+    cache.lock().unwrap().insert(key, result);
+    StatusCode::NO_CONTENT
+}
+
+/// Checks the `Authorization` header of an upload against the bearer token of
+/// the server. Without a token, every upload is rejected.
+fn is_authorized(upload_token: Option<&str>, authorization: Option<&str>) -> bool {
+    let (Some(upload_token), Some(authorization)) = (upload_token, authorization) else {
+        return false;
+    };
+    let Some(token) = authorization.strip_prefix("Bearer ") else {
+        return false;
+    };
+    // Compare all the bytes, so that the time does not reveal the common prefix.
+    token.len() == upload_token.len()
+        && token
+            .bytes()
+            .zip(upload_token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Parses the key of an entry. Entries of other cache versions are treated as
+/// missing.
+fn parse_key(version: u64, key: &str) -> Option<u64> {
+    if version != RESULT_CACHE_VERSION || key.len() != 16 {
+        return None;
+    }
+    u64::from_str_radix(key, 16).ok()
+}
@@ -0,0 +1,51 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use clap::Parser;
+use prusti_utils::config;
+use std::{env, net::IpAddr, path::PathBuf};
+use viper::PersistentCache;
+
+/// A server that shares Prusti verification results between machines. Point
+/// the `REMOTE_CACHE_URL` flag of Prusti to it.
+#[derive(Parser, Debug)]
+#[clap(version, about, long_about = None)]
+struct Args {
+    /// Sets the address on which to listen for incoming requests.
+    #[clap(short, long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    address: IpAddr,
+    /// Sets the port on which to listen for incoming requests.
+    /// Pass 0 to get a free one assigned by the OS.
+    #[clap(short, long, value_name = "PORT", default_value_t = 0)]
+    port: u16,
+    /// Sets the directory in which the verification results are stored.
+    #[clap(short, long, value_name = "DIR")]
+    cache_dir: PathBuf,
+    /// Sets the maximum size (in megabytes) of the cache directory.
+    #[clap(short, long, value_name = "MB", default_value_t = 1024)]
+    max_size: u64,
+    /// Sets the bearer token that clients need to upload verification results.
+    /// Defaults to the `PRUSTI_CACHE_SERVER_UPLOAD_TOKEN` environment variable.
+    /// Without a token, the server is read-only.
+    #[clap(long, value_name = "TOKEN")]
+    upload_token: Option<String>,
+}
+
+fn main() {
+    env_logger::init_from_env(
+        env_logger::Env::new()
+            .filter_or("PRUSTI_LOG", config::log())
+            .write_style_or("PRUSTI_LOG_STYLE", config::log_style()),
+    );
+
+    let args = Args::parse();
+
+    let upload_token = args
+        .upload_token
+        .or_else(|| env::var("PRUSTI_CACHE_SERVER_UPLOAD_TOKEN").ok());
+    let cache = PersistentCache::load_cache(args.cache_dir, args.max_size);
+    prusti_server::start_cache_server(args.address, args.port, cache, upload_token);
+}
@@ -4,7 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::VerificationRequest;
+use crate::{RequestId, RequestStatus, ServerMessage, VerificationRequest};
 use prusti_common::config;
 use reqwest::Client;
 use url::{ParseError, Url};
@@ -58,4 +58,94 @@ impl PrustiClient {
         };
         Ok(response)
     }
+
+    /// Sends a batch of requests to the streaming endpoint and calls
+    /// `on_message` for every progress event as soon as it arrives. Returns
+    /// once the server closes the stream.
+    pub async fn verify_stream<F>(
+        &self,
+        requests: Vec<VerificationRequest>,
+        on_message: F,
+    ) -> reqwest::Result<()>
+    where
+        F: FnMut(ServerMessage),
+    {
+        self.stream("verify_stream/", &requests, on_message).await
+    }
+
+    /// Like `verify_stream`, but each element of the batch is a portfolio:
+    /// requests that verify the same program with different backend
+    /// configurations. The server races the configurations of a portfolio and
+    /// reports the one that won in `ServerMessage::Finished`.
+    pub async fn verify_portfolio_stream<F>(
+        &self,
+        portfolios: Vec<Vec<VerificationRequest>>,
+        on_message: F,
+    ) -> reqwest::Result<()>
+    where
+        F: FnMut(ServerMessage),
+    {
+        self.stream("verify_portfolio_stream/", &portfolios, on_message)
+            .await
+    }
+
+    async fn stream<T, F>(&self, endpoint: &str, body: &T, mut on_message: F) -> reqwest::Result<()>
+    where
+        T: serde::Serialize,
+        F: FnMut(ServerMessage),
+    {
+        let mut response = self
+            .client
+            .post(
+                self.server_url
+                    .join("json/")
+                    .unwrap()
+                    .join(endpoint)
+                    .unwrap(),
+            )
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        // Chunks do not necessarily end at line boundaries.
+        let mut buffer = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let message = serde_json::from_slice(&line).expect("error decoding server message");
+                on_message(message);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the requests that the server is currently working on or that
+    /// wait in its queue.
+    pub async fn status(&self) -> reqwest::Result<Vec<RequestStatus>> {
+        self.client
+            .get(self.server_url.join("status/").unwrap())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Aborts a queued or running request. Fails if the server does not know
+    /// the request, e.g. because it has already finished.
+    pub async fn cancel(&self, id: RequestId) -> reqwest::Result<()> {
+        self.client
+            .delete(
+                self.server_url
+                    .join("request/")
+                    .unwrap()
+                    .join(&id.to_string())
+                    .unwrap(),
+            )
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
@@ -4,14 +4,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+mod cache_server;
 mod client;
 mod process_verification;
+mod request_status;
 mod server;
+mod server_message;
 mod verification_request;
+mod worker_pool;
 
+pub use cache_server::*;
 pub use client::*;
 pub use process_verification::*;
+pub use request_status::*;
 pub use server::*;
+pub use server_message::*;
 pub use verification_request::*;
 
 // Futures returned by `Client` need to be executed in a compatible tokio runtime.
@@ -12,16 +12,103 @@ use prusti_common::{
     vir::{program_normalization::NormalizationInfo, ToViper},
     Stopwatch,
 };
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::create_dir_all,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
 use viper::{
-    smt_manager::SmtManager, Cache, VerificationBackend, VerificationContext, VerificationResult,
+    smt_manager::SmtManager, Cache, PersistentCache, RemoteCache, SmtSolverKind,
+    VerificationBackend, VerificationContext, VerificationResult, Verifier, VerifierStopHandle,
+    Viper,
 };
 
+/// Loads the verification cache given by `CACHE_PATH`, backed by the remote
+/// cache given by `REMOTE_CACHE_URL`, if any. New results are only uploaded to
+/// the remote cache with the `REMOTE_CACHE_TOKEN`.
+pub fn load_verification_cache() -> PersistentCache {
+    let cache = PersistentCache::load_cache(config::cache_path(), config::cache_max_size());
+    match config::remote_cache_url() {
+        Some(url) => cache.with_remote(RemoteCache::new(
+            &url,
+            Duration::from_secs(config::remote_cache_timeout()),
+            config::remote_cache_token(),
+        )),
+        None => cache,
+    }
+}
+
+/// The SMT wrapper is configured through environment variables, which are
+/// shared by all the workers of the process. A verifier that uses the wrapper
+/// holds this lock from the moment the variables are set until its
+/// verification is finished, because Carbon only starts the solver when it
+/// verifies a program.
+static SMT_WRAPPER_ENVIRONMENT: Mutex<()> = Mutex::new(());
+
+/// Allows another thread to abort the verification of a request.
+#[derive(Default)]
+pub(crate) struct CancellationToken {
+    cancelled: AtomicBool,
+    stop_handle: Mutex<Option<VerifierStopHandle>>,
+}
+
+impl CancellationToken {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Marks the request as cancelled and stops its verifier, if the
+    /// verification has already started.
+    pub(crate) fn cancel(&self, viper: &Viper) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        let stop_handle = self.stop_handle.lock().unwrap().clone();
+        if let Some(stop_handle) = stop_handle {
+            viper.attach_current_thread().stop_verifier(&stop_handle);
+        }
+    }
+
+    fn register_verifier(&self, verification_context: &VerificationContext, verifier: &Verifier) {
+        let stop_handle = verifier.stop_handle();
+        *self.stop_handle.lock().unwrap() = Some(stop_handle.clone());
+        // The request might have been cancelled before the handle was stored.
+        if self.is_cancelled() {
+            verification_context.stop_verifier(&stop_handle);
+        }
+    }
+
+    fn unregister_verifier(&self) {
+        *self.stop_handle.lock().unwrap() = None;
+    }
+}
+
+/// Verifies the request, or returns the reason why it cannot be verified with
+/// the configuration of the server.
 pub fn process_verification_request<'v, 't: 'v>(
+    verification_context: &'v VerificationContext<'t>,
+    request: VerificationRequest,
+    cache: impl Cache,
+) -> Result<viper::VerificationResult, String> {
+    process_cancellable_verification_request(
+        verification_context,
+        request,
+        cache,
+        &CancellationToken::default(),
+    )
+}
+
+/// Like `process_verification_request`, but the verification can be aborted
+/// with the given token. The result of a cancelled verification is not cached
+/// and should be discarded.
+pub(crate) fn process_cancellable_verification_request<'v, 't: 'v>(
     verification_context: &'v VerificationContext<'t>,
     mut request: VerificationRequest,
     cache: impl Cache,
-) -> viper::VerificationResult {
+    cancellation_token: &CancellationToken,
+) -> Result<viper::VerificationResult, String> {
     let ast_utils = verification_context.new_ast_utils();
 
     // Only for testing: Check that the normalization is reversible.
@@ -37,9 +124,9 @@ pub fn process_verification_request<'v, 't: 'v>(
     // Normalize the request before reaching the cache.
     let normalization_info = NormalizationInfo::normalize_program(&mut request.program);
 
-    let hash = request.get_hash();
+    let hash = request.get_fingerprint();
     info!(
-        "Verification request hash: {} - for program {}",
+        "Verification request fingerprint: {} - for program {}",
         hash,
         request.program.get_name()
     );
@@ -76,7 +163,7 @@ pub fn process_verification_request<'v, 't: 'v>(
                 let _ = build_or_dump_viper_program();
             });
         }
-        return viper::VerificationResult::Success;
+        return Ok(viper::VerificationResult::Success);
     }
 
     // Early return in case of cache hit
@@ -93,7 +180,7 @@ pub fn process_verification_request<'v, 't: 'v>(
                 });
             }
             normalization_info.denormalize_result(&mut result);
-            return result;
+            return Ok(result);
         }
     };
 
@@ -104,14 +191,21 @@ pub fn process_verification_request<'v, 't: 'v>(
         // Create a new verifier each time.
         // Workaround for https://github.com/viperproject/prusti-dev/issues/744
         let mut stopwatch = Stopwatch::start("prusti-server", "verifier startup");
-        let mut verifier =
-            new_viper_verifier(program_name, verification_context, request.backend_config);
+        let (mut verifier, smt_wrapper_environment) =
+            new_viper_verifier(program_name, verification_context, request.backend_config)?;
+
+        cancellation_token.register_verifier(verification_context, &verifier);
 
         stopwatch.start_next("verification");
         let mut result = verifier.verify(viper_program);
+        cancellation_token.unregister_verifier();
+        drop(smt_wrapper_environment);
 
         // Don't cache Java exceptions, which might be due to misconfigured paths.
-        if config::enable_cache() && !matches!(result, VerificationResult::JavaException(_)) {
+        if config::enable_cache()
+            && !matches!(result, VerificationResult::JavaException(_))
+            && !cancellation_token.is_cancelled()
+        {
             info!(
                 "Storing new cached result {:?} for program {}",
                 &result,
@@ -121,7 +215,7 @@ pub fn process_verification_request<'v, 't: 'v>(
         }
 
         normalization_info.denormalize_result(&mut result);
-        result
+        Ok(result)
     })
 }
 
@@ -132,11 +226,14 @@ fn dump_viper_program(ast_utils: &viper::AstUtils, program: viper::Program, prog
     report(namespace, filename, ast_utils.pretty_print(program));
 }
 
+/// Creates a verifier for the given configuration. If the verifier uses the
+/// SMT wrapper, the returned guard must be kept until the verification is
+/// finished. Fails if the configuration is not supported.
 fn new_viper_verifier<'v, 't: 'v>(
     program_name: &str,
     verification_context: &'v viper::VerificationContext<'t>,
     backend_config: ViperBackendConfig,
-) -> viper::Verifier<'v> {
+) -> Result<(viper::Verifier<'v>, Option<MutexGuard<'static, ()>>), String> {
     let mut verifier_args: Vec<String> = backend_config.verifier_args;
     let report_path: Option<PathBuf>;
     if config::dump_debug_info() {
@@ -167,8 +264,37 @@ fn new_viper_verifier<'v, 't: 'v>(
             verifier_args.extend(vec!["--disableTempDirectory".to_string()]);
         }
     }
+    let smt_solver_path = match backend_config.smt_solver {
+        SmtSolverKind::Z3 => config::smt_solver_path(),
+        SmtSolverKind::Cvc5 => config::cvc5_path(),
+    };
+    let mut smt_wrapper_environment = None;
     let (smt_solver, smt_manager) = if config::use_smt_wrapper() {
-        std::env::set_var("PRUSTI_ORIGINAL_SMT_SOLVER_PATH", config::smt_solver_path());
+        // These checks analyze the statistics and traces of Z3.
+        if backend_config.smt_solver != SmtSolverKind::Z3
+            && (config::smt_qi_bound_global().is_some()
+                || config::smt_qi_bound_global_kind().is_some()
+                || config::smt_qi_bound_trace().is_some()
+                || config::smt_qi_bound_trace_kind().is_some()
+                || config::smt_unique_triggers_bound().is_some()
+                || config::smt_unique_triggers_bound_total().is_some())
+        {
+            return Err(format!(
+                "the smt_qi_bound_* and smt_unique_triggers_bound* flags are only supported \
+                by Z3, not by {}",
+                backend_config.smt_solver
+            ));
+        }
+        smt_wrapper_environment = Some(
+            SMT_WRAPPER_ENVIRONMENT
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        std::env::set_var("PRUSTI_ORIGINAL_SMT_SOLVER_PATH", smt_solver_path);
+        std::env::set_var(
+            "PRUSTI_ORIGINAL_SMT_SOLVER_KIND",
+            backend_config.smt_solver.to_string(),
+        );
         let log_path = config::log_dir()
             .join("smt")
             .join(to_legal_file_name(program_name));
@@ -191,22 +317,27 @@ fn new_viper_verifier<'v, 't: 'v>(
         if config::log_smt_wrapper_interaction() {
             std::env::set_var("PRUSTI_LOG_SMT_INTERACTION", "true");
         }
+        if config::record_smt_sessions() {
+            std::env::set_var("PRUSTI_RECORD_SMT_SESSION", "true");
+        }
+        if let Some(bound) = config::smt_qi_bound_global() {
+            // We need to set the environment variable to reach our Z3 wrapper.
+            std::env::set_var("PRUSTI_SMT_QI_BOUND_GLOBAL", bound.to_string());
+        }
         (config::smt_solver_wrapper_path(), smt_manager)
     } else {
-        (config::smt_solver_path(), SmtManager::default())
+        (smt_solver_path, SmtManager::default())
     };
     let boogie_path = config::boogie_path();
-    if let Some(bound) = config::smt_qi_bound_global() {
-        // We need to set the environment variable to reach our Z3 wrapper.
-        std::env::set_var("PRUSTI_SMT_QI_BOUND_GLOBAL", bound.to_string());
-    }
 
-    verification_context.new_verifier(
+    let verifier = verification_context.new_verifier(
         backend_config.backend,
         verifier_args,
         report_path,
+        backend_config.smt_solver,
         smt_solver,
         boogie_path,
         smt_manager,
-    )
+    );
+    Ok((verifier, smt_wrapper_environment))
 }
@@ -0,0 +1,31 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// The identifier the server assigns to every verification request it
+/// receives. It can be used to cancel the request.
+pub type RequestId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RequestState {
+    /// The request waits for a free worker.
+    Queued,
+    /// A worker is verifying the request.
+    Running,
+    /// The request has been cancelled, but its worker has not stopped yet.
+    Cancelling,
+}
+
+/// An entry of the list returned by the `status` endpoint of the server.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RequestStatus {
+    pub id: RequestId,
+    pub program_name: String,
+    pub state: RequestState,
+    /// How long the request waited (or has been waiting so far) in the queue.
+    pub queued_ms: u64,
+    /// How long a worker has been verifying the request, if it has started.
+    pub running_ms: Option<u64>,
+}
@@ -4,22 +4,47 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{process_verification_request, VerificationRequest};
+use crate::{
+    load_verification_cache,
+    worker_pool::{WorkerPool, WorkerPoolError},
+    RequestId, ServerMessage, VerificationRequest,
+};
+use futures::{channel::mpsc::unbounded, future::join_all, Stream, StreamExt};
 use log::info;
 use prusti_common::{config, Stopwatch};
 use std::{
+    convert::Infallible,
     net::{Ipv4Addr, SocketAddr},
     sync::{mpsc, Arc, Mutex},
     thread,
 };
 use tokio::runtime::Builder;
-use viper::{PersistentCache, Viper};
-use warp::Filter;
+use viper::Viper;
+use warp::{http::StatusCode, Filter, Rejection, Reply};
 
 #[derive(Debug)]
 struct BincodeReject(bincode::Error);
 impl warp::reject::Reject for BincodeReject {}
 
+#[derive(Debug)]
+struct WorkerPoolReject(WorkerPoolError);
+impl warp::reject::Reject for WorkerPoolReject {}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<WorkerPoolReject>() {
+        Some(WorkerPoolReject(err)) => {
+            let status = match err {
+                WorkerPoolError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+                WorkerPoolError::Cancelled => StatusCode::CONFLICT,
+                WorkerPoolError::WorkerStopped => StatusCode::INTERNAL_SERVER_ERROR,
+                WorkerPoolError::Unsupported(_) => StatusCode::BAD_REQUEST,
+            };
+            Ok(warp::reply::with_status(err.to_string(), status))
+        }
+        None => Err(rejection),
+    }
+}
+
 pub fn start_server_on_port(port: u16) {
     listen_on_port_with_address_callback(port, move |address| {
         if port == 0 {
@@ -57,23 +82,24 @@ where
     ));
     stopwatch.finish();
 
-    let cache_data = PersistentCache::load_cache(config::cache_path());
+    let cache_data = load_verification_cache();
     let cache = Arc::new(Mutex::new(cache_data));
-    let build_verification_request_handler = |viper_arc: Arc<Viper>, cache| {
+    let worker_pool = Arc::new(WorkerPool::new(viper, cache.clone()));
+    let build_verification_request_handler = |worker_pool: Arc<WorkerPool>| {
         move |request: VerificationRequest| {
-            let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
-            let viper_thread = viper_arc.attach_current_thread();
-            stopwatch.finish();
-            process_verification_request(&viper_thread, request, &cache)
+            let worker_pool = worker_pool.clone();
+            async move {
+                worker_pool.verify(request).await.map_err(|err| {
+                    info!("verification request failed: {:?}", err);
+                    warp::reject::custom(WorkerPoolReject(err))
+                })
+            }
         }
     };
 
     let json_verify = warp::path!("json" / "verify")
         .and(warp::body::json())
-        .map(build_verification_request_handler(
-            viper.clone(),
-            cache.clone(),
-        ))
+        .and_then(build_verification_request_handler(worker_pool.clone()))
         .map(|response| warp::reply::json(&response));
 
     let bincode_verify = warp::path!("bincode" / "verify")
@@ -84,13 +110,51 @@ where
                 warp::reject::custom(BincodeReject(err))
             })
         })
-        .map(build_verification_request_handler(viper, cache.clone()))
+        .and_then(build_verification_request_handler(worker_pool.clone()))
         .map(|result| {
             warp::http::Response::new(
                 bincode::serialize(&result).expect("could not encode verification result"),
             )
         });
 
+    let stream_worker_pool = worker_pool.clone();
+    let json_verify_stream = warp::path!("json" / "verify_stream")
+        .and(warp::body::json())
+        .map(move |requests: Vec<VerificationRequest>| {
+            // Every request is a portfolio with a single configuration.
+            let portfolios = requests.into_iter().map(|request| vec![request]).collect();
+            warp::http::Response::new(warp::hyper::Body::wrap_stream(stream_verification_results(
+                stream_worker_pool.clone(),
+                portfolios,
+            )))
+        });
+
+    let status_worker_pool = worker_pool.clone();
+    let status = warp::get()
+        .and(warp::path!("status"))
+        .map(move || warp::reply::json(&status_worker_pool.status()));
+
+    let cancel_worker_pool = worker_pool.clone();
+    let cancel_request =
+        warp::delete()
+            .and(warp::path!("request" / RequestId))
+            .map(move |id: RequestId| {
+                if cancel_worker_pool.cancel(id) {
+                    warp::reply::with_status("Cancelled", StatusCode::OK)
+                } else {
+                    warp::reply::with_status("Unknown request", StatusCode::NOT_FOUND)
+                }
+            });
+
+    let json_verify_portfolio_stream = warp::path!("json" / "verify_portfolio_stream")
+        .and(warp::body::json())
+        .map(move |portfolios: Vec<Vec<VerificationRequest>>| {
+            warp::http::Response::new(warp::hyper::Body::wrap_stream(stream_verification_results(
+                worker_pool.clone(),
+                portfolios,
+            )))
+        });
+
     let save_cache = warp::post()
         .and(warp::path("save"))
         .and(warp::path::end())
@@ -99,11 +163,17 @@ where
             warp::reply::html("Saved")
         });
 
-    let endpoints = json_verify.or(bincode_verify).or(save_cache);
+    let endpoints = json_verify
+        .or(bincode_verify)
+        .or(json_verify_stream)
+        .or(json_verify_portfolio_stream)
+        .or(status)
+        .or(cancel_request)
+        .or(save_cache)
+        .recover(handle_rejection);
 
-    // Here we use a single thread because
-    // 1. Viper is not thread safe yet (Silicon issue #578), and
-    // 2. By default Silicon already uses as many cores as possible.
+    // A single thread is enough to serve the HTTP endpoints, because the
+    // verification itself happens on the threads of the worker pool.
     let runtime = Builder::new_current_thread()
         .thread_name("prusti-server")
         .enable_all()
@@ -124,3 +194,64 @@ where
 
     unreachable!("The server unexpectedly stopped.");
 }
+
+/// Verifies all portfolios of a batch concurrently on the worker pool and
+/// returns a stream of JSON lines, one `ServerMessage` per line, in the order
+/// in which the events happen. A portfolio consists of requests that verify
+/// the same program with different backend configurations; see
+/// `WorkerPool::verify_portfolio`.
+fn stream_verification_results(
+    worker_pool: Arc<WorkerPool>,
+    portfolios: Vec<Vec<VerificationRequest>>,
+) -> impl Stream<Item = Result<String, Infallible>> {
+    let (sender, receiver) = unbounded();
+    let tasks: Vec<_> = portfolios
+        .into_iter()
+        .enumerate()
+        .map(|(request_index, requests)| {
+            let worker_pool = worker_pool.clone();
+            let sender = sender.clone();
+            async move {
+                let program_name = requests[0].program.get_name().to_string();
+                let backend_configs: Vec<_> = requests
+                    .iter()
+                    .map(|request| request.backend_config.clone())
+                    .collect();
+                let start_sender = sender.clone();
+                let start_program_name = program_name.clone();
+                let on_start = move |request_id| {
+                    let _ = start_sender.unbounded_send(ServerMessage::Started {
+                        request_index,
+                        request_id,
+                        program_name: start_program_name.clone(),
+                    });
+                };
+                let message = match worker_pool.verify_portfolio(requests, on_start).await {
+                    Ok((winner, result)) => ServerMessage::Finished {
+                        request_index,
+                        program_name,
+                        backend_config: backend_configs[winner].clone(),
+                        result,
+                    },
+                    Err(err) => ServerMessage::Failed {
+                        request_index,
+                        program_name,
+                        reason: err.to_string(),
+                        timed_out: matches!(err, WorkerPoolError::Timeout),
+                    },
+                };
+                // The receiver is gone if the client disconnected.
+                let _ = sender.unbounded_send(message);
+            }
+        })
+        .collect();
+    tokio::spawn(async move {
+        join_all(tasks).await;
+        let _ = sender.unbounded_send(ServerMessage::Completed);
+    });
+    receiver.map(|message| {
+        let mut line = serde_json::to_string(&message).expect("could not encode server message");
+        line.push('\n');
+        Ok(line)
+    })
+}
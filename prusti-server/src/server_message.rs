@@ -0,0 +1,44 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{RequestId, ViperBackendConfig};
+use viper::VerificationResult;
+
+/// A progress event sent by the server while it verifies a batch of requests
+/// on the streaming endpoint. Each message is encoded as a single line of
+/// JSON. The `request_index` refers to the position of the request in the
+/// submitted batch.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ServerMessage {
+    /// A worker started verifying the request. The `request_id` can be used
+    /// to cancel the request. For a portfolio, this message is sent for every
+    /// configuration that is started.
+    Started {
+        request_index: usize,
+        request_id: RequestId,
+        program_name: String,
+    },
+    /// The verification of the request finished. The `backend_config` is the
+    /// configuration that produced the result, which is interesting when
+    /// several configurations were raced in a portfolio.
+    Finished {
+        request_index: usize,
+        program_name: String,
+        backend_config: ViperBackendConfig,
+        result: VerificationResult,
+    },
+    /// The server could not verify the request. `timed_out` is set if the
+    /// request did not finish within `server_request_timeout` seconds.
+    Failed {
+        request_index: usize,
+        program_name: String,
+        reason: String,
+        timed_out: bool,
+    },
+    /// All requests of the batch have been answered. This is the last message
+    /// of a stream.
+    Completed,
+}
@@ -4,12 +4,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use log::warn;
 use prusti_common::{config, vir::program::Program};
+use sha1::{Digest, Sha1};
 use std::{
-    collections::hash_map::DefaultHasher,
+    fmt,
     hash::{Hash, Hasher},
 };
-use viper::{self, VerificationBackend};
+use viper::{self, SmtSolverKind, UknownBackendError, UnknownSmtSolverError, VerificationBackend};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Hash)]
 pub struct VerificationRequest {
@@ -18,24 +20,145 @@ pub struct VerificationRequest {
 }
 
 impl VerificationRequest {
-    pub(crate) fn get_hash(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
+    /// A fingerprint of the request that is used as key of the verification
+    /// cache. Unlike `DefaultHasher`, it is stable across runs and Prusti
+    /// builds. It does not depend on the name of the program nor on the
+    /// order in which its items were encoded, so the same method verified
+    /// from a different crate layout still hits the cache.
+    pub(crate) fn get_fingerprint(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.backend_config.hash(&mut hasher);
+        match &self.program {
+            Program::Legacy(program) => {
+                hash_unordered(&program.domains, &mut hasher);
+                hash_unordered(&program.fields, &mut hasher);
+                hash_unordered(&program.builtin_methods, &mut hasher);
+                hash_unordered(&program.methods, &mut hasher);
+                hash_unordered(&program.functions, &mut hasher);
+                hash_unordered(&program.viper_predicates, &mut hasher);
+            }
+            Program::Low(program) => {
+                program.check_mode.hash(&mut hasher);
+                hash_unordered(&program.domains, &mut hasher);
+                hash_unordered(&program.predicates, &mut hasher);
+                hash_unordered(&program.functions, &mut hasher);
+                hash_unordered(&program.methods, &mut hasher);
+                hash_unordered(&program.procedures, &mut hasher);
+            }
+        }
         hasher.finish()
     }
 }
 
+/// Hashes the items independently of their order.
+fn hash_unordered<T: Hash>(items: &[T], state: &mut StableHasher) {
+    let mut item_hashes: Vec<u64> = items
+        .iter()
+        .map(|item| {
+            let mut hasher = StableHasher::default();
+            item.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    item_hashes.sort_unstable();
+    item_hashes.hash(state);
+}
+
+/// A `Hasher` backed by SHA-1, whose output does not change between runs.
+#[derive(Default)]
+struct StableHasher(Sha1);
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+/// Checks that the backends and the SMT solver selected by the configuration
+/// exist and can be used together. Returns a description of the problem
+/// otherwise, so that it can be reported before anything is verified.
+pub fn check_backend_configuration() -> Result<(), String> {
+    let smt_solver: SmtSolverKind = config::smt_solver_kind()
+        .parse()
+        .map_err(|err: UnknownSmtSolverError| err.to_string())?;
+    for (flag, backend) in [
+        ("viper_backend", config::viper_backend()),
+        (
+            "verify_specifications_backend",
+            config::verify_specifications_backend(),
+        ),
+    ] {
+        let backend: VerificationBackend = backend
+            .parse()
+            .map_err(|err: UknownBackendError| err.to_string())?;
+        if backend == VerificationBackend::Carbon && smt_solver != SmtSolverKind::Z3 {
+            return Err(format!(
+                "the Carbon backend selected by {} only supports Z3, but smt_solver_kind is {}",
+                flag, smt_solver
+            ));
+        }
+    }
+    // These checks analyze the statistics and traces of Z3.
+    let z3_only_flags = [
+        ("smt_qi_bound_global", config::smt_qi_bound_global()),
+        (
+            "smt_qi_bound_global_kind",
+            config::smt_qi_bound_global_kind(),
+        ),
+        ("smt_qi_bound_trace", config::smt_qi_bound_trace()),
+        ("smt_qi_bound_trace_kind", config::smt_qi_bound_trace_kind()),
+        (
+            "smt_unique_triggers_bound",
+            config::smt_unique_triggers_bound(),
+        ),
+        (
+            "smt_unique_triggers_bound_total",
+            config::smt_unique_triggers_bound_total(),
+        ),
+    ];
+    if smt_solver != SmtSolverKind::Z3 {
+        if let Some((flag, _)) = z3_only_flags.iter().find(|(_, value)| value.is_some()) {
+            return Err(format!(
+                "{} is only supported by Z3, but smt_solver_kind is {}",
+                flag, smt_solver
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The SMT solver selected by the configuration, which has been validated by
+/// `check_backend_configuration`.
+fn configured_smt_solver() -> SmtSolverKind {
+    config::smt_solver_kind()
+        .parse()
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
 /// The configuration for the viper backend, (i.e. verifier).
-/// Expresses which backend (silicon or carbon) should be used, and provides command-line arguments
-/// to the viper verifier.
+/// Expresses which backend (silicon or carbon) and which SMT solver should be used, and provides
+/// command-line arguments to the viper verifier.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Eq, PartialEq, Hash)]
 pub struct ViperBackendConfig {
     pub backend: VerificationBackend,
+    pub smt_solver: SmtSolverKind,
+    /// The random seed of the SMT solver, if it is not the default one.
+    pub random_seed: Option<u32>,
     pub verifier_args: Vec<String>,
 }
 
 impl ViperBackendConfig {
     pub fn new(backend: VerificationBackend) -> Self {
+        Self::with_random_seed(backend, None)
+    }
+
+    pub fn with_random_seed(backend: VerificationBackend, random_seed: Option<u32>) -> Self {
+        let smt_solver = configured_smt_solver();
         let mut verifier_args = config::extra_verifier_args();
         match backend {
             VerificationBackend::Silicon => {
@@ -54,17 +177,9 @@ impl ViperBackendConfig {
                 verifier_args.extend(vec![
                     "--assertTimeout".to_string(),
                     config::assert_timeout().to_string(),
-                    "--proverConfigArgs".to_string(),
-                    // model.partial changes the default case of functions in counterexamples
-                    // to #unspecified
-                    format!(
-                        "smt.qi.eager_threshold={} model.partial={}",
-                        config::smt_qi_eager_threshold(),
-                        config::counterexample()
-                    ),
-                    "--logLevel".to_string(),
-                    "ERROR".to_string(),
                 ]);
+                verifier_args.extend(prover_config_args(smt_solver, random_seed));
+                verifier_args.extend(vec!["--logLevel".to_string(), "ERROR".to_string()]);
 
                 if let Some(check_timeout) = config::check_timeout() {
                     verifier_args.push("--checkTimeout".to_string());
@@ -72,12 +187,100 @@ impl ViperBackendConfig {
                 }
             }
             VerificationBackend::Carbon => {
+                assert_eq!(
+                    smt_solver,
+                    SmtSolverKind::Z3,
+                    "The Carbon backend does not support the SMT solver {}",
+                    smt_solver
+                );
                 verifier_args.extend(vec!["--disableAllocEncoding".to_string()]);
+                if let Some(seed) = random_seed {
+                    verifier_args.extend(vec![
+                        "--boogieOpt".to_string(),
+                        format!("/randomSeed:{}", seed),
+                    ]);
+                }
             }
         }
         Self {
             backend,
+            smt_solver,
+            random_seed,
             verifier_args,
         }
     }
+
+    /// The configurations with which a program should be verified: just
+    /// `backend` by default, or the whole portfolio if the `portfolio` flag is
+    /// enabled. The configuration of `backend` always comes first.
+    pub fn portfolio(backend: VerificationBackend) -> Vec<Self> {
+        if !config::portfolio() {
+            return vec![Self::new(backend)];
+        }
+        let mut backends = vec![backend];
+        let other_backend = match backend {
+            VerificationBackend::Silicon => VerificationBackend::Carbon,
+            VerificationBackend::Carbon => VerificationBackend::Silicon,
+        };
+        // Carbon needs Boogie, which uses Z3.
+        let smt_solver = configured_smt_solver();
+        if other_backend == VerificationBackend::Silicon
+            || (config::boogie_path().is_some() && smt_solver == SmtSolverKind::Z3)
+        {
+            backends.push(other_backend);
+        } else {
+            warn!("Carbon is not available, so the portfolio only uses Silicon");
+        }
+        let seeds: Vec<_> = std::iter::once(None)
+            .chain(config::portfolio_seeds().into_iter().map(Some))
+            .collect();
+        backends
+            .into_iter()
+            .flat_map(|backend| {
+                seeds
+                    .iter()
+                    .map(move |&seed| Self::with_random_seed(backend, seed))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ViperBackendConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.backend)?;
+        if self.backend == VerificationBackend::Silicon {
+            write!(f, " with {}", self.smt_solver)?;
+        }
+        if let Some(seed) = self.random_seed {
+            write!(f, " (seed {})", seed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Translates Prusti's solver options to the options of the given solver.
+fn prover_config_args(smt_solver: SmtSolverKind, random_seed: Option<u32>) -> Vec<String> {
+    match smt_solver {
+        SmtSolverKind::Z3 => {
+            // model.partial changes the default case of functions in counterexamples
+            // to #unspecified
+            let mut prover_config = format!(
+                "smt.qi.eager_threshold={} model.partial={}",
+                config::smt_qi_eager_threshold(),
+                config::counterexample()
+            );
+            if let Some(seed) = random_seed {
+                prover_config.push_str(&format!(
+                    " smt.random_seed={} sat.random_seed={}",
+                    seed, seed
+                ));
+            }
+            vec!["--proverConfigArgs".to_string(), prover_config]
+        }
+        // cvc5 has no equivalent of the Z3 options above.
+        SmtSolverKind::Cvc5 => match random_seed {
+            Some(seed) => vec!["--proverArgs".to_string(), format!("--seed={}", seed)],
+            None => vec![],
+        },
+    }
 }
@@ -0,0 +1,463 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    process_verification::{process_cancellable_verification_request, CancellationToken},
+    RequestId, RequestState, RequestStatus, VerificationRequest,
+};
+use futures::{stream::FuturesUnordered, StreamExt};
+use log::{error, info, warn};
+use prusti_common::{config, Stopwatch};
+use std::{
+    collections::HashMap,
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+use viper::{PersistentCache, VerificationBackend, VerificationResult, Viper};
+
+/// A verification request waiting in the queue of the worker pool, together
+/// with the channel on which its result should be sent.
+struct Job {
+    id: RequestId,
+    request: VerificationRequest,
+    result_sender: oneshot::Sender<Result<VerificationResult, WorkerPoolError>>,
+    on_start: StartCallback,
+}
+
+/// Called by the worker right before it starts verifying a request.
+pub(crate) type StartCallback = Box<dyn FnOnce(RequestId) + Send>;
+
+type ResultReceiver = oneshot::Receiver<Result<VerificationResult, WorkerPoolError>>;
+
+/// The bookkeeping of a request that is queued or running.
+struct RequestEntry {
+    program_name: String,
+    submitted: Instant,
+    started: Option<Instant>,
+    cancellation_token: Arc<CancellationToken>,
+}
+
+type RequestMap = Mutex<HashMap<RequestId, RequestEntry>>;
+
+/// The reason why the worker pool could not produce a verification result.
+#[derive(Debug)]
+pub(crate) enum WorkerPoolError {
+    /// The request did not finish within `server_request_timeout` seconds.
+    Timeout,
+    /// The request was cancelled.
+    Cancelled,
+    /// The worker that took the request failed without producing a result.
+    WorkerStopped,
+    /// The request cannot be verified with the configuration of the server.
+    Unsupported(String),
+}
+
+impl fmt::Display for WorkerPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkerPoolError::Timeout => write!(f, "verification request timed out"),
+            WorkerPoolError::Cancelled => write!(f, "verification request was cancelled"),
+            WorkerPoolError::WorkerStopped => {
+                write!(f, "verification worker stopped unexpectedly")
+            }
+            WorkerPoolError::Unsupported(reason) => {
+                write!(f, "verification request is not supported: {}", reason)
+            }
+        }
+    }
+}
+
+/// A fixed number of worker threads that process verification requests from a
+/// shared queue. Each worker can keep its own attachment to the JVM, which
+/// avoids re-attaching a thread for every request.
+pub(crate) struct WorkerPool {
+    viper: Arc<Viper>,
+    job_sender: Mutex<mpsc::Sender<Job>>,
+    requests: Arc<RequestMap>,
+    next_request_id: AtomicU64,
+    timeout: Option<Duration>,
+}
+
+impl WorkerPool {
+    /// Spawns `server_max_concurrency` workers (by default one per logical
+    /// core, and only one if the SMT wrapper is used). Only the first `server_max_stored_verifiers` of them keep their
+    /// JVM attachment between requests; the others attach for every request.
+    pub(crate) fn new(viper: Arc<Viper>, cache: Arc<Mutex<PersistentCache>>) -> Self {
+        let mut max_concurrency = config::server_max_concurrency()
+            .unwrap_or_else(num_cpus::get)
+            .max(1);
+        if config::use_smt_wrapper() && max_concurrency > 1 {
+            // The verifiers that use the SMT wrapper configure it through the
+            // environment of the process, so they run one at a time anyway.
+            warn!(
+                "use_smt_wrapper is enabled, so the server verifies one request at a time \
+                instead of {}",
+                max_concurrency
+            );
+            max_concurrency = 1;
+        }
+        let max_stored_verifiers = config::server_max_stored_verifiers().unwrap_or(max_concurrency);
+        if max_stored_verifiers < max_concurrency {
+            warn!(
+                "server_max_stored_verifiers ({}) is lower than server_max_concurrency ({}); \
+                some workers will attach to the JVM for every request",
+                max_stored_verifiers, max_concurrency
+            );
+        }
+        info!(
+            "Starting {} verification workers ({} with stored verifiers)",
+            max_concurrency,
+            max_stored_verifiers.min(max_concurrency)
+        );
+
+        let (job_sender, job_receiver) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let requests = Arc::new(Mutex::new(HashMap::new()));
+        for worker_id in 0..max_concurrency {
+            let worker = Worker {
+                viper: viper.clone(),
+                cache: cache.clone(),
+                job_receiver: job_receiver.clone(),
+                requests: requests.clone(),
+            };
+            let stores_verifier = worker_id < max_stored_verifiers;
+            thread::Builder::new()
+                .name(format!("prusti-server-worker-{}", worker_id))
+                .spawn(move || worker.run(stores_verifier))
+                .expect("failed to spawn verification worker");
+        }
+
+        WorkerPool {
+            viper,
+            job_sender: Mutex::new(job_sender),
+            requests,
+            next_request_id: AtomicU64::new(0),
+            timeout: config::server_request_timeout().map(Duration::from_secs),
+        }
+    }
+
+    /// Queues the request and waits until a worker has verified it.
+    pub(crate) async fn verify(
+        &self,
+        request: VerificationRequest,
+    ) -> Result<VerificationResult, WorkerPoolError> {
+        self.verify_notifying(request, Box::new(|_| ())).await
+    }
+
+    /// Like `verify`, but calls `on_start` once a worker takes the request
+    /// from the queue.
+    pub(crate) async fn verify_notifying(
+        &self,
+        request: VerificationRequest,
+        on_start: StartCallback,
+    ) -> Result<VerificationResult, WorkerPoolError> {
+        let (id, result_receiver) = self.enqueue(request, on_start)?;
+        self.wait(id, result_receiver).await
+    }
+
+    /// Verifies the same program with several backend configurations at the
+    /// same time. Since the encoder produces one program per procedure, the
+    /// configurations are raced per procedure. The first definitive result
+    /// wins and the configurations that are still queued or running are
+    /// cancelled:
+    ///  * a successful verification is always definitive;
+    ///  * a verification failure is definitive once all configurations of the
+    ///    same backend (i.e. all its seeds) have failed, because a different
+    ///    seed cannot turn a genuine error into a success.
+    ///
+    /// Otherwise, the result of the first configuration that produced a
+    /// result (in the order of `requests`) is returned, preferring
+    /// verification failures over Java exceptions and timeouts.
+    ///
+    /// Returns the index of the request that produced the result. `on_start`
+    /// is called whenever a worker takes one of the requests from the queue.
+    pub(crate) async fn verify_portfolio(
+        &self,
+        requests: Vec<VerificationRequest>,
+        on_start: impl Fn(RequestId) + Clone + Send + 'static,
+    ) -> Result<(usize, VerificationResult), WorkerPoolError> {
+        assert!(!requests.is_empty(), "the portfolio is empty");
+        let backends: Vec<_> = requests
+            .iter()
+            .map(|request| request.backend_config.backend)
+            .collect();
+        let mut pending = FuturesUnordered::new();
+        let mut ids = Vec::with_capacity(requests.len());
+        for (index, request) in requests.into_iter().enumerate() {
+            let (id, result_receiver) = match self.enqueue(request, Box::new(on_start.clone())) {
+                Ok(queued) => queued,
+                Err(err) => {
+                    self.cancel_all(&ids);
+                    return Err(err);
+                }
+            };
+            ids.push(id);
+            pending.push(async move { (index, self.wait(id, result_receiver).await) });
+        }
+        let mut results: Vec<Option<Result<VerificationResult, WorkerPoolError>>> =
+            (0..ids.len()).map(|_| None).collect();
+        while let Some((index, result)) = pending.next().await {
+            let is_success = matches!(result, Ok(VerificationResult::Success));
+            results[index] = Some(result);
+            let winner = if is_success {
+                Some(index)
+            } else {
+                definitive_failure(&backends, &results, index)
+            };
+            if let Some(winner) = winner {
+                drop(pending);
+                let others: Vec<_> = ids
+                    .iter()
+                    .zip(&results)
+                    .filter(|(_, result)| result.is_none())
+                    .map(|(&id, _)| id)
+                    .collect();
+                self.cancel_all(&others);
+                let result = results.swap_remove(winner).unwrap();
+                return result.map(|result| (winner, result));
+            }
+        }
+        let mut results: Vec<_> = results.into_iter().map(Option::unwrap).collect();
+        // Java exceptions might be caused by the configuration, e.g. a
+        // missing Boogie installation.
+        let is_conclusive = |result: &Result<VerificationResult, WorkerPoolError>| match result {
+            Ok(VerificationResult::JavaException(_)) | Err(_) => false,
+            Ok(_) => true,
+        };
+        let preferred = results
+            .iter()
+            .position(is_conclusive)
+            .or_else(|| results.iter().position(Result::is_ok))
+            .unwrap_or(0);
+        results
+            .swap_remove(preferred)
+            .map(|result| (preferred, result))
+    }
+
+    /// Puts the request into the queue of the workers.
+    fn enqueue(
+        &self,
+        request: VerificationRequest,
+        on_start: StartCallback,
+    ) -> Result<(RequestId, ResultReceiver), WorkerPoolError> {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        self.requests.lock().unwrap().insert(
+            id,
+            RequestEntry {
+                program_name: request.program.get_name().to_string(),
+                submitted: Instant::now(),
+                started: None,
+                cancellation_token: Default::default(),
+            },
+        );
+        let (result_sender, result_receiver) = oneshot::channel();
+        let job = Job {
+            id,
+            request,
+            result_sender,
+            on_start,
+        };
+        if self.job_sender.lock().unwrap().send(job).is_err() {
+            self.requests.lock().unwrap().remove(&id);
+            return Err(WorkerPoolError::WorkerStopped);
+        }
+        Ok((id, result_receiver))
+    }
+
+    /// Waits until the queued request has been verified.
+    async fn wait(
+        &self,
+        id: RequestId,
+        result_receiver: ResultReceiver,
+    ) -> Result<VerificationResult, WorkerPoolError> {
+        let result = if let Some(timeout) = self.timeout {
+            match tokio::time::timeout(timeout, result_receiver).await {
+                Ok(result) => result,
+                Err(_) => {
+                    info!("Verification request {} timed out", id);
+                    self.cancel(id);
+                    return Err(WorkerPoolError::Timeout);
+                }
+            }
+        } else {
+            result_receiver.await
+        };
+        result.unwrap_or(Err(WorkerPoolError::WorkerStopped))
+    }
+
+    fn cancel_all(&self, ids: &[RequestId]) {
+        for &id in ids {
+            self.cancel(id);
+        }
+    }
+
+    /// Cancels a queued or running request. Returns `false` if there is no
+    /// such request.
+    pub(crate) fn cancel(&self, id: RequestId) -> bool {
+        let cancellation_token = match self.requests.lock().unwrap().get(&id) {
+            Some(entry) => entry.cancellation_token.clone(),
+            None => return false,
+        };
+        info!("Cancelling verification request {}", id);
+        // The lock is not held here, because stopping the verifier calls into
+        // the JVM.
+        cancellation_token.cancel(&self.viper);
+        true
+    }
+
+    /// The status of all queued and running requests, ordered by their id.
+    pub(crate) fn status(&self) -> Vec<RequestStatus> {
+        let now = Instant::now();
+        let mut status: Vec<_> = self
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&id, entry)| {
+                let state = if entry.cancellation_token.is_cancelled() {
+                    RequestState::Cancelling
+                } else if entry.started.is_some() {
+                    RequestState::Running
+                } else {
+                    RequestState::Queued
+                };
+                let queued_until = entry.started.unwrap_or(now);
+                RequestStatus {
+                    id,
+                    program_name: entry.program_name.clone(),
+                    state,
+                    queued_ms: as_millis(queued_until - entry.submitted),
+                    running_ms: entry.started.map(|started| as_millis(now - started)),
+                }
+            })
+            .collect();
+        status.sort_by_key(|request| request.id);
+        status
+    }
+}
+
+/// If the result of the request at `index` completes a verification failure
+/// that all the configurations of its backend agree on, returns the index of
+/// the first of these configurations.
+fn definitive_failure(
+    backends: &[VerificationBackend],
+    results: &[Option<Result<VerificationResult, WorkerPoolError>>],
+    index: usize,
+) -> Option<usize> {
+    let mut same_backend = backends
+        .iter()
+        .zip(results)
+        .enumerate()
+        .filter(|(_, (&backend, _))| backend == backends[index]);
+    let all_failed = same_backend
+        .clone()
+        .all(|(_, (_, result))| matches!(result, Some(Ok(VerificationResult::Failure(_)))));
+    if all_failed {
+        same_backend.next().map(|(first, _)| first)
+    } else {
+        None
+    }
+}
+
+fn as_millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+struct Worker {
+    viper: Arc<Viper>,
+    cache: Arc<Mutex<PersistentCache>>,
+    job_receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+    requests: Arc<RequestMap>,
+}
+
+impl Worker {
+    fn run(&self, stores_verifier: bool) {
+        let stored_context = if stores_verifier {
+            let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
+            let context = self.viper.attach_current_thread();
+            stopwatch.finish();
+            Some(context)
+        } else {
+            None
+        };
+        loop {
+            // The lock is released as soon as a job has been taken from the queue.
+            let job = self.job_receiver.lock().unwrap().recv();
+            let Ok(job) = job else {
+                // The pool has been dropped.
+                break;
+            };
+            let Some(cancellation_token) = self.start(job.id) else {
+                // The request does not exist anymore.
+                continue;
+            };
+            if cancellation_token.is_cancelled() || job.result_sender.is_closed() {
+                // The request was cancelled or the client stopped waiting
+                // (e.g. it timed out) while the request was still in the queue.
+                info!(
+                    "Skipping abandoned verification request {} for program {}",
+                    job.id,
+                    job.request.program.get_name()
+                );
+                self.requests.lock().unwrap().remove(&job.id);
+                let _ = job.result_sender.send(Err(WorkerPoolError::Cancelled));
+                continue;
+            }
+            (job.on_start)(job.id);
+            // A cancelled verification usually ends with a Java exception, on
+            // which the verifier panics. The worker must survive that.
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                if let Some(context) = &stored_context {
+                    process_cancellable_verification_request(
+                        context,
+                        job.request,
+                        &self.cache,
+                        &cancellation_token,
+                    )
+                } else {
+                    let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
+                    let context = self.viper.attach_current_thread();
+                    stopwatch.finish();
+                    process_cancellable_verification_request(
+                        &context,
+                        job.request,
+                        &self.cache,
+                        &cancellation_token,
+                    )
+                }
+            }));
+            self.requests.lock().unwrap().remove(&job.id);
+            let result = if cancellation_token.is_cancelled() {
+                Err(WorkerPoolError::Cancelled)
+            } else {
+                match result {
+                    Ok(result) => result.map_err(WorkerPoolError::Unsupported),
+                    Err(_) => {
+                        error!("Verification request {} panicked", job.id);
+                        Err(WorkerPoolError::WorkerStopped)
+                    }
+                }
+            };
+            // The receiver is gone if the request timed out in the meantime.
+            let _ = job.result_sender.send(result);
+        }
+    }
+
+    /// Marks the request as running and returns its cancellation token.
+    fn start(&self, id: RequestId) -> Option<Arc<CancellationToken>> {
+        let mut requests = self.requests.lock().unwrap();
+        let entry = requests.get_mut(&id)?;
+        entry.started = Some(Instant::now());
+        Some(entry.cancellation_token.clone())
+    }
+}
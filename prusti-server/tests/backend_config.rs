@@ -0,0 +1,49 @@
+use prusti_server::{check_backend_configuration, ViperBackendConfig};
+use viper::{SmtSolverKind, VerificationBackend};
+
+#[test]
+fn parse_smt_solver_kind() {
+    assert_eq!("Z3".parse::<SmtSolverKind>().unwrap(), SmtSolverKind::Z3);
+    assert_eq!("z3".parse::<SmtSolverKind>().unwrap(), SmtSolverKind::Z3);
+    assert_eq!(
+        "cvc5".parse::<SmtSolverKind>().unwrap(),
+        SmtSolverKind::Cvc5
+    );
+    assert_eq!(
+        "CVC5".parse::<SmtSolverKind>().unwrap(),
+        SmtSolverKind::Cvc5
+    );
+    let error = "cvc4".parse::<SmtSolverKind>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid SMT solver: 'cvc4'. Allowed values are 'Z3' and 'cvc5'"
+    );
+}
+
+#[test]
+fn default_configuration_is_valid() {
+    assert_eq!(check_backend_configuration(), Ok(()));
+}
+
+#[test]
+fn z3_prover_config_args() {
+    let config = ViperBackendConfig::with_random_seed(VerificationBackend::Silicon, Some(7));
+    assert_eq!(config.smt_solver, SmtSolverKind::Z3);
+    let position = config
+        .verifier_args
+        .iter()
+        .position(|arg| arg == "--proverConfigArgs")
+        .expect("the Z3 options are missing");
+    let prover_config = &config.verifier_args[position + 1];
+    assert!(prover_config.starts_with("smt.qi.eager_threshold="));
+    assert!(prover_config.ends_with(" smt.random_seed=7 sat.random_seed=7"));
+    assert!(!config.verifier_args.contains(&"--proverArgs".to_string()));
+
+    let config = ViperBackendConfig::new(VerificationBackend::Silicon);
+    let position = config
+        .verifier_args
+        .iter()
+        .position(|arg| arg == "--proverConfigArgs")
+        .expect("the Z3 options are missing");
+    assert!(!config.verifier_args[position + 1].contains("random_seed"));
+}
@@ -1,8 +1,8 @@
 use lazy_static::lazy_static;
 use prusti_common::vir::*;
 use prusti_server::{
-    spawn_server_thread, tokio::runtime::Builder, PrustiClient, VerificationRequest,
-    ViperBackendConfig,
+    spawn_server_thread, tokio::runtime::Builder, PrustiClient, RequestId, RequestState,
+    ServerMessage, VerificationRequest, ViperBackendConfig,
 };
 use viper::VerificationResult;
 
@@ -42,12 +42,277 @@ fn empty_program() {
     }
 }
 
+#[test]
+fn concurrent_requests() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| process_program(|_| ())))
+        .collect();
+
+    for handle in handles {
+        match handle.join().expect("request thread panicked") {
+            VerificationResult::Success => {}
+            other => panic!(
+                "concurrent empty program not verified successfully, instead found {:?}",
+                other
+            ),
+        }
+    }
+}
+
+#[test]
+fn streamed_requests() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let requests = vec![build_request(|_| ()), build_request(|_| ())];
+
+    let mut finished = vec![false; requests.len()];
+    let mut completed = false;
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.verify_stream(requests, |message| match message {
+            ServerMessage::Started { .. } => assert!(!completed),
+            ServerMessage::Finished {
+                request_index,
+                result,
+                ..
+            } => {
+                assert_eq!(result, VerificationResult::Success);
+                finished[request_index] = true;
+            }
+            ServerMessage::Failed { reason, .. } => panic!("streamed request failed: {}", reason),
+            ServerMessage::Completed => completed = true,
+        }))
+        .expect("Verification request failed");
+
+    assert!(completed, "stream ended without a completion message");
+    assert!(finished.iter().all(|&finished| finished));
+}
+
+#[test]
+fn portfolio_request() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let backend = prusti_common::config::viper_backend().parse().unwrap();
+    let portfolio: Vec<_> = [None, Some(1), Some(2)]
+        .into_iter()
+        .map(|seed| VerificationRequest {
+            backend_config: ViperBackendConfig::with_random_seed(backend, seed),
+            ..build_request(|_| ())
+        })
+        .collect();
+    let backend_configs: Vec<_> = portfolio
+        .iter()
+        .map(|request| request.backend_config.clone())
+        .collect();
+
+    let mut winner = None;
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(
+            client.verify_portfolio_stream(vec![portfolio], |message| match message {
+                ServerMessage::Finished {
+                    request_index,
+                    backend_config,
+                    result,
+                    ..
+                } => {
+                    assert_eq!(request_index, 0);
+                    assert_eq!(result, VerificationResult::Success);
+                    winner = Some(backend_config);
+                }
+                ServerMessage::Failed { reason, .. } => {
+                    panic!("portfolio request failed: {}", reason)
+                }
+                ServerMessage::Started { .. } | ServerMessage::Completed => {}
+            }),
+        )
+        .expect("Verification request failed");
+
+    let winner = winner.expect("the portfolio produced no result");
+    assert!(backend_configs.contains(&winner));
+}
+
+#[test]
+fn portfolio_request_with_failure() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let backend = prusti_common::config::viper_backend().parse().unwrap();
+    let portfolio: Vec<_> = [None, Some(1), Some(2)]
+        .into_iter()
+        .map(|seed| VerificationRequest {
+            backend_config: ViperBackendConfig::with_random_seed(backend, seed),
+            ..build_request(add_failing_function)
+        })
+        .collect();
+
+    let mut result = None;
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(
+            client.verify_portfolio_stream(vec![portfolio], |message| match message {
+                ServerMessage::Finished {
+                    result: finished, ..
+                } => result = Some(finished),
+                ServerMessage::Failed { reason, .. } => {
+                    panic!("portfolio request failed: {}", reason)
+                }
+                ServerMessage::Started { .. } | ServerMessage::Completed => {}
+            }),
+        )
+        .expect("Verification request failed");
+
+    match result.expect("the portfolio produced no result") {
+        VerificationResult::Failure(errors) => assert_eq!(errors.len(), 1),
+        other => panic!(
+            "failing program not reported as a failure, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn status_and_cancel_unknown_request() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime");
+
+    let status = runtime
+        .block_on(client.status())
+        .expect("Status request failed");
+    assert!(status.iter().all(|request| request.id != RequestId::MAX));
+    assert!(
+        runtime.block_on(client.cancel(RequestId::MAX)).is_err(),
+        "cancelling an unknown request should fail"
+    );
+}
+
+#[test]
+fn cancel_running_request() {
+    let (started_sender, started_receiver) = std::sync::mpsc::channel();
+    let stream_thread = std::thread::spawn(move || {
+        let client =
+            PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+        let mut cancelled = false;
+        Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to construct Tokio runtime")
+            .block_on(
+                client.verify_stream(
+                    vec![build_request(add_slow_function)],
+                    |message| match message {
+                        ServerMessage::Started { request_id, .. } => {
+                            started_sender.send(request_id).unwrap()
+                        }
+                        ServerMessage::Finished { result, .. } => {
+                            panic!("the cancelled request finished with {:?}", result)
+                        }
+                        ServerMessage::Failed { timed_out, .. } => {
+                            assert!(!timed_out);
+                            cancelled = true;
+                        }
+                        ServerMessage::Completed => {}
+                    },
+                ),
+            )
+            .expect("Verification request failed");
+        cancelled
+    });
+
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime");
+    let id = started_receiver
+        .recv()
+        .expect("the request was never started");
+    let status = runtime
+        .block_on(client.status())
+        .expect("Status request failed");
+    let request = status
+        .iter()
+        .find(|request| request.id == id)
+        .expect("the started request is not listed");
+    assert_eq!(request.state, RequestState::Running);
+    runtime
+        .block_on(client.cancel(id))
+        .expect("cancelling a running request should succeed");
+
+    assert!(
+        stream_thread.join().expect("request thread panicked"),
+        "the cancelled request was not reported as failed"
+    );
+    let status = runtime
+        .block_on(client.status())
+        .expect("Status request failed");
+    assert!(status.iter().all(|request| request.id != id));
+}
+
 fn process_program<F>(configure: F) -> VerificationResult
 where
     F: FnOnce(&mut Program),
 {
     let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let request = build_request(configure);
 
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.verify(request))
+        .expect("Verification request failed")
+}
+
+/// Adds a function whose postcondition does not hold.
+fn add_failing_function(program: &mut Program) {
+    program.functions.push(Function {
+        name: "wrong".to_string(),
+        formal_args: vec![],
+        return_type: Type::Bool,
+        pres: vec![],
+        posts: vec![false.into()],
+        body: Some(true.into()),
+    });
+}
+
+/// Adds a function whose nonlinear postcondition (Fermat's last theorem for
+/// cubes) keeps the solver busy for much longer than the test needs.
+fn add_slow_function(program: &mut Program) {
+    let args: Vec<_> = ["x", "y", "z"]
+        .into_iter()
+        .map(|name| LocalVar::new(name, Type::Int))
+        .collect();
+    let cube = |var: &LocalVar| {
+        Expr::mul(
+            Expr::local(var.clone()),
+            Expr::mul(Expr::local(var.clone()), Expr::local(var.clone())),
+        )
+    };
+    let pres = args
+        .iter()
+        .map(|var| Expr::gt_cmp(Expr::local(var.clone()), 0.into()))
+        .collect();
+    let post = Expr::ne_cmp(Expr::add(cube(&args[0]), cube(&args[1])), cube(&args[2]));
+    program.functions.push(Function {
+        name: "fermat".to_string(),
+        formal_args: args,
+        return_type: Type::Bool,
+        pres,
+        posts: vec![post],
+        body: Some(true.into()),
+    });
+}
+
+fn build_request<F>(configure: F) -> VerificationRequest
+where
+    F: FnOnce(&mut Program),
+{
     let mut program = Program {
         name: "dummy".to_string(),
         domains: vec![],
@@ -59,17 +324,10 @@ where
     };
     configure(&mut program);
 
-    let request = VerificationRequest {
+    VerificationRequest {
         program: prusti_common::vir::program::Program::Legacy(program),
         backend_config: ViperBackendConfig::new(
             prusti_common::config::viper_backend().parse().unwrap(),
         ),
-    };
-
-    Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .expect("failed to construct Tokio runtime")
-        .block_on(client.verify(request))
-        .expect("Verification request failed")
+    }
 }
@@ -0,0 +1,35 @@
+//! The configuration is read only once per process, so the tests that change
+//! it live in a test binary of their own, with a single test.
+
+use prusti_server::{check_backend_configuration, ViperBackendConfig};
+use viper::{SmtSolverKind, VerificationBackend};
+
+#[test]
+fn cvc5_configuration() {
+    std::env::set_var("PRUSTI_SMT_SOLVER_KIND", "cvc5");
+    std::env::set_var("PRUSTI_VERIFY_SPECIFICATIONS_BACKEND", "Carbon");
+
+    assert_eq!(
+        check_backend_configuration(),
+        Err(
+            "the Carbon backend selected by verify_specifications_backend only supports Z3, \
+             but smt_solver_kind is cvc5"
+                .to_string()
+        )
+    );
+
+    let config = ViperBackendConfig::with_random_seed(VerificationBackend::Silicon, Some(3));
+    assert_eq!(config.smt_solver, SmtSolverKind::Cvc5);
+    assert!(!config
+        .verifier_args
+        .contains(&"--proverConfigArgs".to_string()));
+    let position = config
+        .verifier_args
+        .iter()
+        .position(|arg| arg == "--proverArgs")
+        .expect("the cvc5 options are missing");
+    assert_eq!(config.verifier_args[position + 1], "--seed=3");
+
+    let config = ViperBackendConfig::new(VerificationBackend::Silicon);
+    assert!(!config.verifier_args.contains(&"--proverArgs".to_string()));
+}
@@ -0,0 +1,77 @@
+use prusti_server::spawn_cache_server_thread;
+use std::time::Duration;
+use viper::{Cache, PersistentCache, RemoteCache, VerificationResult};
+
+fn consistency_error(message: &str) -> VerificationResult {
+    VerificationResult::ConsistencyErrors(vec![message.to_string()])
+}
+
+const UPLOAD_TOKEN: &str = "secret";
+
+fn remote_cache_with_tokens(
+    server_dir: &tempfile::TempDir,
+    server_token: Option<&str>,
+    client_token: Option<&str>,
+) -> RemoteCache {
+    let server_cache = PersistentCache::load_cache(server_dir.path().join("cache"), 1024);
+    let address = spawn_cache_server_thread(server_cache, server_token.map(str::to_string));
+    RemoteCache::new(
+        &format!("http://{}", address),
+        Duration::from_secs(10),
+        client_token.map(str::to_string),
+    )
+}
+
+fn remote_cache(server_dir: &tempfile::TempDir) -> RemoteCache {
+    remote_cache_with_tokens(server_dir, Some(UPLOAD_TOKEN), Some(UPLOAD_TOKEN))
+}
+
+#[test]
+fn remote_cache_round_trip() {
+    let server_dir = tempfile::tempdir().unwrap();
+    let remote = remote_cache(&server_dir);
+
+    assert_eq!((&remote).get(1), None);
+    (&remote).insert(1, VerificationResult::Success);
+    (&remote).insert(2, consistency_error("error"));
+    assert_eq!((&remote).get(1), Some(VerificationResult::Success));
+    assert_eq!((&remote).get(2), Some(consistency_error("error")));
+    assert_eq!((&remote).get(3), None);
+}
+
+#[test]
+fn uploads_need_the_token_of_the_server() {
+    for (server_token, client_token) in [
+        (None, Some(UPLOAD_TOKEN)),
+        (Some(UPLOAD_TOKEN), Some("wrong")),
+        (Some(UPLOAD_TOKEN), None),
+    ] {
+        let server_dir = tempfile::tempdir().unwrap();
+        let remote = remote_cache_with_tokens(&server_dir, server_token, client_token);
+        (&remote).insert(1, VerificationResult::Success);
+        let remote = remote_cache(&server_dir);
+        assert_eq!((&remote).get(1), None);
+    }
+}
+
+#[test]
+fn local_cache_is_filled_from_remote_cache() {
+    let server_dir = tempfile::tempdir().unwrap();
+    let local_dir = tempfile::tempdir().unwrap();
+    let local_path = local_dir.path().join("cache");
+    let remote = remote_cache(&server_dir);
+    (&remote).insert(1, VerificationResult::Success);
+
+    let mut cache = PersistentCache::load_cache(local_path.clone(), 1024).with_remote(remote);
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    (&mut cache).insert(2, consistency_error("error"));
+    drop(cache);
+
+    // The remote result was copied to the local cache.
+    let mut local_cache = PersistentCache::load_cache(local_path, 1024);
+    assert_eq!((&mut local_cache).get(1), Some(VerificationResult::Success));
+
+    // The local result was uploaded to the remote cache.
+    let remote = remote_cache(&server_dir);
+    assert_eq!((&remote).get(2), Some(consistency_error("error")));
+}
@@ -4,17 +4,19 @@ use async_std::{
     net::TcpStream,
     sync::Mutex,
 };
-use std::{env::VarError, str::FromStr};
+use std::{env::VarError, path::Path, str::FromStr};
 
 #[derive(Debug)]
 pub(super) struct Context {
     pub(crate) quantifier_instantiations_bound_global: Option<u64>,
     pub(crate) log_file: Option<Mutex<BufWriter<File>>>,
+    /// The SMT-LIB session that is recorded for `prusti-smt-replay`.
+    pub(crate) session_file: Option<Mutex<BufWriter<File>>>,
     pub(crate) z3_trace_path: Option<String>,
 }
 
 impl Context {
-    pub(crate) async fn new() -> Self {
+    pub(crate) async fn new() -> Result<Self, std::io::Error> {
         let (log_file_path, z3_trace_path) =
             if let Some(port) = read_integer("PRUSTI_SMT_SOLVER_MANAGER_PORT") {
                 let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
@@ -33,7 +35,24 @@ impl Context {
         let log_file = {
             if let Ok(value) = std::env::var("PRUSTI_LOG_SMT_INTERACTION") {
                 if value == "true" {
-                    let file = File::create(log_file_path.as_ref().unwrap()).await.unwrap();
+                    let log_file_path =
+                        required_log_file_path(&log_file_path, "PRUSTI_LOG_SMT_INTERACTION")?;
+                    let file = File::create(log_file_path).await?;
+                    Some(Mutex::new(BufWriter::new(file)))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        };
+        let session_file = {
+            if let Ok(value) = std::env::var("PRUSTI_RECORD_SMT_SESSION") {
+                if value == "true" {
+                    let log_file_path =
+                        required_log_file_path(&log_file_path, "PRUSTI_RECORD_SMT_SESSION")?;
+                    let session_file_path = Path::new(log_file_path).with_extension("smt2");
+                    let file = File::create(session_file_path).await?;
                     Some(Mutex::new(BufWriter::new(file)))
                 } else {
                     None
@@ -43,11 +62,46 @@ impl Context {
             }
         };
 
-        Self {
+        Ok(Self {
             quantifier_instantiations_bound_global,
             log_file,
+            session_file,
             z3_trace_path,
+        })
+    }
+
+    /// Starts the recorded session with the command-line arguments of the
+    /// solver, which are needed to replay it.
+    pub(crate) async fn record_arguments(&self, args: &[String]) -> Result<(), std::io::Error> {
+        if let Some(session_file) = &self.session_file {
+            let mut file = session_file.lock().await;
+            writeln!(file, "; SMT session recorded by prusti-smt-solver").await?;
+            writeln!(file, "; arguments: {}", args.join(" ")).await?;
+            file.flush().await?;
         }
+        Ok(())
+    }
+
+    /// Records a command and the response of the solver. The response and the
+    /// time the solver took are written as comments, so that the session can
+    /// also be run directly by a solver.
+    pub(crate) async fn record_command(
+        &self,
+        command: &str,
+        response: &str,
+        elapsed_ms: u128,
+    ) -> Result<(), std::io::Error> {
+        if let Some(session_file) = &self.session_file {
+            let mut file = session_file.lock().await;
+            write!(file, "{}", command).await?;
+            for line in response.lines() {
+                writeln!(file, "; > {}", line).await?;
+            }
+            writeln!(file, "; elapsed-ms: {}", elapsed_ms).await?;
+            // Silicon kills the solver at the end, so nothing can be buffered.
+            file.flush().await?;
+        }
+        Ok(())
     }
 
     pub(crate) async fn write_to_log(
@@ -106,6 +160,23 @@ impl Context {
     }
 }
 
+/// The log file path is supplied by the solver manager of Prusti, so the
+/// logging flags cannot be used when the solver is started by someone else.
+fn required_log_file_path<'a>(
+    log_file_path: &'a Option<String>,
+    flag: &str,
+) -> Result<&'a str, std::io::Error> {
+    log_file_path.as_deref().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "{} is set, but PRUSTI_SMT_SOLVER_MANAGER_PORT is not, so there is no log file path",
+                flag
+            ),
+        )
+    })
+}
+
 fn read_integer<T: FromStr>(name: &str) -> Option<T>
 where
     <T as FromStr>::Err: std::fmt::Debug,
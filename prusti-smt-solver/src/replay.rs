@@ -0,0 +1,197 @@
+//! Replays an SMT session recorded by `prusti-smt-solver` with the
+//! `record_smt_sessions` flag.
+//!
+//! Usage: `prusti-smt-replay <session.smt2> [<solver>]`. The solver defaults to
+//! the `Z3_EXE` environment variable. The commands of the session are sent one
+//! by one to the solver, which is started with the recorded arguments. The
+//! responses are compared with the recorded ones and the slowest `check-sat`
+//! commands are reported. The exit code is non-zero if any response differs.
+
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    process::{Command, ExitCode, Stdio},
+    time::Instant,
+};
+
+/// How many of the slowest `check-sat` commands are reported.
+const REPORTED_CHECK_SATS: usize = 10;
+
+struct RecordedCommand {
+    /// The line of the session file on which the command starts.
+    line: usize,
+    command: String,
+    response: String,
+    elapsed_ms: u128,
+}
+
+struct Session {
+    arguments: Vec<String>,
+    commands: Vec<RecordedCommand>,
+}
+
+fn are_parens_balanced(line: &str) -> bool {
+    let openning_parens = line.chars().filter(|c| *c == '(').count();
+    let closing_parens = line.chars().filter(|c| *c == ')').count();
+    openning_parens == closing_parens
+}
+
+fn parse_session(content: &str) -> Session {
+    let mut arguments = Vec::new();
+    let mut commands = Vec::new();
+    let mut command = String::new();
+    let mut command_line = 0;
+    let mut response = String::new();
+    for (index, line) in content.lines().enumerate() {
+        if let Some(response_line) = line.strip_prefix("; > ") {
+            response.push_str(response_line);
+            response.push('\n');
+        } else if let Some(elapsed_ms) = line.strip_prefix("; elapsed-ms: ") {
+            commands.push(RecordedCommand {
+                line: command_line,
+                command: std::mem::take(&mut command),
+                response: std::mem::take(&mut response),
+                elapsed_ms: elapsed_ms.parse().unwrap_or_default(),
+            });
+        } else if let Some(recorded_arguments) = line.strip_prefix("; arguments: ") {
+            if commands.is_empty() && command.is_empty() {
+                arguments = recorded_arguments
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect();
+            }
+        } else if !(line.starts_with(';') && command.is_empty()) {
+            if command.is_empty() {
+                command_line = index + 1;
+            }
+            command.push_str(line);
+            command.push('\n');
+        }
+    }
+    Session {
+        arguments,
+        commands,
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let (session_path, solver_path) = match args.as_slice() {
+        [session_path] => match std::env::var("Z3_EXE") {
+            Ok(solver_path) => (session_path, solver_path),
+            Err(_) => {
+                eprintln!("No solver given and the Z3_EXE environment variable is not set.");
+                return ExitCode::FAILURE;
+            }
+        },
+        [session_path, solver_path] => (session_path, solver_path.clone()),
+        _ => {
+            eprintln!("Usage: prusti-smt-replay <session.smt2> [<solver>]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let content = match fs::read_to_string(session_path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", session_path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let session = parse_session(&content);
+
+    let mut solver = Command::new(&solver_path)
+        .args(&session.arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("failed to start the solver {}: {}", solver_path, e));
+    let mut solver_stdin = solver
+        .stdin
+        .take()
+        .expect("failed to create stdin pipe with the solver");
+    let mut solver_stdout = BufReader::new(
+        solver
+            .stdout
+            .take()
+            .expect("failed to create stdout pipe with the solver"),
+    );
+
+    let mut differences = 0;
+    let mut replayed = 0;
+    let mut recorded_total_ms = 0;
+    let mut replayed_total_ms = 0;
+    // (replayed time, recorded time, line) of every `check-sat`.
+    let mut check_sats = Vec::new();
+    let mut response = String::new();
+    for recorded in &session.commands {
+        let start = Instant::now();
+        if solver_stdin
+            .write_all(recorded.command.as_bytes())
+            .and_then(|()| solver_stdin.flush())
+            .is_err()
+        {
+            println!(
+                "The solver exited before the command on line {}.",
+                recorded.line
+            );
+            differences += 1;
+            break;
+        }
+        response.clear();
+        while solver_stdout
+            .read_line(&mut response)
+            .expect("failed to read the response of the solver")
+            > 0
+        {
+            if are_parens_balanced(&response) {
+                break;
+            }
+        }
+        let elapsed_ms = start.elapsed().as_millis();
+        replayed += 1;
+        recorded_total_ms += recorded.elapsed_ms;
+        replayed_total_ms += elapsed_ms;
+        if recorded.command.trim_start().starts_with("(check-sat") {
+            check_sats.push((elapsed_ms, recorded.elapsed_ms, recorded.line));
+        }
+        if response.trim() != recorded.response.trim() {
+            differences += 1;
+            println!(
+                "Different response to the command on line {}:",
+                recorded.line
+            );
+            println!("  command:  {}", recorded.command.trim());
+            println!("  recorded: {}", recorded.response.trim());
+            println!("  replayed: {}", response.trim());
+        }
+    }
+    drop(solver_stdin);
+    let _ = solver.wait();
+
+    check_sats.sort_by(|a, b| b.cmp(a));
+    println!(
+        "Replayed {} of {} commands in {} ms (recorded: {} ms).",
+        replayed,
+        session.commands.len(),
+        replayed_total_ms,
+        recorded_total_ms
+    );
+    if !check_sats.is_empty() {
+        println!("Slowest check-sat commands:");
+        for (elapsed_ms, recorded_ms, line) in check_sats.iter().take(REPORTED_CHECK_SATS) {
+            println!(
+                "  line {}: {} ms (recorded: {} ms)",
+                line, elapsed_ms, recorded_ms
+            );
+        }
+    }
+    if differences > 0 {
+        println!(
+            "{} responses differ from the recorded session.",
+            differences
+        );
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
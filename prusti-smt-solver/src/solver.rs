@@ -8,19 +8,23 @@ mod subprocess;
 
 #[async_std::main]
 async fn main() -> Result<(), std::io::Error> {
-    let z3_path = std::env::var("PRUSTI_ORIGINAL_SMT_SOLVER_PATH").unwrap();
-    let context = Context::new().await;
+    let solver_path = std::env::var("PRUSTI_ORIGINAL_SMT_SOLVER_PATH").unwrap();
+    // The solver is Z3 unless Prusti says otherwise.
+    let is_z3 = std::env::var("PRUSTI_ORIGINAL_SMT_SOLVER_KIND")
+        .map(|kind| kind.eq_ignore_ascii_case("z3"))
+        .unwrap_or(true);
+    let context = Context::new().await?;
     for arg in std::env::args() {
         context.write_to_log("init", &format!("{}\n", arg)).await?;
     }
     context.write_config_to_log().await?;
     let args: Vec<_> = std::env::args().skip(1).collect();
-    let mut cmd = Command::new(z3_path);
+    let mut cmd = Command::new(solver_path);
     cmd.args(&args)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
-    if let Some(z3_trace_path) = &context.z3_trace_path {
+    if let Some(z3_trace_path) = context.z3_trace_path.as_ref().filter(|_| is_z3) {
         cmd.arg("trace=true")
             .arg("proof=true")
             .arg(format!("trace_file_name={}", z3_trace_path))
@@ -29,9 +33,15 @@ async fn main() -> Result<(), std::io::Error> {
     }
     context.write_to_log("z3", &format!("{:?}\n", cmd)).await?;
     let solver = cmd.spawn()?;
-    let solver_stdin = solver.stdin.expect("failed to create stdin pipe with Z3");
-    let solver_stdout = solver.stdout.expect("failed to create stdout pipe with Z3");
-    let solver_stderr = solver.stderr.expect("failed to create stderr pipe with Z3");
+    let solver_stdin = solver
+        .stdin
+        .expect("failed to create stdin pipe with the solver");
+    let solver_stdout = solver
+        .stdout
+        .expect("failed to create stdout pipe with the solver");
+    let solver_stderr = solver
+        .stderr
+        .expect("failed to create stderr pipe with the solver");
     let error = pass_error(&context, solver_stderr);
     if args
         .iter()
@@ -40,6 +50,7 @@ async fn main() -> Result<(), std::io::Error> {
         let communicate = get_version(&context, solver_stdout);
         try_join!(communicate, error)?;
     } else {
+        context.record_arguments(&args).await?;
         let communicate = communicate(&context, solver_stdin, solver_stdout);
         try_join!(communicate, error)?;
     };
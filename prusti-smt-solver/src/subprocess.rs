@@ -64,6 +64,7 @@ pub(crate) async fn communicate(
         let elapsed = now.elapsed().as_millis();
         context.write_to_log("out", &response).await?;
         context.write_number_to_log("elapsed-time", elapsed).await?;
+        context.record_command(&command, &response, elapsed).await?;
         stdout.write_all(response.as_bytes()).await?;
         stdout.flush().await?;
 
@@ -0,0 +1,110 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    thread,
+};
+
+const SESSION: &str = "(set-option :print-success true)
+(declare-const x Int)
+(assert (> x 0))
+(check-sat)
+";
+
+fn z3_exe() -> String {
+    std::env::var("Z3_EXE").expect("failed to get Z3_EXE")
+}
+
+fn log_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("prusti-smt-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the wrapper on `input` as if it were started by Silicon.
+fn run_solver(input: &str, envs: &[(&str, String)]) -> Output {
+    let mut solver = Command::new(env!("CARGO_BIN_EXE_prusti-smt-solver"))
+        .arg("-smt2")
+        .arg("-in")
+        .envs(envs.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start prusti-smt-solver");
+    solver
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    solver.wait_with_output().unwrap()
+}
+
+/// Answers the connection of the wrapper like the SMT manager of Prusti.
+fn serve_log_file_path(log_file: &Path, trace_file: &Path) -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let message = format!("{}\n{}\n", log_file.display(), trace_file.display());
+    let manager = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+        socket.write_all(message.as_bytes()).unwrap();
+    });
+    (port, manager)
+}
+
+#[test]
+fn record_and_replay_session() {
+    let dir = log_dir("record-replay");
+    let log_file = dir.join("wrapper_0.log");
+    let (port, manager) = serve_log_file_path(&log_file, &dir.join("trace0.log"));
+    let output = run_solver(
+        SESSION,
+        &[
+            ("PRUSTI_ORIGINAL_SMT_SOLVER_PATH", z3_exe()),
+            ("PRUSTI_SMT_SOLVER_MANAGER_PORT", port.to_string()),
+            ("PRUSTI_RECORD_SMT_SESSION", "true".to_string()),
+        ],
+    );
+    manager.join().unwrap();
+    assert!(
+        output.status.success(),
+        "prusti-smt-solver failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sat"));
+
+    let session_file = log_file.with_extension("smt2");
+    let mut session = String::new();
+    std::fs::File::open(&session_file)
+        .expect("the session was not recorded")
+        .read_to_string(&mut session)
+        .unwrap();
+    assert!(session.contains("; arguments: -smt2 -in"));
+    assert!(session.contains("(check-sat)\n; > sat\n"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_prusti-smt-replay"))
+        .arg(&session_file)
+        .arg(z3_exe())
+        .output()
+        .expect("failed to start prusti-smt-replay");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "the replay differs: {}", stdout);
+    assert!(stdout.contains("Replayed 4 of 4 commands"), "{}", stdout);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn record_session_without_manager() {
+    let output = run_solver(
+        SESSION,
+        &[
+            ("PRUSTI_ORIGINAL_SMT_SOLVER_PATH", "z3".to_string()),
+            ("PRUSTI_RECORD_SMT_SESSION", "true".to_string()),
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("PRUSTI_RECORD_SMT_SESSION is set, but PRUSTI_SMT_SOLVER_MANAGER_PORT is not"));
+}
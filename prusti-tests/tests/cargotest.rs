@@ -85,6 +85,70 @@ error: could not compile `foo` due to previous error; 1 warning emitted
         .run();
 }
 
+#[cargo_test]
+fn json_verification_report() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "\
+fn verified() { assert!(true); }
+fn failing() { assert!(false); }
+fn main() { verified(); failing(); }
+",
+        )
+        .build();
+    p.process(cargo_prusti_path())
+        .arg("--message-format=json")
+        .with_status(101)
+        .with_json_contains_unordered(
+            r#"
+{
+    "reason": "prusti-item",
+    "crate": "foo",
+    "item": "foo::verified",
+    "location": "src/main.rs:1:1",
+    "status": "verified",
+    "duration_ms": "{...}"
+}
+
+{
+    "reason": "prusti-item",
+    "crate": "foo",
+    "item": "foo::failing",
+    "location": "src/main.rs:2:1",
+    "status": "failed",
+    "duration_ms": "{...}"
+}
+
+{
+    "reason": "prusti-summary",
+    "crates": [
+        {
+            "crate": "foo",
+            "items": 3,
+            "verified": 2,
+            "failed": 1,
+            "timed-out": 0,
+            "skipped": 0,
+            "trusted": 0,
+            "duration_ms": "{...}"
+        }
+    ],
+    "total": {
+        "items": 3,
+        "verified": 2,
+        "failed": 1,
+        "timed-out": 0,
+        "skipped": 0,
+        "trusted": 0,
+        "duration_ms": "{...}"
+    }
+}
+"#,
+        )
+        .run();
+}
+
 /// Test `cargo-prusti` on one of the crates in `test/cargo_verify`.
 ///
 /// Special files and folders in the root of the test crate:
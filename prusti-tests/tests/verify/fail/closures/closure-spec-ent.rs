@@ -0,0 +1,72 @@
+use prusti_contracts::*;
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result > x)])]
+#[requires(x > 0)]
+#[ensures(result > x)]
+fn apply<F: Fn(i32) -> i32>(f: F, x: i32) -> i32 {
+    f(x)
+}
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result > x)])]
+fn apply_to_zero<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(0) //~ ERROR precondition might not hold
+}
+
+// The state of `f` may change between calls.
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result >= x)])]
+fn call_twice<F: FnMut(i32) -> i32>(mut f: F) {
+    let a = f(1);
+    let b = f(1);
+    assert!(a == b); //~ ERROR the asserted expression might not hold
+}
+
+fn test_stronger_precondition() {
+    let strict = closure!(
+        #[requires(x > 10)]
+        #[ensures(result == x)]
+        |x: i32| -> i32 { x }
+    );
+    apply(strict, 5); //~ ERROR precondition might not hold
+}
+
+fn test_weaker_postcondition() {
+    let identity = closure!(
+        #[requires(x > 0)]
+        #[ensures(result >= x)]
+        |x: i32| -> i32 { x }
+    );
+    apply(identity, 5); //~ ERROR precondition might not hold
+}
+
+#[requires(f |= |x: i32| [
+    requires(0 <= x && x < 100),
+    ensures(x <= result && result < 100)
+])]
+#[ensures(result >= 1)]
+fn apply_twice<F: FnMut(i32) -> i32>(mut f: F) -> i32 {
+    let a = f(1);
+    f(a)
+}
+
+#[requires(f |= |x: i32| [
+    requires(x > 0),
+    ensures(result == x + 1)
+])]
+#[ensures(result == 11)]
+fn apply_once<F: FnOnce(i32) -> i32>(f: F) -> i32 {
+    f(10)
+}
+
+fn test_unbounded_result() {
+    let inc = closure!(
+        #[requires(x < 100)]
+        #[ensures(result == x + 1)]
+        |x: i32| -> i32 { x + 1 }
+    );
+    // `inc(99)` is not lower than 100.
+    apply_twice(inc); //~ ERROR precondition might not hold
+    // `inc` does not accept all positive arguments.
+    apply_once(inc); //~ ERROR precondition might not hold
+}
+
+fn main() {}
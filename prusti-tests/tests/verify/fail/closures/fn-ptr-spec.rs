@@ -0,0 +1,45 @@
+use prusti_contracts::*;
+
+#[requires(x > 10)]
+#[ensures(result > x)]
+fn strict_double(x: i32) -> i32 {
+    if x > i32::MAX / 2 {
+        i32::MAX
+    } else {
+        x + x
+    }
+}
+
+#[requires(x > 0)]
+#[ensures(result >= x)]
+fn identity(x: i32) -> i32 {
+    x
+}
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result > x)])]
+#[requires(x > 0)]
+#[ensures(result > x)]
+fn apply(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result > x)])]
+fn apply_to_zero(f: fn(i32) -> i32) -> i32 {
+    f(0) //~ ERROR precondition might not hold
+}
+
+#[requires(f |= |x: i32| [requires(x > 0), ensures(result > x)])]
+fn apply_and_check(f: fn(i32) -> i32) {
+    let r = f(1);
+    assert!(r > 2); //~ ERROR the asserted expression might not hold
+}
+
+fn test_stronger_precondition() {
+    apply(strict_double, 5); //~ ERROR precondition might not hold
+}
+
+fn test_weaker_postcondition() {
+    apply(identity, 5); //~ ERROR precondition might not hold
+}
+
+fn main() {}
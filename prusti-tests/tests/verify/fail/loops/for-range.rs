@@ -0,0 +1,33 @@
+use prusti_contracts::*;
+
+fn only_first(n: usize) {
+    for i in 0..n {
+        body_invariant!(i == 0); //~ ERROR loop invariant might not hold after a loop iteration that preserves the loop condition
+    }
+}
+
+#[ensures(result == n)] //~ ERROR postcondition might not hold
+fn skip_first(n: usize) -> usize {
+    let mut count = 0;
+    for i in 1..n {
+        body_invariant!(count + 1 == i);
+        count += 1;
+    }
+    count
+}
+
+fn wrong_visited(n: usize) {
+    for _ in 0..n {
+        body_invariant!(visited::<usize>().len() == 1); //~ ERROR loop invariant might not hold after a loop iteration that preserves the loop condition
+    }
+}
+
+fn visited_in_while(n: usize) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(visited::<usize>().len() == i); //~ ERROR `visited()` can only be used in the body invariant of a `for` loop over a range of integers, a slice or a vector
+        i += 1;
+    }
+}
+
+fn main() {}
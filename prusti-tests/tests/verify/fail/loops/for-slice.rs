@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+
+fn first_only(s: &[i32]) {
+    for _ in s {
+        body_invariant!(visited::<i32>().len() == 1); //~ ERROR loop invariant might not hold after a loop iteration that preserves the loop condition
+    }
+}
+
+fn not_positive(s: &[i32]) {
+    for x in s {
+        assert!(*x > 0); //~ ERROR the asserted expression might not hold
+    }
+}
+
+fn vector_items_unknown(v: Vec<u32>) {
+    for x in v {
+        assert!(x == 0); //~ ERROR the asserted expression might not hold
+    }
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+extern crate prusti_std;
+use prusti_contracts::*;
+use std::collections::HashMap;
+
+fn vec() {
+    let mut v: Vec<u32> = Vec::new();
+    v.remove(0); //~ ERROR precondition might not hold
+}
+
+fn vec_len(v: &mut Vec<u32>) {
+    v.push(1);
+    assert!(v.len() == 1); //~ ERROR the asserted expression might not hold
+}
+
+fn option() {
+    let x: Option<u32> = None;
+    x.unwrap(); //~ ERROR precondition might not hold
+}
+
+fn hash_map(m: &mut HashMap<u32, u32>) {
+    m.insert(1, 2);
+    assert!(m.len() == 1); //~ ERROR the asserted expression might not hold
+}
+
+#[requires(s.len() > 0)]
+fn slice(s: &mut [u32]) {
+    s.swap(0, 1); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+trait Reset {
+    fn reset(&mut self);
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Reset for Counter {
+    fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+fn reset_dyn(value: &mut dyn Reset) {
+    value.reset();
+}
+
+fn reset_through_dyn() {
+    let mut counter = Counter { count: 5 };
+    let value: &mut dyn Reset = &mut counter;
+    value.reset();
+    assert!(counter.count == 5); //~ ERROR the asserted expression might not hold
+}
+
+fn reset_through_call() {
+    let mut counter = Counter { count: 5 };
+    reset_dyn(&mut counter);
+    assert!(counter.count == 5); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
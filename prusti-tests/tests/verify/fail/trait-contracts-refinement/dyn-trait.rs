@@ -0,0 +1,36 @@
+use prusti_contracts::*;
+
+trait Counter {
+    #[requires(step <= 10)]
+    #[ensures(result <= step)]
+    fn next(&self, step: u32) -> u32;
+}
+
+struct Zero;
+
+impl Counter for Zero {
+    fn next(&self, _step: u32) -> u32 {
+        0
+    }
+}
+
+fn call_pre(counter: &dyn Counter) {
+    counter.next(11); //~ ERROR precondition
+}
+
+fn call_post(counter: &dyn Counter) {
+    let result = counter.next(5);
+    assert!(result <= 5);
+    assert!(result == 5); //~ ERROR the asserted expression might not hold
+}
+
+fn call_boxed(counter: Box<dyn Counter>) {
+    let result = counter.next(5);
+    assert!(result == 0); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {
+    call_pre(&Zero);
+    call_post(&Zero);
+    call_boxed(Box::new(Zero));
+}
@@ -0,0 +1,60 @@
+use prusti_contracts::*;
+
+#[requires(f |= |x: i32| [
+    requires(0 <= x && x < 100),
+    ensures(result > x)
+])]
+#[ensures(result > 5)]
+fn apply<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(5)
+}
+
+// The state of `f` may change between calls, but the contract holds in
+// every state.
+#[requires(f |= |x: i32| [
+    requires(0 <= x && x < 100),
+    ensures(x <= result && result < 100)
+])]
+#[ensures(result >= 1)]
+fn apply_twice<F: FnMut(i32) -> i32>(mut f: F) -> i32 {
+    let a = f(1);
+    f(a)
+}
+
+#[requires(f |= |x: i32| [
+    requires(x > 0),
+    ensures(result == x + 1)
+])]
+#[ensures(result == 11)]
+fn apply_once<F: FnOnce(i32) -> i32>(f: F) -> i32 {
+    f(10)
+}
+
+// Entailments are passed on to other higher-order functions.
+#[requires(f |= |x: i32| [
+    requires(0 <= x && x < 100),
+    ensures(result > x)
+])]
+#[ensures(result > 5)]
+fn forward<G: Fn(i32) -> i32>(f: G) -> i32 {
+    apply(f)
+}
+
+fn main() {
+    // A weaker precondition than required.
+    let inc = closure!(
+        #[requires(x < 100)]
+        #[ensures(result == x + 1)]
+        |x: i32| -> i32 { x + 1 }
+    );
+    assert!(apply(inc) > 5);
+    assert!(forward(inc) > 5);
+
+    // A stronger postcondition than required.
+    let same = closure!(
+        #[requires(0 <= x && x < 100)]
+        #[ensures(result == x)]
+        |x: i32| -> i32 { x }
+    );
+    assert!(apply_twice(same) >= 1);
+}
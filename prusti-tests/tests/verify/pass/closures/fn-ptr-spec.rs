@@ -0,0 +1,83 @@
+use prusti_contracts::*;
+
+#[requires(0 <= a && a <= 100)]
+#[requires(0 <= b && b <= 100)]
+#[ensures(result == a + b)]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+// A stronger postcondition than required.
+#[requires(0 < x && x < 100)]
+#[ensures(result == 2 * x)]
+fn double(x: i32) -> i32 {
+    x + x
+}
+
+// A weaker precondition than required.
+#[requires(x < 100)]
+#[ensures(result == x + 1)]
+fn increment(x: i32) -> i32 {
+    x + 1
+}
+
+#[requires(f |= |a: i32, b: i32| [
+    requires(0 <= a && a <= 10),
+    requires(0 <= b && b <= 10),
+    ensures(result == a + b)
+])]
+#[ensures(result == 16)]
+fn call_add(f: fn(i32, i32) -> i32) -> i32 {
+    f(7, 9)
+}
+
+#[requires(f |= |x: i32| [requires(0 < x && x < 100), ensures(result > x)])]
+#[requires(0 < x && x < 100)]
+#[ensures(result > x)]
+fn apply(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+#[requires(f |= |x: i32| [requires(0 < x && x < 100), ensures(result > x)])]
+#[ensures(result > 1)]
+fn apply_twice(f: fn(i32) -> i32) -> i32 {
+    let y = f(1);
+    if y < 100 {
+        f(y)
+    } else {
+        y
+    }
+}
+
+#[requires(*x >= 0)]
+#[ensures(result == *x)]
+fn read(x: &i32) -> i32 {
+    *x
+}
+
+#[requires(f |= |x: &i32| [requires(*x >= 0), ensures(result >= 0)])]
+#[ensures(result >= 0)]
+fn read_local(f: fn(&i32) -> i32) -> i32 {
+    let value = 5;
+    f(&value)
+}
+
+// The pointer types differ from the one of `read_local` only in their regions.
+#[requires(f |= |x: &'static i32| [requires(*x >= 0), ensures(result >= 0)])]
+#[ensures(result >= 0)]
+fn read_static(f: fn(&'static i32) -> i32) -> i32 {
+    f(&5)
+}
+
+fn main() {
+    assert!(call_add(add) == 16);
+    let r = apply(double, 3);
+    assert!(r > 3);
+    let r = apply(increment, 3);
+    assert!(r > 3);
+    let f: fn(i32) -> i32 = increment;
+    let r = apply_twice(f);
+    assert!(r > 1);
+    assert!(read_local(read) >= 0);
+    assert!(read_static(read) >= 0);
+}
@@ -0,0 +1,44 @@
+use prusti_contracts::*;
+
+#[ensures(result == n)]
+fn count(n: usize) -> usize {
+    let mut count = 0;
+    for i in 0..n {
+        body_invariant!(count == i);
+        count += 1;
+    }
+    count
+}
+
+#[requires(a <= b)]
+#[ensures(result == b - a)]
+fn count_from(a: u32, b: u32) -> u32 {
+    let mut count = 0;
+    for i in a..b {
+        body_invariant!(a <= i && i < b);
+        body_invariant!(count == i - a);
+        count += 1;
+    }
+    count
+}
+
+fn visited_items(n: usize) {
+    for i in 0..n {
+        body_invariant!(visited::<usize>().len() == i + 1);
+        body_invariant!(visited::<usize>()[0] == 0);
+        body_invariant!(visited::<usize>()[i] == i);
+        body_invariant!(forall(|k: usize| k < visited::<usize>().len() ==> visited::<usize>()[k] == k));
+    }
+}
+
+fn nested(n: usize, m: usize) {
+    for i in 0..n {
+        body_invariant!(i < n);
+        for j in i..m {
+            body_invariant!(i <= j && j < m);
+            body_invariant!(visited::<usize>().len() == j - i + 1);
+        }
+    }
+}
+
+fn main() {}
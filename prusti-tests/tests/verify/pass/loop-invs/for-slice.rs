@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+#[ensures(result == s.len())]
+fn count(s: &[i32]) -> usize {
+    let mut count = 0;
+    for _ in s {
+        body_invariant!(count + 1 == visited::<i32>().len());
+        body_invariant!(visited::<i32>().len() <= s.len());
+        count += 1;
+    }
+    count
+}
+
+#[requires(forall(|i: usize| i < s.len() ==> s[i] > 0))]
+fn all_positive(s: &[i32]) {
+    for x in s {
+        body_invariant!(visited::<i32>().len() > 0);
+        body_invariant!(*x == visited::<i32>()[visited::<i32>().len() - 1]);
+        body_invariant!(forall(|i: usize| i < visited::<i32>().len() ==> visited::<i32>()[i] == s[i]));
+        assert!(*x > 0);
+    }
+}
+
+fn iter(s: &[u32]) {
+    for x in s.iter() {
+        body_invariant!(*x == visited::<u32>()[visited::<u32>().len() - 1]);
+        body_invariant!(visited::<u32>().len() <= s.len());
+    }
+}
+
+fn vector(v: Vec<u32>) {
+    let mut count = 0;
+    for x in v {
+        body_invariant!(count + 1 == visited::<u32>().len());
+        body_invariant!(x == visited::<u32>()[count]);
+        count += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+extern crate prusti_std;
+use prusti_contracts::*;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+fn hash_map() {
+    let mut m: HashMap<u32, bool> = HashMap::new();
+    assert!(m.is_empty());
+    assert!(m.insert(1, true).is_none());
+    assert!(m.contains_key(&1));
+    assert!(m.len() == 1);
+    assert!(m.insert(1, false).is_some());
+    assert!(m.len() == 1);
+    assert!(m.get(&1).is_some());
+    assert!(m.remove(&1).is_some());
+    assert!(!m.contains_key(&1));
+    assert!(m.is_empty());
+}
+
+#[requires(m.contains_key(&key))]
+fn btree_map(key: u32, m: &mut BTreeMap<u32, u32>) {
+    assert!(m.get(&key).is_some());
+    assert!(m.first_key_value().is_some());
+    let len = m.len();
+    m.remove(&key);
+    assert!(m.len() == len - 1);
+    m.clear();
+    assert!(m.last_key_value().is_none());
+}
+
+fn vec_deque() {
+    let mut d = VecDeque::new();
+    d.push_back(1);
+    d.push_front(0);
+    assert!(d.len() == 2);
+    assert!(d.front().is_some());
+    assert!(d.pop_back().is_some());
+    assert!(d.pop_front().is_some());
+    assert!(d.pop_front().is_none());
+    assert!(d.back().is_none());
+}
+
+fn main() {
+    hash_map();
+    vec_deque();
+}
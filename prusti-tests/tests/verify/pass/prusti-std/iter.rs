@@ -0,0 +1,15 @@
+// compile-flags: -Penable_ghost_constraints=true
+
+extern crate prusti_std;
+use prusti_contracts::*;
+
+#[requires(v.len() == 10)]
+fn adapters(v: Vec<u32>) {
+    assert!(v.iter().len() == 10);
+    assert!(v.iter().enumerate().len() == 10);
+    assert!(v.iter().rev().len() == 10);
+    assert!(v.iter().map(|x| x + 1).len() == 10);
+    assert!(v.iter().rev().enumerate().len() == 10);
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+extern crate prusti_std;
+use prusti_contracts::*;
+
+#[requires(x.is_some())]
+fn unwrap_twice(x: Option<i32>) -> i32 {
+    x.unwrap() + x.expect("checked by the precondition")
+}
+
+fn take_and_replace() {
+    let mut x = Some(1);
+    let old = x.take();
+    assert!(old.is_some());
+    assert!(x.is_none());
+    let previous = x.replace(2);
+    assert!(previous.is_none());
+    assert!(x.is_some());
+}
+
+fn combinators(x: Option<i32>, y: Option<i32>) {
+    assert!(x.as_ref().is_some() == x.is_some());
+    assert!(x.map(|v| v > 0).is_some() == x.is_some());
+    assert!(x.ok_or(()).is_ok() == x.is_some());
+    assert!(x.or(y).is_some() == (x.is_some() || y.is_some()));
+    assert!(x.and(y).is_some() == (x.is_some() && y.is_some()));
+    assert!(x.xor(y).is_some() == (x.is_some() != y.is_some()));
+    assert!(x.zip(y).is_some() == (x.is_some() && y.is_some()));
+}
+
+fn main() {
+    unwrap_twice(Some(3));
+    take_and_replace();
+    combinators(Some(1), None);
+}
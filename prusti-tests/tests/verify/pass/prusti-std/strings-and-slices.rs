@@ -0,0 +1,30 @@
+extern crate prusti_std;
+use prusti_contracts::*;
+
+fn strings() {
+    let mut s = String::new();
+    assert!(s.is_empty());
+    s.push_str("abc");
+    assert!(s.len() == 3);
+    s.push('d');
+    assert!(!s.is_empty());
+    assert!(s.pop().is_some());
+    s.clear();
+    assert!(s.as_str().is_empty());
+}
+
+#[requires(a.len() > 3)]
+fn slices(a: &mut [i32]) {
+    assert!(!a.is_empty());
+    assert!(a.first().is_some());
+    assert!(a.last().is_some());
+    a.swap(0, 3);
+    a.reverse();
+    let (left, right) = a.split_at(1);
+    assert!(left.len() == 1);
+    assert!(right.len() == a.len() - 1);
+}
+
+fn main() {
+    strings();
+}
@@ -0,0 +1,46 @@
+extern crate prusti_std;
+use prusti_contracts::*;
+
+fn push_and_pop() {
+    let mut v = Vec::new();
+    assert!(v.is_empty());
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    assert!(v.len() == 3);
+    assert!(v.pop().is_some());
+    assert!(v.len() == 2);
+    v.insert(0, 4);
+    v.remove(1);
+    v.swap_remove(0);
+    assert!(v.len() == 1);
+    v.clear();
+    assert!(v.pop().is_none());
+}
+
+#[requires(v.len() > 2)]
+fn split(mut v: Vec<u32>) {
+    let len = v.len();
+    let tail = v.split_off(2);
+    assert!(v.len() == 2);
+    assert!(tail.len() == len - 2);
+}
+
+fn resize_and_extend(v: &mut Vec<u32>, s: &[u32]) {
+    v.resize(5, 0);
+    v.extend_from_slice(s);
+    assert!(v.len() == 5 + s.len());
+    v.truncate(3);
+    assert!(v.len() == 3);
+}
+
+fn append(v: &mut Vec<u32>, w: &mut Vec<u32>) {
+    let len = v.len() + w.len();
+    v.append(w);
+    assert!(v.len() == len);
+    assert!(w.is_empty());
+}
+
+fn main() {
+    push_and_pop();
+}
@@ -0,0 +1,79 @@
+use prusti_contracts::*;
+
+trait Shape {
+    #[pure]
+    fn sides(&self) -> u32;
+
+    #[ensures(result == self.sides())]
+    fn count_sides(&self) -> u32;
+
+    #[requires(factor > 0)]
+    #[ensures(result >= factor)]
+    fn scale(&mut self, factor: u32) -> u32;
+}
+
+struct Square {
+    side: u32,
+}
+
+#[refine_trait_spec]
+impl Shape for Square {
+    #[pure]
+    fn sides(&self) -> u32 {
+        4
+    }
+
+    fn count_sides(&self) -> u32 {
+        4
+    }
+
+    #[requires(true)]
+    #[ensures(result == factor)]
+    fn scale(&mut self, factor: u32) -> u32 {
+        factor
+    }
+}
+
+#[ensures(result == shape.sides())]
+fn sides_of(shape: &dyn Shape) -> u32 {
+    shape.count_sides()
+}
+
+#[ensures(result >= 3)]
+fn scale_by_three(shape: &mut dyn Shape) -> u32 {
+    shape.scale(3)
+}
+
+fn sides_of_boxed(shape: Box<dyn Shape>) -> u32 {
+    shape.count_sides()
+}
+
+trait Convert<T> {
+    type Output;
+
+    #[ensures(result)]
+    fn convert(&self, value: T) -> bool;
+}
+
+// Trait objects of the same trait with different type arguments or
+// associated types are distinct types.
+fn convert_all(
+    from_u32: &dyn Convert<u32, Output = u32>,
+    from_i32: &dyn Convert<i32, Output = u32>,
+    to_i32: &dyn Convert<u32, Output = i32>,
+) {
+    assert!(from_u32.convert(1));
+    assert!(from_i32.convert(-1));
+    assert!(to_i32.convert(1));
+}
+
+fn main() {
+    let square = Square { side: 2 };
+    sides_of(&square);
+
+    let mut square = Square { side: 2 };
+    let result = scale_by_three(&mut square);
+    assert!(result >= 3);
+
+    sides_of_boxed(Box::new(Square { side: 2 }));
+}
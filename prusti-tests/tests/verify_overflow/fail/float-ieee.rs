@@ -0,0 +1,48 @@
+// compile-flags: -Pencode_ieee_floats=true
+use prusti_contracts::*;
+
+#[ensures(!result.is_nan())] //~ ERROR
+fn sub(a: f64, b: f64) -> f64 {
+    a - b
+}
+
+#[requires(a.is_finite() && b.is_finite())]
+#[ensures(result.is_finite())] //~ ERROR
+fn mul(a: f32, b: f32) -> f32 {
+    a * b
+}
+
+#[ensures(result)] //~ ERROR
+fn reflexive(a: f32) -> bool {
+    a == a
+}
+
+#[requires(a == 0.1 && b == 0.2)]
+#[ensures(result == 0.3)] //~ ERROR
+fn rounding_f64(a: f64, b: f64) -> f64 {
+    a + b
+}
+
+#[ensures(result >= 0.0)] //~ ERROR
+fn abs(x: f64) -> f64 {
+    x.abs()
+}
+
+#[requires(x >= 3.5 && x < 4.0)]
+#[ensures(result == 4)] //~ ERROR
+fn float_to_int_truncates(x: f32) -> u8 {
+    x as u8
+}
+
+#[ensures(result <= 1000)] //~ ERROR
+fn float_to_int_saturates(x: f64) -> u32 {
+    x as u32
+}
+
+#[requires(n == 16777217)]
+#[ensures(result == 16777217.0)] //~ ERROR
+fn int_to_float_rounds(n: u32) -> f32 {
+    n as f32
+}
+
+fn main() {}
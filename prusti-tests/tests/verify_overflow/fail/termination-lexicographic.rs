@@ -0,0 +1,50 @@
+// compile-flags: -Punsafe_core_proof=true
+
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(m >= 0 && n >= 0)]
+#[terminates((Int::new(m), Int::new(n)))]
+fn first_component_increases(m: i64, n: i64) {
+    if m > 0 && n > 0 && m < 1_000 {
+        first_component_increases(m + 1, n - 1); //~ ERROR the termination measure of this call is not necessarily lower (component 1 of 2)
+    }
+}
+
+#[requires(m >= 0 && n >= 0)]
+#[terminates((Int::new(m), Int::new(n)))]
+fn second_component_unchanged(m: i64, n: i64) {
+    if m > 0 {
+        second_component_unchanged(m, n); //~ ERROR the termination measure of this call is not necessarily lower (component 2 of 2, while the preceding components are unchanged)
+    }
+}
+
+#[terminates((Int::new(m), Int::new(n)))]
+fn first_component_negative(m: i64, n: i64) {
+    if m > -1_000 {
+        first_component_negative(m - 1, n); //~ ERROR the termination measure of this call might become negative (component 1 of 2)
+    }
+}
+
+#[requires(m >= 0)]
+#[terminates((Int::new(m), Int::new(n)))]
+fn second_component_negative(m: i64, n: i64) {
+    if n > -1_000 {
+        second_component_negative(m, n - 1); //~ ERROR the termination measure of this call might become negative (component 2 of 2, while the preceding components are unchanged)
+    }
+}
+
+#[requires(rows >= 0 && columns >= 0)]
+#[terminates]
+fn column_not_decreased(rows: i64, columns: i64) {
+    let mut row = rows;
+    let column = columns;
+    while row > 0 {
+        body_invariant!(row > 0 && column >= 0);
+        body_variant!((Int::new(row), Int::new(column))); //~ ERROR The loop variant might not have decreased (component 2 of 2, while the preceding components are unchanged)
+        if column == 0 {
+            row -= 1;
+        }
+    }
+}
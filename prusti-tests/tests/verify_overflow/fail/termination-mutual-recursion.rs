@@ -0,0 +1,75 @@
+// compile-flags: -Punsafe_core_proof=true
+
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(n >= 0)]
+#[terminates(Int::new(n))]
+fn is_even(n: i64) {
+    if n > 0 {
+        is_odd(n - 1);
+    }
+}
+
+#[requires(n >= 0)]
+#[terminates(Int::new(n))]
+fn is_odd(n: i64) {
+    if n > 0 {
+        is_even(n); //~ ERROR the termination measure of this call from `is_odd` to `is_even` is not necessarily lower
+    }
+}
+
+#[terminates(Int::new(n))]
+fn countdown(n: i64) {
+    if n > -3 {
+        countdown_helper(n - 1); //~ ERROR the termination measure of this call from `countdown` to `countdown_helper` might become negative
+    }
+}
+
+#[terminates(Int::new(n))]
+fn countdown_helper(n: i64) {
+    if n > 0 {
+        countdown(n - 1);
+    }
+}
+
+#[terminates(Int::new(n))]
+fn ping(n: i64) { //~ ERROR this function might be called recursively by `pong`, which is not marked with `#[terminates]`
+    if n > 0 {
+        pong(n - 1);
+    }
+}
+
+fn pong(n: i64) {
+    if n > 0 {
+        ping(n - 1);
+    }
+}
+
+#[terminates((Int::new(a), Int::new(b)))]
+fn left(a: i64, b: i64) { //~ ERROR the termination measure of this function has a different number of components (2) than the one of `right` (1), which might call it recursively
+    if a > 0 && b >= 0 {
+        right(a - 1);
+    }
+}
+
+#[terminates(Int::new(a))]
+fn right(a: i64) { //~ ERROR the termination measure of this function has a different number of components (1) than the one of `left` (2), which might call it recursively
+    if a > 0 {
+        left(a - 1, 0);
+    }
+}
+
+#[requires(b >= 0)]
+#[terminates((Int::new(a), Int::new(b)))]
+fn calls_trusted(a: i64, b: i64) {
+    if a > -3 {
+        trusted_step(a - 1, b); //~ ERROR the termination measure might be negative at this call from `calls_trusted` to `trusted_step`, whose termination is trusted (component 1 of 2)
+    }
+}
+
+#[terminates(trusted)]
+fn trusted_step(a: i64, b: i64) {
+    calls_trusted(a, b);
+}
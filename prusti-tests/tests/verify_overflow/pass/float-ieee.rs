@@ -0,0 +1,126 @@
+// compile-flags: -Pencode_ieee_floats=true
+use prusti_contracts::*;
+
+#[requires(a.is_finite() && b.is_finite())]
+#[ensures(!result.is_nan())]
+fn add_finite(a: f64, b: f64) -> f64 {
+    a + b
+}
+
+#[requires(a == f32::MAX)]
+#[ensures(result.is_infinite() && result > 0.0)]
+fn overflow_to_infinity(a: f32) -> f32 {
+    a * 2.0
+}
+
+#[requires(a.is_infinite())]
+#[ensures(result.is_nan())]
+fn infinity_minus_infinity(a: f64) -> f64 {
+    a - a
+}
+
+#[requires(a.is_nan())]
+#[ensures(!result)]
+fn nan_is_not_equal_to_itself(a: f32) -> bool {
+    a == a
+}
+
+#[requires(a == 0.1 && b == 0.2)]
+#[ensures(result == 0.3)]
+fn rounding_f32(a: f32, b: f32) -> f32 {
+    a + b
+}
+
+#[requires(a == 0.1 && b == 0.2)]
+#[ensures(result != 0.3)]
+fn rounding_f64(a: f64, b: f64) -> f64 {
+    a + b
+}
+
+#[requires(x.is_finite())]
+#[ensures(result.is_finite())]
+fn half(x: f64) -> f64 {
+    x / 2.0
+}
+
+#[ensures(!result.is_nan() ==> result >= 0.0)]
+fn abs(x: f64) -> f64 {
+    x.abs()
+}
+
+#[requires(x.is_nan() && !y.is_nan())]
+#[ensures(result == y)]
+fn min_ignores_nan(x: f32, y: f32) -> f32 {
+    x.min(y)
+}
+
+#[requires(x <= y)]
+#[ensures(result == y)]
+fn max(x: f64, y: f64) -> f64 {
+    x.max(y)
+}
+
+#[requires(x >= 3.0 && x < 4.0)]
+#[ensures(result == 3)]
+fn float_to_int_truncates(x: f32) -> u8 {
+    x as u8
+}
+
+#[requires(x.is_nan())]
+#[ensures(result == 0)]
+fn nan_to_int(x: f64) -> i32 {
+    x as i32
+}
+
+#[requires(x >= 1e10)]
+#[ensures(result == i32::MAX)]
+fn float_to_int_saturates(x: f64) -> i32 {
+    x as i32
+}
+
+#[requires(x < -1e10)]
+#[ensures(result == i64::MIN)]
+fn float_to_int_saturates_negative(x: f32) -> i64 {
+    x as i64
+}
+
+#[requires(x < 0.0)]
+#[ensures(result == 0)]
+fn negative_float_to_unsigned(x: f64) -> u16 {
+    x as u16
+}
+
+#[requires(n == 16777217)]
+#[ensures(result == 16777216.0)]
+fn int_to_float_rounds(n: u32) -> f32 {
+    n as f32
+}
+
+#[requires(n == -3)]
+#[ensures(result == -3.0)]
+fn signed_int_to_float(n: i8) -> f64 {
+    n as f64
+}
+
+#[requires(x >= 1e39)]
+#[ensures(result.is_infinite())]
+fn narrowing_overflows(x: f64) -> f32 {
+    x as f32
+}
+
+#[requires(x == 0.1)]
+#[ensures(result != 0.1)]
+fn widening_keeps_rounding_error(x: f32) -> f64 {
+    x as f64
+}
+
+#[pure]
+#[requires(x.is_finite())]
+fn truncate(x: f64) -> i64 {
+    x as i64
+}
+
+#[ensures(truncate(-2.5) == -2)]
+fn pure_cast() {}
+
+fn main() {}
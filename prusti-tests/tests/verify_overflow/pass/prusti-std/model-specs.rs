@@ -0,0 +1,40 @@
+// compile-flags: -Punsafe_core_proof=true -Penable_ghost_constraints=true
+
+extern crate prusti_std;
+use prusti_contracts::*;
+use prusti_std::*;
+use std::collections::HashMap;
+
+#[ensures(result.model().elements == seq![4])]
+fn singleton() -> Vec<u32> {
+    let mut vec = Vec::new();
+    vec.push(4);
+    vec
+}
+
+#[requires(vec.model().elements == seq![1, 2])]
+#[ensures(vec.model().elements == seq![1, 2, 3])]
+fn push_three(vec: &mut Vec<u32>) {
+    vec.push(3);
+}
+
+#[ensures(vec.model().elements == Seq::empty())]
+fn clear(vec: &mut Vec<u32>) {
+    vec.clear();
+}
+
+#[requires(map.model().entries == Map::empty())]
+#[ensures(map.model().entries[1] == 2)]
+#[ensures(map.contains_key(&1))]
+fn insert_one(map: &mut HashMap<u32, u32>) {
+    map.insert(1, 2);
+}
+
+#[ensures(result.model().entries == map![1 => 2])]
+fn new_map() -> HashMap<u32, u32> {
+    let mut map = HashMap::new();
+    map.insert(1, 2);
+    map
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// compile-flags: -Punsafe_core_proof=true
+
+extern crate prusti_std;
+use prusti_contracts::*;
+use prusti_std::*;
+
+#[trusted]
+#[ensures(vec.model().elements == old(vec.model().elements).concat(seq![value]))]
+fn push(vec: &mut Vec<u32>, value: u32) {
+    vec.push(value)
+}
+
+#[trusted]
+#[ensures(map.model().entries == old(map.model().entries).insert(key, value))]
+fn insert(map: &mut std::collections::HashMap<u32, u32>, key: u32, value: u32) {
+    map.insert(key, value);
+}
+
+#[requires(vec.model().elements == seq![1, 2])]
+#[ensures(vec.model().elements == seq![1, 2, 3])]
+fn push_three(vec: &mut Vec<u32>) {
+    push(vec, 3);
+}
+
+#[requires(map.model().entries == Map::empty())]
+#[ensures(map.model().entries[1] == 2)]
+fn insert_one(map: &mut std::collections::HashMap<u32, u32>) {
+    insert(map, 1, 2);
+}
+
+fn main() {}
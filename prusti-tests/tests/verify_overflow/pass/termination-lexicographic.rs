@@ -0,0 +1,97 @@
+// compile-flags: -Punsafe_core_proof=true
+
+extern crate prusti_std;
+use prusti_contracts::*;
+use prusti_std::*;
+
+fn main() {}
+
+#[requires(m >= 0 && n >= 0)]
+#[terminates((Int::new(m), Int::new(n)))]
+fn ackermann_like(m: i64, n: i64) {
+    if m > 0 {
+        if n == 0 {
+            ackermann_like(m - 1, 1);
+        } else {
+            ackermann_like(m, n - 1);
+            ackermann_like(m - 1, 1_000);
+        }
+    }
+}
+
+#[requires(trees >= 0 && nodes >= 0)]
+#[terminates((Int::new(trees), Int::new(nodes) + Int::new(1)))]
+fn walk_forest(trees: i64, nodes: i64) {
+    if trees > 0 {
+        walk_tree(trees, nodes);
+    }
+}
+
+#[requires(trees > 0 && nodes >= 0)]
+#[terminates((Int::new(trees), Int::new(nodes)))]
+fn walk_tree(trees: i64, nodes: i64) {
+    if nodes > 0 {
+        walk_tree(trees, nodes - 1);
+    } else {
+        walk_forest(trees - 1, 1_000);
+    }
+}
+
+#[requires(rows >= 0 && columns >= 0)]
+#[terminates]
+fn visit_grid(rows: i64, columns: i64) {
+    let mut row = rows;
+    let mut column = columns;
+    while row > 0 {
+        body_invariant!(row > 0 && column >= 0 && column <= columns);
+        body_variant!((Int::new(row), Int::new(column)));
+        if column > 0 {
+            column -= 1;
+        } else {
+            row -= 1;
+            column = columns;
+        }
+    }
+}
+
+/// A user-chosen well-founded order on intervals: the size of the interval.
+#[pure]
+#[terminates]
+#[requires(low <= high)]
+fn interval_size(low: i64, high: i64) -> Int {
+    Int::new(high) - Int::new(low)
+}
+
+#[requires(0 <= low && low <= high)]
+#[terminates(interval_size(low, high))]
+fn bisect(low: i64, high: i64) {
+    if low < high {
+        let middle = low + (high - low) / 2;
+        bisect(middle + 1, high);
+    }
+}
+
+#[pure]
+#[trusted]
+#[terminates]
+#[ensures(result == (vec.model().elements.len() <= Int::new(0)))]
+fn is_empty(vec: &Vec<u32>) -> bool {
+    vec.is_empty()
+}
+
+#[trusted]
+#[terminates]
+#[requires(vec.model().elements.len() > Int::new(0))]
+#[ensures(vec.model().elements.len() == old(vec.model().elements.len()) - Int::new(1))]
+fn pop(vec: &mut Vec<u32>) {
+    vec.pop();
+}
+
+#[terminates]
+fn clear(vec: &mut Vec<u32>) {
+    while !is_empty(vec) {
+        body_invariant!(true);
+        body_variant!(vec.model().elements.len());
+        pop(vec);
+    }
+}
@@ -0,0 +1,67 @@
+// compile-flags: -Punsafe_core_proof=true
+
+use prusti_contracts::*;
+
+fn main() {}
+
+#[requires(n >= 0)]
+#[terminates(Int::new(n))]
+fn is_even(n: i64) {
+    if n > 0 {
+        is_odd(n - 1);
+    }
+}
+
+#[requires(n >= 0)]
+#[terminates(Int::new(n))]
+fn is_odd(n: i64) {
+    if n > 0 {
+        is_even(n - 1);
+    }
+}
+
+/// A cycle of three functions sharing a lexicographic measure.
+#[requires(rounds >= 0 && steps >= 0)]
+#[terminates((Int::new(rounds), Int::new(steps) + Int::new(2)))]
+fn first(rounds: i64, steps: i64) {
+    second(rounds, steps);
+}
+
+#[requires(rounds >= 0 && steps >= 0)]
+#[terminates((Int::new(rounds), Int::new(steps) + Int::new(1)))]
+fn second(rounds: i64, steps: i64) {
+    third(rounds, steps);
+}
+
+#[requires(rounds >= 0 && steps >= 0)]
+#[terminates((Int::new(rounds), Int::new(steps)))]
+fn third(rounds: i64, steps: i64) {
+    if steps > 0 {
+        first(rounds, steps - 1);
+    } else if rounds > 0 {
+        first(rounds - 1, 10);
+    }
+}
+
+/// Calls to functions outside of the recursive cycle of the caller do not
+/// need to decrease its measure.
+#[requires(n >= 0 && n < 1000)]
+#[terminates(Int::new(n))]
+fn outside_of_cycle(n: i64) {
+    is_even(n + 100);
+}
+
+/// A trusted function can close a cycle without sharing the measure.
+#[requires(n >= 0)]
+#[terminates((Int::new(n), Int::new(0)))]
+fn calls_trusted(n: i64) {
+    if n > 0 {
+        trusted_step(n - 1);
+    }
+}
+
+#[requires(n >= 0)]
+#[terminates(trusted)]
+fn trusted_step(n: i64) {
+    calls_trusted(n);
+}
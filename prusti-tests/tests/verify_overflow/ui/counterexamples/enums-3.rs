@@ -0,0 +1,21 @@
+// compile-flags: -Punsafe_core_proof=true -Pcounterexample=true
+
+use prusti_contracts::*;
+
+enum Explicit {
+    First = -1,
+    Second = 5,
+    Third = 10,
+}
+
+#[ensures(result)]
+fn test1(x: Explicit) -> bool {
+    !matches!(x, Explicit::Second)
+}
+
+#[ensures(result)]
+fn test2(x: Explicit) -> bool {
+    !matches!(x, Explicit::First)
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// compile-flags: -Punsafe_core_proof=true -Pcounterexample=true -Pshow_failing_path=true
+
+use prusti_contracts::*;
+
+#[requires(x == -1)] // force specific counterexample
+#[ensures(result > 0)]
+fn test1(x: i32) -> i32 {
+    if x > 0 {
+        return 3;
+    }
+    let y = 5 + x;
+    y - 4
+}
+
+fn main() {}
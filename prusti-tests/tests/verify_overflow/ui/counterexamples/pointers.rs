@@ -0,0 +1,20 @@
+// compile-flags: -Punsafe_core_proof=true -Pcounterexample=true
+
+use prusti_contracts::*;
+
+fn test1() {
+    let a = 4u32;
+    let b = 5u32;
+    let x = &a as *const u32;
+    let y = &b as *const u32;
+    assert!(x == y);
+}
+
+fn test2() {
+    let mut a = 4u32;
+    let x = &mut a as *mut u32;
+    let y = x;
+    assert!(x != y);
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// compile-flags: -Punsafe_core_proof=true -Pcounterexample=true
+
+use prusti_contracts::*;
+
+fn test1(x: &[i32]) -> i32 {
+    x[0]
+}
+
+fn test2(x: &mut [i32]) {
+    x[0] = 4;
+}
+
+fn main() {}
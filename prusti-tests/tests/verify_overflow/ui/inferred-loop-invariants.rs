@@ -0,0 +1,22 @@
+// compile-flags: -Pinfer_loop_invariants=true
+use prusti_contracts::*;
+
+#[ensures(result == 10)]
+fn count_up() -> u32 {
+    let mut i = 0;
+    while i < 10 {
+        i += 1;
+    }
+    i
+}
+
+#[ensures(result == 0)]
+fn count_down() -> i32 {
+    let mut j: i32 = 10;
+    while j > 0 {
+        j -= 1;
+    }
+    j
+}
+
+fn main() {}
@@ -71,6 +71,10 @@ lazy_static::lazy_static! {
         settings.set_default("viper_backend", "Silicon").unwrap();
         settings.set_default::<Option<String>>("smt_solver_path", env::var("Z3_EXE").ok()).unwrap();
         settings.set_default::<Option<String>>("smt_solver_wrapper_path", None).unwrap();
+        settings.set_default("smt_solver_kind", "Z3").unwrap();
+        settings.set_default::<Option<String>>("cvc5_path", env::var("CVC5_EXE").ok()).unwrap();
+        settings.set_default("portfolio", false).unwrap();
+        settings.set_default::<Vec<u32>>("portfolio_seeds", vec![]).unwrap();
         settings.set_default::<Option<String>>("boogie_path", env::var("BOOGIE_EXE").ok()).unwrap();
         settings.set_default::<Option<String>>("viper_home", None).unwrap();
         settings.set_default::<Option<String>>("java_home", None).unwrap();
@@ -81,11 +85,15 @@ lazy_static::lazy_static! {
         settings.set_default("check_panics", true).unwrap();
         settings.set_default("encode_unsigned_num_constraint", false).unwrap();
         settings.set_default("encode_bitvectors", false).unwrap();
+        settings.set_default("encode_ieee_floats", false).unwrap();
+        settings.set_default("infer_loop_invariants", false).unwrap();
         settings.set_default("simplify_encoding", true).unwrap();
         settings.set_default("log", "").unwrap();
         settings.set_default("log_style", "auto").unwrap();
         settings.set_default("log_dir", "log").unwrap();
         settings.set_default("cache_path", "").unwrap();
+        settings.set_default("cache_max_size", 1024).unwrap();
+        settings.set_default("remote_cache_timeout", 10).unwrap();
         settings.set_default("dump_debug_info", false).unwrap();
         settings.set_default("dump_debug_info_during_fold", false).unwrap();
         settings.set_default("dump_nll_facts", false).unwrap();
@@ -133,7 +141,9 @@ lazy_static::lazy_static! {
         settings.set_default("hide_uuids", false).unwrap();
         settings.set_default("counterexample", false).unwrap();
         settings.set_default("print_counterexample_if_model_is_present", false).unwrap();
+        settings.set_default("counterexample_unit_test", false).unwrap();
         settings.set_default::<Option<String>>("save_failing_trace_to_file", None).unwrap();
+        settings.set_default("show_failing_path", false).unwrap();
         settings.set_default::<Option<String>>("execute_only_failing_trace", None).unwrap();
         settings.set_default::<Option<String>>("dump_fold_unfold_state_of_blocks", None).unwrap();
         settings.set_default("print_hash", false).unwrap();
@@ -158,6 +168,7 @@ lazy_static::lazy_static! {
         settings.set_default("preserve_smt_trace_files", false).unwrap();
         settings.set_default("write_smt_statistics", false).unwrap();
         settings.set_default("log_smt_wrapper_interaction", false).unwrap();
+        settings.set_default("record_smt_sessions", false).unwrap();
 
         // Flags for debugging Prusti that can change verification results.
         settings.set_default("disable_name_mangling", false).unwrap();
@@ -170,7 +181,11 @@ lazy_static::lazy_static! {
         let mut allowed_keys = get_keys(&settings);
         allowed_keys.insert("server_max_stored_verifiers".to_string());
         allowed_keys.insert("server_max_concurrency".to_string());
+        allowed_keys.insert("server_request_timeout".to_string());
         allowed_keys.insert("server_address".to_string());
+        allowed_keys.insert("remote_cache_url".to_string());
+        allowed_keys.insert("remote_cache_token".to_string());
+        allowed_keys.insert("verification_report_dir".to_string());
         allowed_keys.insert("config".to_string());
         allowed_keys.insert("log".to_string());
         allowed_keys.insert("log_style".to_string());
@@ -207,6 +222,7 @@ lazy_static::lazy_static! {
                 .with_list_parse_key("delete_basic_blocks")
                 .with_list_parse_key("extra_jvm_args")
                 .with_list_parse_key("extra_verifier_args")
+                .with_list_parse_key("portfolio_seeds")
                 .with_list_parse_key("verify_only_basic_block_path")
                 .list_separator(" ")
         ).unwrap();
@@ -322,6 +338,44 @@ pub fn smt_solver_path() -> String {
         .expect("please set the smt_solver_path configuration flag")
 }
 
+/// The SMT solver used by the Silicon backend. Possible values:
+///
+/// - `Z3` - the solver at `smt_solver_path`.
+/// - `cvc5` - the solver at `cvc5_path`. Z3-specific options, such as
+///   `smt_qi_eager_threshold`, are ignored.
+pub fn smt_solver_kind() -> String {
+    read_setting::<String>("smt_solver_kind")
+        .to_lowercase()
+        .trim()
+        .to_string()
+}
+
+/// When enabled, every program is verified with both Silicon and Carbon at the
+/// same time (and with the seeds of `portfolio_seeds`). The first definitive
+/// result wins and the others are cancelled: a successful verification, or a
+/// verification failure on which all the seeds of a backend agree. The
+/// configuration that produced the result is reported to the user.
+///
+/// The portfolio runs on the verification server. If `server_address` is not
+/// set, a local server is started like with `MOCK`.
+pub fn portfolio() -> bool {
+    read_setting("portfolio")
+}
+
+/// Random seeds of the SMT solver with which each backend of the portfolio is
+/// run, in addition to the default configuration.
+///
+/// **Note:** This option is taken into account only when `portfolio` is true.
+pub fn portfolio_seeds() -> Vec<u32> {
+    read_setting("portfolio_seeds")
+}
+
+/// The path to cvc5, which is used if `smt_solver_kind` is `cvc5`.
+pub fn cvc5_path() -> String {
+    read_setting::<Option<String>>("cvc5_path")
+        .expect("please set the cvc5_path configuration flag")
+}
+
 /// The path to the SMT solver wrapper. `prusti-rustc` is expected to set this
 /// configuration flag to the correct path.
 pub fn smt_solver_wrapper_path() -> String {
@@ -438,14 +492,43 @@ pub fn log_dir() -> PathBuf {
     PathBuf::from(read_setting::<String>("log_dir"))
 }
 
-/// Path to a cache file, where verification cache will be loaded from and
-/// saved to. The default empty string disables saving any cache to disk.
-/// A path to a file which does not yet exist will result in using an empty
-/// cache, but then creating and saving to that location on exit.
+/// Path to a cache directory, where verification results will be loaded from
+/// and saved to. The default empty string disables saving any cache to disk.
+/// A path which does not yet exist will result in using an empty cache, which
+/// is created at that location. Several Prusti processes can use the same
+/// directory at the same time.
 pub fn cache_path() -> PathBuf {
     PathBuf::from(read_setting::<String>("cache_path"))
 }
 
+/// Maximum size (in megabytes) of the cache directory given by `CACHE_PATH`.
+/// When the cache is saved, the least recently used results are evicted
+/// until the cache fits into this size.
+pub fn cache_max_size() -> u64 {
+    read_setting("cache_max_size")
+}
+
+/// When set to a URL (e.g. `"http://cache.example.com:4000"`), verification
+/// results that are not in the local cache are looked up on this remote cache
+/// server, and new results are uploaded to it. This allows several machines to
+/// share their verification results.
+pub fn remote_cache_url() -> Option<String> {
+    read_optional_setting("remote_cache_url")
+}
+
+/// The bearer token with which new verification results are uploaded to the
+/// remote cache given by `REMOTE_CACHE_URL`. Without a token, the remote cache
+/// is only read.
+pub fn remote_cache_token() -> Option<String> {
+    read_optional_setting("remote_cache_token")
+}
+
+/// Maximum time (in seconds) to wait for a response of the remote cache given
+/// by `REMOTE_CACHE_URL`.
+pub fn remote_cache_timeout() -> u64 {
+    read_setting("remote_cache_timeout")
+}
+
 /// When enabled, binary operations and numeric casts will be checked for
 /// overflows.
 pub fn check_overflows() -> bool {
@@ -465,6 +548,22 @@ pub fn encode_bitvectors() -> bool {
     read_setting("encode_bitvectors")
 }
 
+/// When enabled, floating-point numbers follow the IEEE 754 semantics of Rust:
+/// arithmetic may produce infinities and NaN instead of reporting an overflow,
+/// `as` casts from and to floats are supported, and the `is_nan`,
+/// `is_infinite`, `is_finite`, `abs`, `min` and `max` methods of `f32` and
+/// `f64` can be used in code and specifications.
+pub fn encode_ieee_floats() -> bool {
+    read_setting("encode_ieee_floats")
+}
+
+/// When enabled, bounds on the integer variables that are assigned in a loop
+/// are inferred and added to the loop invariant, if overflow checks are
+/// enabled. The inferred invariants are reported as warnings.
+pub fn infer_loop_invariants() -> bool {
+    read_setting("infer_loop_invariants")
+}
+
 /// Additional arguments to pass to the JVM when launching a verifier backend.
 pub fn extra_jvm_args() -> Vec<String> {
     read_setting("extra_jvm_args")
@@ -515,6 +614,12 @@ pub fn print_collected_verification_items() -> bool {
     read_setting("print_collected_verification_items")
 }
 
+/// When set, a report with the outcome and verification time of each item is
+/// written into a new file in this directory.
+pub fn verification_report_dir() -> Option<PathBuf> {
+    read_optional_setting::<String>("verification_report_dir").map(PathBuf::from)
+}
+
 /// When enabled, prints the AST with desugared specifications.
 pub fn print_desugared_specs() -> bool {
     read_setting("print_desugared_specs")
@@ -543,6 +648,27 @@ pub fn print_counterexample_if_model_is_present() -> bool {
     read_setting("print_counterexample_if_model_is_present")
 }
 
+/// When enabled, Prusti will additionally print the counterexample as a unit
+/// test that calls the failing function with the arguments of the
+/// counterexample and, where possible, asserts the violated postcondition.
+///
+/// Note: This requires the `counterexample` option to be enabled and the
+/// `unsafe_core_proof` option to be disabled.
+pub fn counterexample_unit_test() -> bool {
+    let value: bool = read_setting("counterexample_unit_test");
+    if value {
+        assert!(
+            counterexample(),
+            "Counterexamples need to be enabled to print them as unit tests"
+        );
+        assert!(
+            !unsafe_core_proof(),
+            "Counterexamples cannot be printed as unit tests with the unsafe core proof"
+        );
+    }
+    value
+}
+
 /// If this is set to a path, Prusti will extract the information about the
 /// trace that led to the error and save it to the given file.
 ///
@@ -563,6 +689,27 @@ pub fn save_failing_trace_to_file() -> Option<String> {
     value
 }
 
+/// When enabled, Prusti will annotate verification errors with the control
+/// flow path that leads to the failure in the counterexample, as a sequence of
+/// notes pointing at the executed basic blocks.
+///
+/// Note: This requires the `counterexample` and `unsafe_core_proof` options to
+/// be enabled.
+pub fn show_failing_path() -> bool {
+    let value: bool = read_setting("show_failing_path");
+    if value {
+        assert!(
+            unsafe_core_proof(),
+            "Unsafe core proof needs to be enabled to show the failing path"
+        );
+        assert!(
+            counterexample(),
+            "Counterexamples need to be enabled to show the failing path"
+        );
+    }
+    value
+}
+
 /// Execute only the failing trace that was saved with
 /// `save_failing_trace_to_file`. This is done by replacing all non-executed
 /// basic blocks with `assume false`.
@@ -617,7 +764,7 @@ pub fn enable_cache() -> bool {
 
 /// Maximum amount of instantiated Viper verifiers the server will keep around
 /// for reuse. If not set, defaults to
-/// `SERVER_MAX_CONCURRENT_VERIFICATION_OPERATIONS`. It also doesn't make much
+/// `SERVER_MAX_CONCURRENCY`. It also doesn't make much
 /// sense to set this option to less than that, since then the server will
 /// likely have to keep creating new verifiers, reducing the performance gained
 /// from reuse.
@@ -626,18 +773,27 @@ pub fn enable_cache() -> bool {
 /// handles concurrently, only the size of what is essentially its verifier
 /// cache.
 pub fn server_max_stored_verifiers() -> Option<usize> {
-    // TODO: default to below in prusti-server
-    // TODO: warn if lower than below
     read_optional_setting("server_max_stored_verifiers")
 }
 
 /// Maximum amount of verification requests the server will work on
 /// concurrently. If not set, defaults to the number of (logical) cores on
-/// the system.
+/// the system. If `use_smt_wrapper` is enabled, the server works on one
+/// request at a time.
 pub fn server_max_concurrency() -> Option<usize> {
     read_optional_setting("server_max_concurrency")
 }
 
+/// Maximum time (in seconds) the server will spend on a single verification
+/// request, including the time the request waits in the queue. If not set,
+/// requests never time out.
+///
+/// Requests that time out are cancelled like requests aborted through the
+/// `DELETE /request/{id}` endpoint of the server.
+pub fn server_request_timeout() -> Option<u64> {
+    read_optional_setting("server_request_timeout")
+}
+
 /// When set to an address and port (e.g. `"127.0.0.1:2468"`), Prusti will
 /// connect to the given server and use it for its verification backend.
 ///
@@ -749,7 +905,8 @@ pub fn verification_deadline() -> Option<u64> {
 
 /// Instead of using Z3 directly, use our SMT wrapper that tracks important
 /// statistics. This must be set to `true` to use any of the
-/// `smt_qi_bound_*`.
+/// `smt_qi_bound_*`. The wrapper is configured through the environment of
+/// the server, which then verifies one request at a time.
 pub fn use_smt_wrapper() -> bool {
     read_setting("use_smt_wrapper")
 }
@@ -858,6 +1015,12 @@ pub fn log_smt_wrapper_interaction() -> bool {
     read_smt_wrapper_dependent_bool("log_smt_wrapper_interaction")
 }
 
+/// Record the SMT-LIB session of Silicon with the solver, so that it can be
+/// replayed with `prusti-smt-replay`.
+pub fn record_smt_sessions() -> bool {
+    read_smt_wrapper_dependent_bool("record_smt_sessions")
+}
+
 /// When enabled, the new core proof is used, suitable for unsafe code
 ///
 /// **Note:** This option is currently very incomplete.
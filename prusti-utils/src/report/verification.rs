@@ -0,0 +1,188 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module defines the per-item verification report that `prusti-driver`
+//! writes into `config::verification_report_dir()` and that `cargo prusti
+//! --message-format=json` aggregates over all crates of a workspace.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The outcome of the verification of a single item.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemStatus {
+    /// All programs encoding the item were verified successfully.
+    Verified,
+    /// The item could not be encoded or its verification reported errors.
+    Failed,
+    /// The verification of the item did not finish in time. Only the server
+    /// enforces a timeout, so this status is reported only when Prusti
+    /// verifies through a server.
+    TimedOut,
+    /// The item was collected but not verified, e.g. because it is a
+    /// predicate or because the enabled checks do not cover it.
+    Skipped,
+    /// The item is marked with `#[trusted]`.
+    Trusted,
+}
+
+impl ItemStatus {
+    pub const ALL: [ItemStatus; 5] = [
+        ItemStatus::Verified,
+        ItemStatus::Failed,
+        ItemStatus::TimedOut,
+        ItemStatus::Skipped,
+        ItemStatus::Trusted,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemStatus::Verified => "verified",
+            ItemStatus::Failed => "failed",
+            ItemStatus::TimedOut => "timed-out",
+            ItemStatus::Skipped => "skipped",
+            ItemStatus::Trusted => "trusted",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemReport {
+    /// The definition path of the item, e.g. `my_crate::module::function`.
+    pub item: String,
+    /// The location of the item, as `file:line:column`.
+    pub location: String,
+    pub status: ItemStatus,
+    /// The time spent verifying the programs encoding the item.
+    pub duration_ms: u64,
+}
+
+/// The items of one crate, as verified by one run of `prusti-driver`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrateReport {
+    pub crate_name: String,
+    pub items: Vec<ItemReport>,
+    /// The time spent encoding and verifying the crate.
+    pub duration_ms: u64,
+}
+
+impl CrateReport {
+    /// Writes the report into a new file in `report_dir`. A crate can be
+    /// compiled several times in a workspace (e.g. as a library and as a
+    /// test), so every report gets a unique name.
+    pub fn write(&self, report_dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(report_dir)?;
+        let path = report_dir.join(format!("{}-{}.json", self.crate_name, uuid::Uuid::new_v4()));
+        let content = serde_json::to_string(self).map_err(io::Error::from)?;
+        fs::write(&path, content)?;
+        Ok(path)
+    }
+
+    /// Reads all reports in `report_dir`, sorted by crate name.
+    pub fn read_all(report_dir: &Path) -> io::Result<Vec<Self>> {
+        let mut reports = Vec::new();
+        if !report_dir.is_dir() {
+            return Ok(reports);
+        }
+        for entry in fs::read_dir(report_dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+            {
+                let content = fs::read_to_string(&path)?;
+                let report: CrateReport =
+                    serde_json::from_str(&content).map_err(io::Error::from)?;
+                reports.push(report);
+            }
+        }
+        reports.sort_by(|report1, report2| report1.crate_name.cmp(&report2.crate_name));
+        Ok(reports)
+    }
+
+    /// How many items have the given status.
+    pub fn count(&self, status: ItemStatus) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.status == status)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrateReport, ItemReport, ItemStatus};
+    use serde_json::json;
+
+    fn crate_report() -> CrateReport {
+        CrateReport {
+            crate_name: "foo".to_string(),
+            items: ItemStatus::ALL
+                .iter()
+                .enumerate()
+                .map(|(index, &status)| ItemReport {
+                    item: format!("foo::{}", status.name()),
+                    location: format!("src/lib.rs:{}:1", index + 1),
+                    status,
+                    duration_ms: index as u64,
+                })
+                .collect(),
+            duration_ms: 42,
+        }
+    }
+
+    #[test]
+    fn status_names_match_serialization() {
+        for status in ItemStatus::ALL {
+            assert_eq!(serde_json::to_value(status).unwrap(), json!(status.name()));
+        }
+    }
+
+    #[test]
+    fn crate_report_shape() {
+        let report = crate_report();
+        assert_eq!(
+            serde_json::to_value(&report.items[2]).unwrap(),
+            json!({
+                "item": "foo::timed-out",
+                "location": "src/lib.rs:3:1",
+                "status": "timed-out",
+                "duration_ms": 2,
+            })
+        );
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["crate_name"], json!("foo"));
+        assert_eq!(value["duration_ms"], json!(42));
+        assert_eq!(
+            value["items"].as_array().unwrap().len(),
+            ItemStatus::ALL.len()
+        );
+    }
+
+    #[test]
+    fn write_and_read_all() {
+        let report_dir =
+            std::env::temp_dir().join(format!("prusti-verification-report-{}", std::process::id()));
+        let report = crate_report();
+        let first_path = report.write(&report_dir).unwrap();
+        let second_path = report.write(&report_dir).unwrap();
+        assert_ne!(first_path, second_path);
+        let reports = CrateReport::read_all(&report_dir).unwrap();
+        std::fs::remove_dir_all(&report_dir).unwrap();
+        assert_eq!(reports.len(), 2);
+        for read_report in &reports {
+            assert_eq!(read_report.crate_name, "foo");
+            assert_eq!(read_report.duration_ms, 42);
+            for status in ItemStatus::ALL {
+                assert_eq!(read_report.count(status), 1);
+            }
+        }
+    }
+}
@@ -68,19 +68,29 @@ impl fmt::Display for CounterexampleEntry {
 
 /// A concrete counterexample containing mapped values of arguments and locals
 /// (the latter only for impure functions), as well as the result (if any).
-pub struct Counterexample(Vec<CounterexampleEntry>);
+pub struct Counterexample {
+    entries: Vec<CounterexampleEntry>,
+    /// The source of a unit test that reproduces the counterexample.
+    unit_test: Option<String>,
+}
 
 impl Counterexample {
-    pub fn new(entries: Vec<CounterexampleEntry>) -> Self {
-        Self(entries)
+    pub fn new(entries: Vec<CounterexampleEntry>, unit_test: Option<String>) -> Self {
+        Self { entries, unit_test }
     }
 
     /// Annotates a Prusti error with notes for any variable present in the
-    /// mapped counterexample.
+    /// mapped counterexample, and for the unit test, if any.
     pub fn annotate_error(&self, mut prusti_error: PrustiError) -> PrustiError {
-        for entry in &self.0 {
+        for entry in &self.entries {
             prusti_error = prusti_error.add_note(format!("{}", entry), Some(entry.span));
         }
+        if let Some(unit_test) = &self.unit_test {
+            prusti_error = prusti_error.add_note(
+                format!("counterexample as a unit test:\n{}", unit_test),
+                None,
+            );
+        }
         prusti_error
     }
 }
@@ -112,6 +122,53 @@ pub enum Entry {
 }
 
 impl Entry {
+    /// Returns a Rust expression that constructs the value, or `None` if the
+    /// value is not completely known.
+    pub fn to_rust_expr(&self) -> Option<String> {
+        let expr = match self {
+            Entry::Int(value) => value.clone(),
+            Entry::Float(value) => {
+                // Print the value such that it is parsed as a float literal
+                let value = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())?;
+                format!("{:?}", value)
+            }
+            Entry::Bool(value) => value.to_string(),
+            Entry::Char(value) => format!("{:?}", value),
+            Entry::Ref(entry) => format!("&{}", entry.to_rust_expr()?),
+            Entry::Box(entry) => format!("Box::new({})", entry.to_rust_expr()?),
+            Entry::Struct {
+                name,
+                field_entries,
+            } => fields_to_rust_expr(name, field_entries)?,
+            Entry::Enum {
+                super_name,
+                name,
+                field_entries,
+            } => {
+                if name == "?" {
+                    return None;
+                }
+                fields_to_rust_expr(&format!("{}::{}", super_name, name), field_entries)?
+            }
+            Entry::Tuple(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|entry| entry.to_rust_expr())
+                    .collect::<Option<Vec<_>>>()?;
+                if fields.len() == 1 {
+                    format!("({},)", fields[0])
+                } else {
+                    format!("({})", fields.join(", "))
+                }
+            }
+            Entry::Unknown => return None,
+        };
+        Some(expr)
+    }
+
     pub fn is_unit(&self) -> bool {
         match self {
             Entry::Tuple(fields) => fields.is_empty(),
@@ -206,6 +263,29 @@ impl Entry {
     }
 }
 
+/// Returns a Rust expression that constructs the struct or enum variant
+/// `name` with the given fields.
+fn fields_to_rust_expr(name: &str, field_entries: &[(String, Entry)]) -> Option<String> {
+    if field_entries.is_empty() {
+        return Some(name.to_string());
+    }
+    let named_fields = field_entries[0].0.parse::<usize>().is_err();
+    let mut fields = Vec::new();
+    for (field_name, entry) in field_entries {
+        let value = entry.to_rust_expr()?;
+        if named_fields {
+            fields.push(format!("{}: {}", field_name, value));
+        } else {
+            fields.push(value);
+        }
+    }
+    if named_fields {
+        Some(format!("{} {{ {} }}", name, fields.join(", ")))
+    } else {
+        Some(format!("{}({})", name, fields.join(", ")))
+    }
+}
+
 impl Default for Entry {
     fn default() -> Self {
         Entry::Unknown
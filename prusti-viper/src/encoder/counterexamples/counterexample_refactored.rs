@@ -47,17 +47,31 @@ impl fmt::Display for CounterexampleEntry {
 }
 
 /// A concrete counterexample containing mapped values of arguments and locals
-pub struct Counterexample(Vec<CounterexampleEntry>);
+pub struct Counterexample {
+    entries: Vec<CounterexampleEntry>,
+    /// The spans of the basic blocks on the path that leads to the failure.
+    failing_path: Vec<MultiSpan>,
+}
 
 impl Counterexample {
-    pub fn new(entries: Vec<CounterexampleEntry>) -> Self {
-        Self(entries)
+    pub fn new(entries: Vec<CounterexampleEntry>, failing_path: Vec<MultiSpan>) -> Self {
+        Self {
+            entries,
+            failing_path,
+        }
     }
 
-    /// Annotates a Prusti error with notes for any variable present in the
-    /// mapped counterexample.
+    /// Annotates a Prusti error with notes for the failing path, if any, and
+    /// for any variable present in the mapped counterexample.
     pub fn annotate_error(&self, mut prusti_error: PrustiError) -> PrustiError {
-        for counterexample_entry in &self.0 {
+        let path_length = self.failing_path.len();
+        for (step, span) in self.failing_path.iter().enumerate() {
+            prusti_error.add_note_mut(
+                format!("failing path, step {} of {}", step + 1, path_length),
+                Some(span.clone()),
+            );
+        }
+        for counterexample_entry in &self.entries {
             let messages = counterexample_entry.history_to_string();
             let mut iter = messages.iter();
             for (_, span) in &counterexample_entry.history {
@@ -79,6 +93,13 @@ pub enum Entry {
     Char(char),
     Ref(Box<Entry>),
     Box(Box<Entry>),
+    /// A raw pointer, given by the symbolic heap address it points to and the
+    /// value at that address, if a reference to it is part of the model. Two
+    /// pointers with the same address are aliases.
+    Pointer {
+        address: String,
+        target: Box<Entry>,
+    },
     Struct {
         name: String,
         field_entries: Vec<(String, Entry)>,
@@ -135,6 +156,12 @@ impl fmt::Debug for Entry {
             }
             Entry::Ref(el) => write!(f, "ref({:#?})", el),
             Entry::Box(el) => write!(f, "box({:#?})", el),
+            Entry::Pointer { address, target } => write!(
+                f,
+                "ptr(@{} -> {:#?})",
+                address.trim_start_matches("Address!val!"),
+                target
+            ),
             Entry::Enum {
                 super_name,
                 name,
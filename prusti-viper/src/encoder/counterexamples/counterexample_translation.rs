@@ -1,19 +1,23 @@
 use super::{super::high::types::HighTypeEncoderInterface, counterexample::*};
 use crate::encoder::{
     counterexamples::*,
+    mir::{contracts::ContractsEncoderInterface, specifications::SpecificationsInterface},
     places::{Local, LocalVariableManager},
     Encoder,
 };
+use prusti_common::config;
 use prusti_interface::{
     data::ProcedureDefId,
     environment::{body::MirBody, EnvQuery},
+    specs::typed::ProcedureSpecificationKind,
 };
 use prusti_rustc_interface::{
+    errors::MultiSpan,
     middle::{
         mir::{self, VarDebugInfo},
-        ty::{self, Ty, TyCtxt},
+        ty::{self, subst::SubstsRef, Ty, TyCtxt},
     },
-    span::Span,
+    span::{def_id::DefId, Span},
 };
 use rustc_hash::FxHashMap;
 use std::iter;
@@ -24,6 +28,7 @@ pub fn backtranslate(
     encoder: &Encoder,
     def_id: ProcedureDefId,
     silicon_counterexample: &SiliconCounterexample,
+    failing_postcondition: Option<&MultiSpan>,
 ) -> Counterexample {
     let translator = CounterexampleTranslator::new(encoder, def_id, silicon_counterexample);
 
@@ -141,7 +146,13 @@ pub fn backtranslate(
         ));
     }
 
-    Counterexample::new(ce_entries)
+    let unit_test = if config::counterexample_unit_test() {
+        translator.unit_test(encoder, &args, failing_postcondition)
+    } else {
+        None
+    };
+
+    Counterexample::new(ce_entries, unit_test)
 }
 
 pub struct CounterexampleTranslator<'ce, 'tcx> {
@@ -182,6 +193,106 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
         }
     }
 
+    /// Renders the counterexample as a unit test that calls the function with
+    /// the initial values of the arguments, or returns `None` if some of the
+    /// values are not known. If the violated postcondition is also a Rust
+    /// expression over `Copy` arguments, it is asserted after the call.
+    fn unit_test(
+        &self,
+        encoder: &Encoder<'_, 'tcx>,
+        args: &FxHashMap<(String, Span), Entry>,
+        failing_postcondition: Option<&MultiSpan>,
+    ) -> Option<String> {
+        let mut statements = vec![];
+        let mut arg_names = vec![];
+        // The postcondition can only be checked if it can refer to the arguments
+        // after the call
+        let mut can_check_postcondition = true;
+        for local in self.mir.args_iter() {
+            let vdi = self.var_debug_info.iter().find(|vdi| {
+                matches!(vdi.value, mir::VarDebugInfoContents::Place(place) if place.as_local() == Some(local))
+            })?;
+            let mut name = vdi.name.to_ident_string();
+            let entry = args.get(&(name.clone(), vdi.source_info.span))?;
+            let ty = self.mir.local_decls[local].ty;
+            let value = match (ty.kind(), entry) {
+                (ty::TyKind::Ref(_, _, mir::Mutability::Mut), Entry::Ref(referenced)) => {
+                    format!("&mut {}", referenced.to_rust_expr()?)
+                }
+                _ => entry.to_rust_expr()?,
+            };
+            if name == "self" {
+                name = "receiver".to_string();
+                can_check_postcondition = false;
+            }
+            can_check_postcondition &= self
+                .env_query
+                .type_is_copy(ty::Binder::dummy(ty), self.def_id);
+            statements.push(format!("let {} = {};", name, value));
+            arg_names.push(name);
+        }
+        let call = format!(
+            "{}({})",
+            self.tcx.def_path_str(self.def_id),
+            arg_names.join(", ")
+        );
+        let postcondition = failing_postcondition
+            .and_then(|span| span.primary_span())
+            .filter(|&span| can_check_postcondition && self.is_rust_postcondition(encoder, span))
+            .and_then(|span| self.tcx.sess.source_map().span_to_snippet(span).ok())
+            .filter(|postcondition| !uses_implication(postcondition));
+        if let Some(postcondition) = postcondition {
+            statements.push(format!("let result = {};", call));
+            statements.push(format!("assert!({});", postcondition));
+        } else {
+            statements.push(format!("{};", call));
+        }
+        let body = statements
+            .iter()
+            .map(|statement| format!("    {}", statement))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(format!(
+            "#[test]\nfn {}_counterexample() {{\n{}\n}}",
+            self.tcx.item_name(self.def_id),
+            body
+        ))
+    }
+
+    /// Returns whether the postcondition at `span` is also a Rust expression with
+    /// the same meaning, judging from its typechecked body: it must not call
+    /// Prusti-only functions, such as `old`, `forall`, `snapshot_equality`, the
+    /// methods of `Seq` or predicates.
+    fn is_rust_postcondition(&self, encoder: &Encoder<'_, 'tcx>, span: Span) -> bool {
+        let substs = self.env_query.identity_substs(self.def_id);
+        let contract = match encoder.get_procedure_contract_for_def(self.def_id, substs) {
+            Ok(contract) => contract,
+            Err(_) => return false,
+        };
+        let postcondition = contract
+            .functional_postcondition(encoder.env(), substs)
+            .into_iter()
+            .find(|(spec_def_id, _)| self.env_query.get_def_span(*spec_def_id).contains(span));
+        if let Some((spec_def_id, spec_substs)) = postcondition {
+            let body = encoder
+                .env()
+                .body
+                .get_spec_body(spec_def_id, spec_substs, self.def_id);
+            !body.basic_blocks.iter().any(|block| {
+                if let mir::TerminatorKind::Call { func, .. } = &block.terminator().kind {
+                    func.const_fn_def()
+                        .map_or(true, |(callee_def_id, callee_substs)| {
+                            is_prusti_only_function(encoder, callee_def_id, callee_substs)
+                        })
+                } else {
+                    false
+                }
+            })
+        } else {
+            false
+        }
+    }
+
     fn entries_to_process(
         &self,
         encoder: &Encoder<'_, 'tcx>,
@@ -787,3 +898,101 @@ fn get_discriminant_of_vardef(vardef: &ty::VariantDef) -> Option<u32> {
         _ => None,
     }
 }
+
+/// Returns whether a function only has a meaning in specifications: the items
+/// of `prusti_contracts` and predicates, whose bodies cannot be executed.
+fn is_prusti_only_function<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    def_id: DefId,
+    substs: SubstsRef<'tcx>,
+) -> bool {
+    encoder.env().name.crate_name(def_id.krate) == "prusti_contracts"
+        || matches!(
+            encoder.get_proc_kind(def_id, Some(substs)),
+            ProcedureSpecificationKind::Predicate(_)
+        )
+}
+
+/// Returns whether the source of a specification uses an implication (`==>` or
+/// `->`), which is desugared into a Rust expression without a call, but is not
+/// valid Rust itself. String and character literals and comments are skipped.
+fn uses_implication(expression: &str) -> bool {
+    let mut chars = expression.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            'r' if matches!(chars.peek(), Some('"' | '#')) => {
+                let mut hashes = 0;
+                while chars.next_if_eq(&'#').is_some() {
+                    hashes += 1;
+                }
+                if chars.next_if_eq(&'"').is_some() {
+                    // A raw string ends with a quote followed by as many hashes
+                    let mut closing = None;
+                    for c in chars.by_ref() {
+                        closing = match (c, closing) {
+                            ('"', _) => Some(0),
+                            ('#', Some(count)) if count < hashes => Some(count + 1),
+                            _ => None,
+                        };
+                        if closing == Some(hashes) {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\'' => {
+                // A character literal, unless it is a lifetime
+                let mut lookahead = chars.clone();
+                let is_char = match lookahead.next() {
+                    Some('\\') => true,
+                    Some(_) => lookahead.next() == Some('\''),
+                    None => false,
+                };
+                if is_char {
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '\'' => break,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+            }
+            '=' if chars.peek() == Some(&'=') => {
+                chars.next();
+                if chars.peek() == Some(&'>') {
+                    return true;
+                }
+            }
+            '-' if chars.peek() == Some(&'>') => return true,
+            _ => {}
+        }
+    }
+    false
+}
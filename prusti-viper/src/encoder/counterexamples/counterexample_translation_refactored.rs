@@ -16,9 +16,9 @@ use prusti_rustc_interface::{
         mir::{self, VarDebugInfo},
         ty::{self, Ty, TyCtxt},
     },
-    span::source_map::Spanned,
+    span::{source_map::Spanned, Span},
 };
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::{iter, vec};
 use viper::silicon_counterexample::*;
 
@@ -39,14 +39,20 @@ pub fn backtranslate(
     }
 
     let counterexample_entry_vec = translator.process_entries(position_manager, &label_markers);
+    let failing_path = if config::show_failing_path() {
+        translator.get_failing_path(&label_markers)
+    } else {
+        Vec::new()
+    };
 
-    Counterexample::new(counterexample_entry_vec)
+    Counterexample::new(counterexample_entry_vec, failing_path)
 }
 
 pub struct CounterexampleTranslator<'ce, 'tcx, 'v> {
     encoder: &'ce Encoder<'v, 'tcx>,
     silicon_counterexample: &'ce SiliconCounterexample,
     tcx: TyCtxt<'tcx>,
+    mir: mir::Body<'tcx>,
     var_debug_info: Vec<VarDebugInfo<'tcx>>,
     local_variable_manager: LocalVariableManager<'tcx>,
     pub(super) var_mapping: VarMapping,
@@ -65,6 +71,7 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
             encoder,
             silicon_counterexample,
             tcx: encoder.env().tcx(),
+            mir,
             var_debug_info,
             local_variable_manager,
             var_mapping: Default::default(),
@@ -95,6 +102,53 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
             .collect::<FxHashMap<String, bool>>()
     }
 
+    /// Reconstructs the path that leads to the failure from the label markers
+    /// and returns the spans of the visited MIR basic blocks, in execution
+    /// order. Consecutive blocks with the same span are shown only once.
+    fn get_failing_path(&self, label_markers: &FxHashMap<String, bool>) -> Vec<MultiSpan> {
+        let mut path: Vec<Span> = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut label = "start_label".to_string();
+        while visited.insert(label.clone()) {
+            if let Some(span) = mir_basic_block(&label).and_then(|bb| self.basic_block_span(bb)) {
+                if path.last() != Some(&span) {
+                    path.push(span);
+                }
+            }
+            if let Some(next) = self.get_successor(&label, label_markers) {
+                label = next.to_string();
+            } else {
+                break;
+            }
+        }
+        path.into_iter().map(MultiSpan::from).collect()
+    }
+
+    /// The span that represents the basic block on the failing path: the span
+    /// of its terminator if it is a branch, call or assertion, and otherwise
+    /// the span of its first assignment.
+    fn basic_block_span(&self, bb: mir::BasicBlock) -> Option<Span> {
+        let block_data = self.mir.basic_blocks.get(bb)?;
+        let terminator = block_data.terminator();
+        let span = match terminator.kind {
+            mir::TerminatorKind::SwitchInt { .. }
+            | mir::TerminatorKind::Call { .. }
+            | mir::TerminatorKind::Assert { .. } => terminator.source_info.span,
+            _ => block_data
+                .statements
+                .iter()
+                .find(|statement| matches!(statement.kind, mir::StatementKind::Assign(..)))
+                .map_or(terminator.source_info.span, |statement| {
+                    statement.source_info.span
+                }),
+        };
+        if span.is_dummy() || span.is_empty() {
+            None
+        } else {
+            Some(span)
+        }
+    }
+
     //Given a MIR var name, it returns all relevant snapshot variables
     fn get_trace_of_var(
         &self,
@@ -327,7 +381,7 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
     ) -> Entry {
         match (model_entry, typ.map(|x| x.kind())) {
             (Some(ModelEntry::LitInt(string)), Some(ty::TyKind::Char)) => {
-                if let Ok(value_int) = string.parse::<u32>() {
+                if let Ok(value_int) = model_int(string).parse::<u32>() {
                     if let Some(value_char) = char::from_u32(value_int) {
                         Entry::Char(value_char)
                     } else {
@@ -337,7 +391,7 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
                     Entry::Unknown
                 }
             }
-            (Some(ModelEntry::LitInt(string)), _) => Entry::Int(string.clone()),
+            (Some(ModelEntry::LitInt(string)), _) => Entry::Int(model_int(string)),
             (Some(ModelEntry::LitFloat(string)), _) => Entry::Float(string.clone()),
             (Some(ModelEntry::LitBool(bool)), _) => Entry::Bool(*bool),
             (Some(ModelEntry::DomainValue(domain_name, _)), Some(ty::TyKind::Ref(_, typ, _))) => {
@@ -357,6 +411,36 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
                     model,
                 ))
             }
+            (
+                Some(ModelEntry::DomainValue(domain_name, _)),
+                Some(ty::TyKind::RawPtr(ty::TypeAndMut { ty: typ, .. })),
+            ) => {
+                //the target of a raw pointer is not part of its snapshot, only its address
+                let sil_domain = self
+                    .silicon_counterexample
+                    .domains
+                    .entries
+                    .get(domain_name)
+                    .unwrap();
+                let sil_fn_name = format!("destructor${}$$value", domain_name);
+                let sil_fn_param = vec![model_entry.cloned()];
+                if let Some(function) = sil_domain.functions.entries.get(&sil_fn_name) {
+                    if let Some(address_entry @ ModelEntry::DomainValue(_, address)) =
+                        function.get_function_value(&sil_fn_param)
+                    {
+                        return Entry::Pointer {
+                            address: address.clone(),
+                            target: box self.translate_pointer_target(
+                                domain_name,
+                                address_entry,
+                                *typ,
+                                model,
+                            ),
+                        };
+                    }
+                }
+                Entry::Unknown
+            }
             (Some(ModelEntry::DomainValue(domain_name, _)), Some(ty::TyKind::Tuple(subst))) => {
                 let sil_domain = self
                     .silicon_counterexample
@@ -483,7 +567,7 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
                         disc_function.get_function_value(&sil_fn_param)
                     {
                         let super_name = format!("{:?}", adt_def);
-                        let disc_value_int = disc_value.parse::<usize>().unwrap();
+                        let disc_value_int = model_int(disc_value).parse::<usize>().unwrap();
                         let variant = adt_def.variants().iter().next().unwrap();
                         let variant_name = variant.fields[disc_value_int]
                             .ident(self.tcx)
@@ -547,12 +631,11 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
                         disc_function.get_function_value(&sil_fn_param)
                     {
                         let super_name = format!("{:?}", adt_def);
-                        let disc_value_int = disc_value.parse::<u32>().unwrap();
-                        if let Some(variant) = adt_def
-                            .variants()
-                            .iter()
-                            .find(|x| get_discriminant_of_vardef(x) == Some(disc_value_int))
+                        if let Some((variant_idx, _)) = adt_def
+                            .discriminants(self.tcx)
+                            .find(|(_, discr)| discr.to_string() == model_int(disc_value))
                         {
+                            let variant = adt_def.variant(variant_idx);
                             let variant_name = variant.ident(self.tcx).name.to_ident_string();
                             let destructor_sil_name =
                                 format!("destructor${}${}$value", domain_name, &variant_name);
@@ -607,6 +690,14 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
 
                 Entry::Array(entries)
             }
+            (Some(ModelEntry::Seq(_, model_entries)), Some(ty::TyKind::Slice(typ))) => {
+                let entries = model_entries
+                    .iter()
+                    .map(|entry| self.translate_snapshot_entry(Some(entry), Some(*typ), model))
+                    .collect();
+
+                Entry::Array(entries)
+            }
             (Some(ModelEntry::DomainValue(domain_name, _)), _) => {
                 //snapshot typ for primitive typ
 
@@ -624,6 +715,53 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
         }
     }
 
+    /// The value at `address`, the target of a raw pointer of the snapshot
+    /// domain `pointer_domain_name`. The snapshot of a raw pointer contains
+    /// only its address, so the value is taken from a reference to the same
+    /// address, if there is one in the model.
+    fn translate_pointer_target(
+        &self,
+        pointer_domain_name: &str,
+        address: &ModelEntry,
+        target_typ: Ty<'tcx>,
+        model: bool, //if false, ignore models
+    ) -> Entry {
+        let target_domain_name = pointer_domain_name.trim_start_matches("Snap$ptr");
+        for uniqueness in ["Shared", "Unique"] {
+            let ref_domain_name = format!("Snap$ref${}{}", uniqueness, target_domain_name);
+            let address_fn_name = format!("destructor${}$$address", ref_domain_name);
+            let target_fn_name = format!("destructor${}$$target_current", ref_domain_name);
+            if let Some(sil_domain) = self
+                .silicon_counterexample
+                .domains
+                .entries
+                .get(&ref_domain_name)
+            {
+                let reference = sil_domain
+                    .functions
+                    .entries
+                    .get(&address_fn_name)
+                    .and_then(|address_function| {
+                        address_function
+                            .options
+                            .iter()
+                            .find(|(_, value)| value.as_ref() == Some(address))
+                    })
+                    .and_then(|(params, _)| params.first().cloned().flatten());
+                if let Some(reference) = reference {
+                    return self.extract_field_value(
+                        &target_fn_name,
+                        Some(target_typ),
+                        Some(&reference),
+                        sil_domain,
+                        model,
+                    );
+                }
+            }
+        }
+        Entry::Unknown
+    }
+
     fn translate_snapshot_adt_fields(
         &self,
         variant: &ty::VariantDef,
@@ -730,13 +868,20 @@ impl<'ce, 'tcx, 'v> CounterexampleTranslator<'ce, 'tcx, 'v> {
     }
 }
 
-fn get_discriminant_of_vardef(vardef: &ty::VariantDef) -> Option<u32> {
-    // TODO: this is not a good way to calculate discriminants
-    // (might be wrong for enums with explicit discriminants, does not handle
-    // large discriminant values)
-    // instead we should maintain a mapping in the Enum predicate we create
-    match vardef.discr {
-        ty::VariantDiscr::Relative(x) => Some(x),
-        _ => None,
-    }
+/// The integer denoted by the value of an integer in the model, in which
+/// negative integers are printed as `(- 1)`.
+fn model_int(value: &str) -> String {
+    value
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split_whitespace()
+        .collect()
+}
+
+/// The MIR basic block encoded by the block with the given label, such as
+/// `label_bb3`, possibly with a prefix added when the block is duplicated, as
+/// in `loop__label_bb1__label_bb3`.
+fn mir_basic_block(label: &str) -> Option<mir::BasicBlock> {
+    let index = label.rsplit("__").next()?.strip_prefix("label_bb")?;
+    index.parse().ok().map(mir::BasicBlock::from_usize)
 }
@@ -34,11 +34,8 @@ impl MirProcedureMapping {
                         } //all statements that could be relevant for variable assignments
                         vir_low::Statement::Inhale(inhale) => {
                             //all statements that could be relevant for pure function calls
-                            if let Some(assume_stmt) =
-                                Self::extract_domain_functions(&inhale.expression)
-                            {
-                                stmts.push(assume_stmt);
-                            }
+                            //and for the validity of references, enums and pointers
+                            Self::extract_domain_functions(&inhale.expression, &mut stmts);
                         }
                         _ => (),
                     };
@@ -58,17 +55,25 @@ impl MirProcedureMapping {
             })
             .collect::<Vec<BasicBlock>>()
     }
-    fn extract_domain_functions(expression: &vir_low::Expression) -> Option<vir_low::Statement> {
+    fn extract_domain_functions(
+        expression: &vir_low::Expression,
+        stmts: &mut Vec<vir_low::Statement>,
+    ) {
         // pure function all called in the following form in Viper:
         //inhale destructor_Bool(constructor_Bool_EqCmp_*(snapvar_1 caller_for$m_foo$(snapvar_2)))
+        // the validity of compound values is inhaled as a conjunction, so
+        // both operands of a conjunction are searched
         match &expression {
             vir_low::Expression::BinaryOp(binary_op) => {
-                Self::extract_domain_functions(&binary_op.right)
+                if binary_op.op_kind == vir_low::BinaryOpKind::And {
+                    Self::extract_domain_functions(&binary_op.left, stmts);
+                }
+                Self::extract_domain_functions(&binary_op.right, stmts);
             }
             vir_low::Expression::DomainFuncApp(domain_function_call) => {
                 if domain_function_call.function_name.contains("valid$") {
                     if let Some(local) = domain_function_call.arguments.first() {
-                        return Some(vir_low::Statement::Assume(
+                        stmts.push(vir_low::Statement::Assume(
                             vir_low::ast::statement::Assume {
                                 expression: local.clone(),
                                 position: Default::default(),
@@ -76,9 +81,8 @@ impl MirProcedureMapping {
                         ));
                     }
                 }
-                None
             }
-            _ => None,
+            _ => (),
         }
     }
 }
@@ -16,6 +16,7 @@ use crate::encoder::procedure_encoder::ProcedureEncoder;
 use prusti_common::{vir_expr, vir_local};
 use prusti_common::config;
 use prusti_common::report::log;
+use prusti_common::report::verification::ItemStatus;
 use prusti_interface::data::ProcedureDefId;
 use prusti_interface::environment::Environment;
 use prusti_interface::specs::typed;
@@ -84,6 +85,9 @@ pub struct Encoder<'v, 'tcx: 'v> {
     pub(super) specifications_state: SpecificationsState<'tcx>,
     type_discriminant_funcs: RefCell<FxHashMap<String, vir::FunctionIdentifier>>,
     type_cast_functions: RefCell<FxHashMap<(ty::Ty<'tcx>, ty::Ty<'tcx>), vir::FunctionIdentifier>>,
+    /// The abstract functions modelling the items visited by a `for` loop
+    /// over a range, indexed by the type of the items.
+    range_visited_functions: RefCell<FxHashMap<ty::Ty<'tcx>, vir::FunctionIdentifier>>,
     pub(super) snapshot_encoder_state: SnapshotEncoderState,
     pub(super) mirror_encoder: RefCell<MirrorEncoder>,
     encoding_queue: RefCell<Vec<EncodingTask<'tcx>>>,
@@ -91,6 +95,9 @@ pub struct Encoder<'v, 'tcx: 'v> {
     vir_program_before_foldunfold_writer: Option<RefCell<Box<dyn Write>>>,
     vir_program_before_viper_writer: Option<RefCell<Box<dyn Write>>>,
     encoding_errors_counter: RefCell<usize>,
+    /// The outcome of the procedures that are not verified because they are
+    /// trusted, not verifiable, or could not be encoded.
+    unverified_procedures: RefCell<FxHashMap<ProcedureDefId, ItemStatus>>,
     name_interner: RefCell<NameInterner>,
     /// Maps locals to the local of their discriminant.
     pub(super) discriminants_state: DiscriminantsState,
@@ -164,6 +171,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             typed_type_encoder_state: Default::default(),
             type_discriminant_funcs: RefCell::new(FxHashMap::default()),
             type_cast_functions: RefCell::new(FxHashMap::default()),
+            range_visited_functions: RefCell::new(FxHashMap::default()),
             encoding_queue: RefCell::new(vec![]),
             queued_types: Default::default(),
             vir_program_before_foldunfold_writer,
@@ -171,6 +179,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             snapshot_encoder_state: Default::default(),
             mirror_encoder: RefCell::new(MirrorEncoder::new()),
             encoding_errors_counter: RefCell::new(0),
+            unverified_procedures: RefCell::new(FxHashMap::default()),
             name_interner: RefCell::new(NameInterner::new()),
             is_encoding_trigger: Cell::new(false),
             specifications_state: SpecificationsState::new(def_spec),
@@ -258,6 +267,25 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         *self.encoding_errors_counter.borrow()
     }
 
+    fn register_procedure_encoding_error(&self, proc_def_id: ProcedureDefId, encoding_error: SpannedEncodingError) {
+        self.register_unverified_procedure(proc_def_id, ItemStatus::Failed);
+        self.register_encoding_error(encoding_error);
+    }
+
+    fn register_unverified_procedure(&self, proc_def_id: ProcedureDefId, status: ItemStatus) {
+        let mut unverified_procedures = self.unverified_procedures.borrow_mut();
+        // An encoding error takes precedence over other reasons.
+        if status == ItemStatus::Failed || !unverified_procedures.contains_key(&proc_def_id) {
+            unverified_procedures.insert(proc_def_id, status);
+        }
+    }
+
+    /// The outcome of a procedure that was not verified, or `None` if the
+    /// procedure was encoded into programs that can be verified.
+    pub fn get_unverified_procedure_status(&self, proc_def_id: ProcedureDefId) -> Option<ItemStatus> {
+        self.unverified_procedures.borrow().get(&proc_def_id).copied()
+    }
+
     pub(super) fn get_mirror_domain(&self) -> Option<vir::Domain> {
         self.mirror_encoder.borrow().get_domain().cloned()
     }
@@ -333,7 +361,8 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             | ty::TyKind::Closure(_, _)
             | ty::TyKind::Array(..)
             | ty::TyKind::Tuple(_)
-            | ty::TyKind::Param(_) => {
+            | ty::TyKind::Param(_)
+            | ty::TyKind::FnPtr(_) => {
                 Ok(base) // don't use a field for tuples and ADTs
             }
             _ => {
@@ -508,6 +537,119 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(function_name)
     }
 
+    /// Encodes an application of the abstract function that models the
+    /// precondition (if `result` is `None`) or the postcondition of calls
+    /// to a callable of type `callable_ty` with signature `sig`: a function
+    /// pointer, a closure, or a type parameter bounded by one of the `Fn*`
+    /// traits. The callable, the arguments and the result are all given as
+    /// snapshots.
+    pub fn encode_callable_spec_func_app(
+        &self,
+        callable_ty: ty::Ty<'tcx>,
+        sig: ty::FnSig<'tcx>,
+        callable: vir::Expr,
+        args: Vec<vir::Expr>,
+        result: Option<vir::Expr>,
+    ) -> EncodingResult<vir::Expr> {
+        trace!("encode_callable_spec_func_app(callable_ty={:?})", callable_ty);
+        let is_post = result.is_some();
+        let function_name = match (callable_ty.kind(), is_post) {
+            (ty::TyKind::FnPtr(_), false) => "fn_ptr$pre",
+            (ty::TyKind::FnPtr(_), true) => "fn_ptr$post",
+            (_, false) => "closure$pre",
+            (_, true) => "closure$post",
+        };
+        let mut formal_args = vec![
+            vir_local!{ callable: {self.encode_snapshot_type(callable_ty)?} },
+        ];
+        for (arg_index, arg_ty) in sig.inputs().iter().enumerate() {
+            formal_args.push(vir::LocalVar::new(
+                format!("arg{}", arg_index),
+                self.encode_snapshot_type(*arg_ty)?,
+            ));
+        }
+        if is_post {
+            formal_args.push(vir_local!{ res: {self.encode_snapshot_type(sig.output())?} });
+        }
+        let function = vir::Function {
+            name: function_name.to_string(),
+            type_arguments: vec![],
+            formal_args: formal_args.clone(),
+            return_type: vir::Type::Bool,
+            pres: vec![],
+            posts: vec![],
+            body: None,
+        };
+        // The function is identified by the snapshots of the callable and its
+        // arguments, which are shared by callable types that differ only in
+        // their regions.
+        let identifier: vir::FunctionIdentifier = function.get_identifier().into();
+        if !self.functions.borrow().contains_key(&identifier) {
+            self.insert_function(function);
+        }
+        let mut actual_args = vec![callable];
+        actual_args.extend(args);
+        actual_args.extend(result);
+        Ok(vir::Expr::func_app(
+            function_name.to_string(),
+            vec![],
+            actual_args,
+            formal_args,
+            vir::Type::Bool,
+            vir::Position::default(),
+        ))
+    }
+
+    /// Encodes the snapshot of the slice `[first, first + 1, .., next - 1]`,
+    /// which models the items visited by a `for` loop over a range whose
+    /// items are of type `elem_ty`.
+    pub fn encode_range_visited_func_app(
+        &self,
+        elem_ty: ty::Ty<'tcx>,
+        first: vir::Expr,
+        next: vir::Expr,
+    ) -> EncodingResult<vir::Expr> {
+        trace!("encode_range_visited_func_app(elem_ty={:?})", elem_ty);
+        let slice_ty = self.env.tcx().mk_slice(elem_ty);
+        let return_type = self.encode_snapshot_type(slice_ty)?;
+        let function_name = format!("range$visited${}", self.encode_type(elem_ty)?.name());
+        let formal_args = vec![vir_local!{ first: Int }, vir_local!{ next: Int }];
+        if !self.range_visited_functions.borrow().contains_key(&elem_ty) {
+            let first: vir::Expr = vir_local!{ first: Int }.into();
+            let next: vir::Expr = vir_local!{ next: Int }.into();
+            let result: vir::Expr = vir::LocalVar::new("__result", return_type.clone()).into();
+            let len = self.encode_snapshot_slice_len(slice_ty, result.clone())?;
+            let idx = vir_local!{ idx: Int };
+            let read = self.encode_snapshot_slice_idx(slice_ty, result, idx.clone().into())?;
+            let idx: vir::Expr = idx.into();
+            let zero = vir::Expr::from(0);
+            let function = vir::Function {
+                name: function_name.clone(),
+                type_arguments: vec![],
+                formal_args: formal_args.clone(),
+                return_type: return_type.clone(),
+                pres: vec![vir_expr!{ [first] <= [next] }],
+                posts: vec![
+                    vir_expr!{ [len] == ([next] - [first]) },
+                    vir_expr!{ forall idx: Int :: { [read] } (
+                        (([zero] <= [idx]) && ([idx] < ([next] - [first]))) ==> ([read] == ([first] + [idx]))
+                    ) },
+                ],
+                body: None,
+            };
+            let identifier = self.insert_function(function);
+            self.range_visited_functions.borrow_mut().insert(elem_ty, identifier);
+        }
+        Ok(vir::Expr::func_app(
+            function_name,
+            vec![],
+            vec![first, next],
+            formal_args,
+            return_type,
+            vir::Position::default(),
+        ))
+    }
+
     /// This encodes the Rust function as a Viper method for verification. It
     /// does this also for pure functions.
     pub fn encode_procedure(&self, def_id: ProcedureDefId) -> SpannedEncodingResult<()> {
@@ -678,15 +820,18 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                     assert!(substs.is_empty());
 
                     if config::unsafe_core_proof() {
+                        if self.is_trusted(proc_def_id, None) {
+                            self.register_unverified_procedure(proc_def_id, ItemStatus::Trusted);
+                        }
                         if self.env.query.is_unsafe_function(proc_def_id) {
                             if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id, CheckMode::Both) {
-                                self.register_encoding_error(error);
+                                self.register_procedure_encoding_error(proc_def_id, error);
                                 debug!("Error encoding function: {:?} {}", proc_def_id, CheckMode::Both);
                             }
                         } else {
                             if config::verify_core_proof() {
                                 if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id, CheckMode::CoreProof) {
-                                    self.register_encoding_error(error);
+                                    self.register_procedure_encoding_error(proc_def_id, error);
                                     debug!("Error encoding function: {:?} {}", proc_def_id, CheckMode::CoreProof);
                                 }
                             }
@@ -697,7 +842,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                                     CheckMode::Specifications
                                 };
                                 if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id, check_mode) {
-                                    self.register_encoding_error(error);
+                                    self.register_procedure_encoding_error(proc_def_id, error);
                                     debug!("Error encoding function: {:?} {}", proc_def_id, check_mode);
                                 }
                             }
@@ -716,7 +861,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                         // the Viper file because that would be unsound.
                         let identity_substs = self.env.query.identity_substs(proc_def_id);
                         if let Err(error) = self.encode_pure_function_def(proc_def_id, proc_def_id, identity_substs) {
-                            self.register_encoding_error(error);
+                            self.register_procedure_encoding_error(proc_def_id, error);
                             debug!("Error encoding function: {:?}", proc_def_id);
                             // Skip encoding the function as a method.
                             continue;
@@ -725,12 +870,14 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
 
                     match proc_kind {
                         _ if self.is_trusted(proc_def_id, None) => {
+                            self.register_unverified_procedure(proc_def_id, ItemStatus::Trusted);
                             debug!(
                                 "Trusted procedure will not be encoded or verified: {:?}",
                                 proc_def_id
                             );
                         },
                         ProcedureSpecificationKind::Predicate(_) => {
+                            self.register_unverified_procedure(proc_def_id, ItemStatus::Skipped);
                             debug!(
                                 "Predicates will not be encoded or verified: {:?}",
                                 proc_def_id
@@ -739,13 +886,13 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                         ProcedureSpecificationKind::Pure |
                         ProcedureSpecificationKind::Impure => {
                             if let Err(error) = self.encode_procedure(proc_def_id) {
-                                self.register_encoding_error(error);
+                                self.register_procedure_encoding_error(proc_def_id, error);
                                 debug!("Error encoding function: {:?}", proc_def_id);
                             } else {
                                 match self.finalize_viper_program(proc_name, proc_def_id) {
                                     Ok(program) => self.programs.push(program),
                                     Err(error) => {
-                                        self.register_encoding_error(error);
+                                        self.register_procedure_encoding_error(proc_def_id, error);
                                         debug!("Error finalizing program: {:?}", proc_def_id);
                                     }
                                 }
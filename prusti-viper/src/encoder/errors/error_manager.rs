@@ -46,6 +46,84 @@ pub enum BuiltinMethodKind {
     Assign,
 }
 
+/// A component of a lexicographic termination measure or loop variant.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MeasureComponent {
+    /// The position of the component, starting from the most significant one.
+    pub index: usize,
+    /// The number of components of the measure.
+    pub count: usize,
+}
+
+impl MeasureComponent {
+    /// Identifies the component in an error message about the measure.
+    fn describe(self) -> String {
+        if self.count == 1 {
+            String::new()
+        } else if self.index == 0 {
+            self.describe_position()
+        } else {
+            format!(
+                " (component {} of {}, while the preceding components are unchanged)",
+                self.index + 1,
+                self.count
+            )
+        }
+    }
+
+    /// Identifies the component in an error message about a condition that
+    /// does not depend on the other components.
+    fn describe_position(self) -> String {
+        if self.count == 1 {
+            String::new()
+        } else {
+            format!(" (component {} of {})", self.index + 1, self.count)
+        }
+    }
+}
+
+/// A call between two functions that might call each other recursively.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RecursiveCall {
+    /// The name of the calling function.
+    pub caller: String,
+    /// The name of the called function.
+    pub callee: String,
+    /// The names of the functions in the strongly connected component of the
+    /// call graph that contains the call.
+    pub cycle: Vec<String>,
+}
+
+impl RecursiveCall {
+    /// Identifies the call in an error message, unless it is a direct
+    /// recursive call.
+    fn describe(&self) -> String {
+        if self.caller == self.callee {
+            String::new()
+        } else {
+            format!(" from `{}` to `{}`", self.caller, self.callee)
+        }
+    }
+
+    /// Lists the functions of the recursive cycle, if there are several.
+    fn note(&self) -> Option<String> {
+        if let [init @ .., last] = self.cycle.as_slice() {
+            if !init.is_empty() {
+                let init = init
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Some(format!(
+                    "{init} and `{last}` might call each other recursively, so they share a \
+                    termination measure that has to decrease on every call between them"
+                ));
+            }
+        }
+        None
+    }
+}
+
 /// In case of verification error, this enum will contain additional information
 /// required to describe the error.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -77,17 +155,20 @@ pub enum ErrorCtxt {
     /// A generic loop variant error.
     LoopVariant,
     /// Loop Variant doesn't hold on entry or after iteration
-    LoopVariantOnEntry,
-    LoopVariantAfterIteration,
-    LoopVariantNonDecreased,
+    LoopVariantOnEntry(MeasureComponent),
+    LoopVariantAfterIteration(MeasureComponent),
+    LoopVariantNonDecreased(MeasureComponent),
     /// If a loop needs to terminate and no loop variant is provided
     UnexpectedReachableLoop,
     /// If a call needs to terminate and it does not necessarily terminate
     UnexpectedReachableCall,
     /// Termination measure of a call might not be lower
-    CallTerminationMeasureLower,
+    CallTerminationMeasureLower(MeasureComponent, RecursiveCall),
     /// The termination measure of a call might be negative
-    CallTerminationMeasureNonNegative,
+    CallTerminationMeasureNonNegative(MeasureComponent, RecursiveCall),
+    /// The termination measure might be negative at a call to a function
+    /// whose termination is trusted
+    TrustedCallTerminationMeasureNonNegative(MeasureComponent, RecursiveCall),
     /// Finding the value of the termination measure at the begin of a method unexpectedly caused an error
     UnexpectedAssignMethodTerminationMeasure,
     /// A Viper `assert false` that encodes the failure (panic) of an `assert` Rust terminator
@@ -256,6 +337,20 @@ impl<'tcx> ErrorManager<'tcx> {
             .and_then(|id| self.position_manager.def_id.get(&id).copied())
     }
 
+    /// Returns the span of the violated postcondition, if the verification
+    /// error is a postcondition that might not hold.
+    pub fn get_failing_postcondition(&self, ver_error: &VerificationError) -> Option<&MultiSpan> {
+        let pos_id: u64 = ver_error.offending_pos_id.as_ref()?.parse().ok()?;
+        match (ver_error.full_id.as_str(), self.error_contexts.get(&pos_id)?) {
+            ("assert.failed:assertion.false", ErrorCtxt::ExhaleMethodPostcondition)
+            | ("assert.failed:assertion.false", ErrorCtxt::AssertMethodPostcondition) => {
+                let reason_pos_id: u64 = ver_error.reason_pos_id.as_ref()?.parse().ok()?;
+                self.position_manager.source_span.get(&reason_pos_id)
+            }
+            _ => None,
+        }
+    }
+
     pub fn translate_verification_error(&self, ver_error: &VerificationError) -> PrustiError {
         debug!("Verification error: {:?}", ver_error);
         let opt_pos_id: Option<u64> = match ver_error.offending_pos_id {
@@ -666,35 +761,54 @@ impl<'tcx> ErrorManager<'tcx> {
                 ).set_help("Consider marking the called function with `#[terminates]` or making it `#[pure]`\nAlternatively, remove the `#[terminates] attribute of this function.")
             }
 
-            ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureLower) => {
-                PrustiError::verification(
-                    "the termination measure of this call is not necessarily lower".to_string(),
+            ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureLower(component, call)) => {
+                let error = PrustiError::verification(
+                    format!("the termination measure of this call{} is not necessarily lower{}", call.describe(), component.describe()),
                     error_span
-                )
+                );
+                match call.note() {
+                    Some(note) => error.add_note(note, None),
+                    None => error,
+                }
             }
 
-            ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureNonNegative) => {
-                PrustiError::verification(
-                    "the termination measure of this call might become negative".to_string(),
+            ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureNonNegative(component, call)) => {
+                let error = PrustiError::verification(
+                    format!("the termination measure of this call{} might become negative{}", call.describe(), component.describe()),
                     error_span
-                )
+                );
+                match call.note() {
+                    Some(note) => error.add_note(note, None),
+                    None => error,
+                }
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::TrustedCallTerminationMeasureNonNegative(component, call)) => {
+                let error = PrustiError::verification(
+                    format!("the termination measure might be negative at this call{}, whose termination is trusted{}", call.describe(), component.describe_position()),
+                    error_span
+                );
+                match call.note() {
+                    Some(note) => error.add_note(note, None),
+                    None => error,
+                }
             }
 
-            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantOnEntry) => {
+            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantOnEntry(component)) => {
                 PrustiError::verification(
-                    "The loop variant might not hold on entry (is lower or equal to zero)".to_string(),
+                    format!("The loop variant might not hold on entry (is lower or equal to zero){}", component.describe()),
                     error_span
                 )
             }
-            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantNonDecreased) => {
+            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantNonDecreased(component)) => {
                 PrustiError::verification(
-                    "The loop variant might not have decreased".to_string(),
+                    format!("The loop variant might not have decreased{}", component.describe()),
                     error_span
                 )
             }
-            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantAfterIteration) => {
+            ("assert.failed:assertion.false", ErrorCtxt::LoopVariantAfterIteration(component)) => {
                 PrustiError::verification(
-                    "The loop variant might go below zero while the loop continues".to_string(),
+                    format!("The loop variant might go below zero while the loop continues{}", component.describe()),
                     error_span
                 )
             }
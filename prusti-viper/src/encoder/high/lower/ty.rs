@@ -41,6 +41,7 @@ impl IntoPolymorphic<vir_poly::Type> for vir_high::Type {
             vir_high::Type::Str => vir_poly::Type::typed_ref("Str"),
             vir_high::Type::Closure(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
             vir_high::Type::FunctionDef(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
+            vir_high::Type::TraitObject(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
             vir_high::Type::Projection(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
             vir_high::Type::Unsupported(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
             vir_high::Type::Trusted(ty) => vir_poly::Type::TypedRef(ty.lower(encoder)),
@@ -145,6 +146,12 @@ impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::FunctionDef {
     }
 }
 
+impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::TraitObject {
+    fn lower(&self, _encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
+        vir_poly::TypedRef::new(self.get_identifier(), Vec::new())
+    }
+}
+
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Projection {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
         vir_poly::TypedRef::new(self.name.clone(), self.arguments.lower(encoder))
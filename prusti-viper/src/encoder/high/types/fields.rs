@@ -31,6 +31,7 @@ pub(crate) fn create_value_field(ty: vir::Type) -> EncodingResult<vir::FieldDecl
         | vir::Type::Enum(_)
         | vir::Type::Closure(_)
         | vir::Type::FunctionDef(_)
+        | vir::Type::TraitObject(_)
         | vir::Type::FnPointer
         | vir::Type::TypeVar(_)
         | vir::Type::Projection(_) => vir::FieldDecl::new("val_ref", 0usize, ty),
@@ -11,6 +11,7 @@ use prusti_interface::environment::mir_sets::PlaceSet;
 use prusti_interface::environment::{BasicBlockIndex, LoopAnalysisError, PermissionForest, ProcedureLoops, Procedure};
 use prusti_interface::utils;
 use prusti_rustc_interface::middle::{mir, ty};
+use prusti_rustc_interface::span::DesugaringKind;
 use log::{trace, debug};
 
 pub enum LoopEncoderError {
@@ -130,6 +131,101 @@ impl<'p, 'tcx: 'p> LoopEncoder<'p, 'tcx> {
             .any(|def_init_place| utils::is_prefix(place, *def_init_place))
     }
 
+    /// If the loop is the desugaring of a `for` loop, return the place of the
+    /// iterator on which `Iterator::next` is called in each iteration.
+    pub fn get_for_loop_iterator(&self, loop_head: BasicBlockIndex) -> Option<mir::Place<'tcx>> {
+        let next_fn = self.tcx.lang_items().next_fn()?;
+        let mir = self.mir();
+        for &bb in self.loops().get_loop_body(loop_head) {
+            if self.get_loop_head(bb) != Some(loop_head) {
+                continue;
+            }
+            let bb_data = &mir[bb];
+            let terminator = bb_data.terminator();
+            if !terminator.source_info.span.is_desugaring(DesugaringKind::ForLoop) {
+                continue;
+            }
+            if let mir::TerminatorKind::Call { ref func, ref args, .. } = terminator.kind {
+                match func.ty(mir, self.tcx).kind() {
+                    ty::TyKind::FnDef(def_id, _) if *def_id == next_fn => {}
+                    _ => continue,
+                }
+                // The iterator is mutably borrowed just before the call
+                let arg_place = args.get(0)?.place()?;
+                return bb_data.statements.iter().rev().find_map(|stmt| match stmt.kind {
+                    mir::StatementKind::Assign(box (
+                        lhs,
+                        mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, iter_place),
+                    )) if lhs == arg_place => Some(iter_place),
+                    _ => None,
+                });
+            }
+        }
+        None
+    }
+
+    /// If the loop is the desugaring of a `for` loop, return the location of
+    /// the `IntoIterator::into_iter` call that creates its iterator.
+    pub fn get_for_loop_into_iter(&self, loop_head: BasicBlockIndex) -> Option<mir::Location> {
+        let iter_place = self.get_for_loop_iterator(loop_head)?;
+        let into_iter_fn = self.tcx.lang_items().into_iter_fn()?;
+        let mir = self.mir();
+        // The result of the call is moved to the iterator before the loop
+        let mut sources = vec![iter_place];
+        for bb_data in mir.basic_blocks.iter() {
+            for stmt in &bb_data.statements {
+                if let mir::StatementKind::Assign(box (
+                    lhs,
+                    mir::Rvalue::Use(mir::Operand::Move(rhs)),
+                )) = stmt.kind {
+                    if lhs == iter_place {
+                        sources.push(rhs);
+                    }
+                }
+            }
+        }
+        for (bb, bb_data) in mir.basic_blocks.iter_enumerated() {
+            if let mir::TerminatorKind::Call { ref func, destination, .. } = bb_data.terminator().kind {
+                match func.ty(mir, self.tcx).kind() {
+                    ty::TyKind::FnDef(def_id, _) if *def_id == into_iter_fn => {}
+                    _ => continue,
+                }
+                if sources.contains(&destination) {
+                    return Some(mir.terminator_loc(bb));
+                }
+            }
+        }
+        None
+    }
+
+    /// If the call at `location` creates the iterator of a `for` loop, return
+    /// the head of that loop.
+    pub fn get_for_loop_of_into_iter(&self, location: mir::Location) -> Option<BasicBlockIndex> {
+        self.loops()
+            .loop_heads
+            .iter()
+            .copied()
+            .find(|&loop_head| self.get_for_loop_into_iter(loop_head) == Some(location))
+    }
+
+    /// If `place` is moved into the `IntoIterator::into_iter` call that
+    /// creates the iterator of a `for` loop, return the head of that loop.
+    pub fn get_for_loop_of_into_iter_arg(&self, place: mir::Place<'tcx>) -> Option<BasicBlockIndex> {
+        let mir = self.mir();
+        self.loops().loop_heads.iter().copied().find(|&loop_head| {
+            let location = match self.get_for_loop_into_iter(loop_head) {
+                Some(location) => location,
+                None => return false,
+            };
+            match mir[location.block].terminator().kind {
+                mir::TerminatorKind::Call { ref args, .. } => {
+                    args.get(0).and_then(|arg| arg.place()) == Some(place)
+                }
+                _ => false,
+            }
+        })
+    }
+
     /// Return the block at whose end the loop invariant holds
     pub fn get_loop_invariant_block(
         &self,
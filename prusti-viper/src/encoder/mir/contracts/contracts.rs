@@ -109,15 +109,15 @@ impl<L: fmt::Debug, P: fmt::Debug> ProcedureContractGeneric<L, P> {
         &'a self,
         env: &'a Environment<'tcx>,
         substs: SubstsRef<'tcx>,
-    ) -> Option<(LocalDefId, SubstsRef<'tcx>)> {
-        match self.specification.terminates {
+    ) -> Option<(&'a [LocalDefId], SubstsRef<'tcx>)> {
+        match &self.specification.terminates {
             typed::SpecificationItem::Empty => None,
-            typed::SpecificationItem::Inherent(t) | typed::SpecificationItem::Refined(_, t) => {
-                t.map(|inherent_def_id| (inherent_def_id, substs))
-            }
-            typed::SpecificationItem::Inherited(t) => t.map(|inherited_def_id| {
+            typed::SpecificationItem::Inherent(t) | typed::SpecificationItem::Refined(_, t) => t
+                .as_ref()
+                .map(|inherent_def_ids| (inherent_def_ids.as_slice(), substs)),
+            typed::SpecificationItem::Inherited(t) => t.as_ref().map(|inherited_def_ids| {
                 (
-                    inherited_def_id,
+                    inherited_def_ids.as_slice(),
                     // Same comment as `functional_precondition` applies.
                     env.query
                         .find_trait_method_substs(self.def_id, substs)
@@ -137,13 +137,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
         }
 
         // Construct the variant info.
-        let loop_variant = encoded_variant_specs.into_iter().next().map(|spec| {
-            let var = self.fresh_ghost_variable(
-                "loop_variant",
-                vir_high::Type::Int(vir_high::ty::Int::Unbounded),
-            );
-            vir_high::ast::statement::LoopVariant { var, expr: spec }
-        });
+        let loop_variants = encoded_variant_specs
+            .into_iter()
+            .map(|spec| {
+                let var = self.fresh_ghost_variable(
+                    "loop_variant",
+                    vir_high::Type::Int(vir_high::ty::Int::Unbounded),
+                );
+                vir_high::ast::statement::LoopVariant { var, expr: spec }
+            })
+            .collect();
 
         // Construct the invariant info.
         let loop_invariant = vir_high::Statement::loop_invariant_no_pos(
@@ -151,7 +154,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
             encoded_back_edges,
             maybe_modified_places,
             encoded_invariant_specs,
-            loop_variant,
+            loop_variants,
         );
         let statement = self.set_statement_error(
             invariant_location,
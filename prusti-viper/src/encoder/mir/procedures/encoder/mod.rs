@@ -117,7 +117,7 @@ pub(super) fn encode_procedure<'v, 'tcx: 'v>(
         points_to_reborrow,
         reborrow_lifetimes_to_remove_for_block,
         current_basic_block,
-        termination_variable: None,
+        termination_variables: Vec::new(),
     };
     procedure_encoder.encode()
 }
@@ -157,7 +157,7 @@ struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     points_to_reborrow: BTreeSet<vir_high::Local>,
     reborrow_lifetimes_to_remove_for_block: BTreeMap<mir::BasicBlock, BTreeSet<String>>,
     current_basic_block: Option<mir::BasicBlock>,
-    termination_variable: Option<vir_high::VariableDecl>,
+    termination_variables: Vec<vir_high::VariableDecl>,
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
@@ -567,7 +567,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
         if let Some(statement) = self.loop_invariant_encoding.remove(&bb) {
             if self.needs_termination(bb)
-                && statement.clone().unwrap_loop_invariant().variant.is_none()
+                && statement.clone().unwrap_loop_invariant().variants.is_empty()
             {
                 block_builder.add_statement(self.encoder.set_statement_error_ctxt(
                     vir_high::Statement::assert_no_pos(false.into()),
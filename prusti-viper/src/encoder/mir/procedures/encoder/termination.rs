@@ -1,5 +1,9 @@
+use super::super::measures::lexicographic_conditions;
 use crate::encoder::{
-    errors::{ErrorCtxt, SpannedEncodingError, SpannedEncodingResult, WithSpan},
+    errors::{
+        ErrorCtxt, MeasureComponent, RecursiveCall, SpannedEncodingError, SpannedEncodingResult,
+        WithSpan,
+    },
     mir::{
         contracts::{ContractsEncoderInterface, ProcedureContractMirDef},
         errors::ErrorInterface,
@@ -8,7 +12,11 @@ use crate::encoder::{
     },
 };
 use prusti_rustc_interface::{
-    middle::{mir::BasicBlock, ty::subst::SubstsRef},
+    hir::def_id::{DefId, LocalDefId},
+    middle::{
+        mir::{BasicBlock, TerminatorKind},
+        ty::subst::SubstsRef,
+    },
     span::Span,
 };
 use vir_crate::{
@@ -17,8 +25,9 @@ use vir_crate::{
 };
 
 pub(super) enum TerminationMeasure {
-    /// A termination measure of type Int.
-    Int(vir_high::Expression),
+    /// A termination measure of type Int or, if it has several components, a
+    /// lexicographic tuple of them. The most significant component is first.
+    Int(Vec<vir_high::Expression>),
     /// The annotated item is trusted to always terminate.
     Trusted,
 }
@@ -39,7 +48,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
     ) -> SpannedEncodingResult<TerminationMeasure> {
         assert!(self.encoder.terminates(self.def_id, None));
 
-        let (expr, expr_substs) = procedure_contract
+        let (components, expr_substs) = procedure_contract
             .functional_termination_measure(self.encoder.env(), call_substs)
             .ok_or_else(|| {
                 SpannedEncodingError::incorrect(
@@ -48,21 +57,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
                 )
             })?;
 
-        let expression = self.encoder.encode_assertion_high(
-            expr.to_def_id(),
-            None,
-            arguments,
-            None,
-            self.def_id,
-            expr_substs,
-        )?;
-        if let vir_high::Expression::FuncApp(vir_high::FuncApp { function_name, .. }) = &expression
-        {
-            if function_name == "m_prusti_contracts$$prusti_terminates_trusted" {
-                return Ok(TerminationMeasure::Trusted);
+        let mut expressions = Vec::with_capacity(components.len());
+        for component in components {
+            let expression = self.encoder.encode_assertion_high(
+                component.to_def_id(),
+                None,
+                arguments,
+                None,
+                self.def_id,
+                expr_substs,
+            )?;
+            if let vir_high::Expression::FuncApp(vir_high::FuncApp { function_name, .. }) =
+                &expression
+            {
+                if function_name == "m_prusti_contracts$$prusti_terminates_trusted" {
+                    return Ok(TerminationMeasure::Trusted);
+                }
             }
+            expressions.push(expression);
         }
-        Ok(TerminationMeasure::Int(expression))
+        Ok(TerminationMeasure::Int(expressions))
     }
 
     pub(super) fn encode_termination_initialization(
@@ -80,6 +94,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
             arguments.push(self.encode_local(local)?.into());
         }
 
+        let mut statements = Vec::new();
         if self.encoder.terminates(self.def_id, None) && self.check_mode != CheckMode::CoreProof {
             let termination_expr = self.encode_termination_expression(
                 &procedure_contract,
@@ -87,27 +102,27 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
                 substs,
                 &arguments,
             )?;
-            if let TerminationMeasure::Int(termination_expr) = termination_expr {
-                let term_var = self.fresh_ghost_variable(
-                    "termination_var",
-                    vir_high::Type::Int(vir_high::ty::Int::Unbounded),
-                );
-                self.termination_variable = Some(term_var.clone());
-                let assign_stmt =
-                    vir_high::Statement::ghost_assign_no_pos(term_var.into(), termination_expr);
-                let assign_stmt = self.encoder.set_statement_error_ctxt(
-                    assign_stmt,
-                    mir_span,
-                    ErrorCtxt::UnexpectedAssignMethodTerminationMeasure,
-                    self.def_id,
-                )?;
-                Ok(vec![assign_stmt])
-            } else {
-                Ok(vec![])
+            if let TerminationMeasure::Int(termination_exprs) = termination_expr {
+                self.check_recursive_termination_measures(termination_exprs.len(), mir_span)?;
+                for termination_expr in termination_exprs {
+                    let term_var = self.fresh_ghost_variable(
+                        "termination_var",
+                        vir_high::Type::Int(vir_high::ty::Int::Unbounded),
+                    );
+                    self.termination_variables.push(term_var.clone());
+                    let assign_stmt =
+                        vir_high::Statement::ghost_assign_no_pos(term_var.into(), termination_expr);
+                    let assign_stmt = self.encoder.set_statement_error_ctxt(
+                        assign_stmt,
+                        mir_span,
+                        ErrorCtxt::UnexpectedAssignMethodTerminationMeasure,
+                        self.def_id,
+                    )?;
+                    statements.push(assign_stmt);
+                }
             }
-        } else {
-            Ok(vec![])
         }
+        Ok(statements)
     }
 
     pub(super) fn encode_termination_measure_call_assertion(
@@ -137,36 +152,211 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
             return Ok(());
         }
 
-        let Some(term_var) = self.termination_variable.clone() else {
+        if self.termination_variables.is_empty() {
             return Ok(());
-        };
+        }
+        let call = self.recursive_call(called_fun, call_substs);
+        let term_vars: Vec<vir_high::Expression> = self
+            .termination_variables
+            .iter()
+            .cloned()
+            .map(vir_high::Expression::from)
+            .collect();
+        let count = term_vars.len();
         let term_ty = vir_high::Type::Int(vir_high::ty::Int::Unbounded);
+        let non_negative = |term_var: &vir_high::Expression, _: &vir_high::Expression| {
+            let zero = vir_high::Expression::constant_no_pos(0.into(), term_ty.clone());
+            vir_high::Expression::greater_equals(term_var.clone(), zero)
+        };
 
         // called termination measure is lower
         let call_expr =
             self.encode_termination_expression(procedure_contract, span, call_substs, arguments)?;
-        if let TerminationMeasure::Int(call_expr) = call_expr {
-            let cond = vir_high::Expression::greater_than(term_var.clone().into(), call_expr);
+        let non_negative_assertions = if let TerminationMeasure::Int(call_exprs) = call_expr {
+            if call_exprs.len() != count {
+                return Err(SpannedEncodingError::incorrect(
+                    format!(
+                        "the termination measure of the called function has {} components, \
+                        but the one of the calling function has {}",
+                        call_exprs.len(),
+                        count
+                    ),
+                    span,
+                ));
+            }
+            let lower_conds =
+                lexicographic_conditions(&term_vars, &call_exprs, |term_var, call_expr| {
+                    vir_high::Expression::greater_than(term_var.clone(), call_expr.clone())
+                });
+            for (index, cond) in lower_conds.into_iter().enumerate() {
+                let assert_statement = self.encoder.set_statement_error_ctxt(
+                    vir_high::Statement::assert_no_pos(cond),
+                    span,
+                    ErrorCtxt::CallTerminationMeasureLower(
+                        MeasureComponent { index, count },
+                        call.clone(),
+                    ),
+                    self.def_id,
+                )?;
+                block_builder.add_statement(assert_statement);
+            }
+            // the component that decreases should be non-negative
+            lexicographic_conditions(&term_vars, &call_exprs, non_negative)
+                .into_iter()
+                .enumerate()
+                .map(|(index, cond)| {
+                    let component = MeasureComponent { index, count };
+                    (
+                        cond,
+                        ErrorCtxt::CallTerminationMeasureNonNegative(component, call.clone()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        } else {
+            // the measure of the callee is unknown, so every component should
+            // be non-negative, regardless of the other components
+            term_vars
+                .iter()
+                .enumerate()
+                .map(|(index, term_var)| {
+                    let component = MeasureComponent { index, count };
+                    (
+                        non_negative(term_var, term_var),
+                        ErrorCtxt::TrustedCallTerminationMeasureNonNegative(
+                            component,
+                            call.clone(),
+                        ),
+                    )
+                })
+                .collect()
+        };
+
+        // called termination measure should be non-negative
+        for (cond, error_ctxt) in non_negative_assertions {
             let assert_statement = self.encoder.set_statement_error_ctxt(
                 vir_high::Statement::assert_no_pos(cond),
                 span,
-                ErrorCtxt::CallTerminationMeasureLower,
+                error_ctxt,
                 self.def_id,
             )?;
             block_builder.add_statement(assert_statement);
         }
 
-        // called termination measure should be non-negative
-        let zero = vir_high::Expression::constant_no_pos(0.into(), term_ty);
-        let cond = vir_high::Expression::greater_equals(term_var.into(), zero);
-        let assert_statement = self.encoder.set_statement_error_ctxt(
-            vir_high::Statement::assert_no_pos(cond),
-            span,
-            ErrorCtxt::CallTerminationMeasureNonNegative,
-            self.def_id,
-        )?;
-        block_builder.add_statement(assert_statement);
+        Ok(())
+    }
 
+    /// Checks that the functions that might call this one recursively share
+    /// its termination measure: they all have to terminate, and their measures
+    /// need to have the same number of components, unless they are trusted.
+    fn check_recursive_termination_measures(
+        &self,
+        count: usize,
+        span: Span,
+    ) -> SpannedEncodingResult<()> {
+        let env = self.encoder.env();
+        let call_graph = env.call_graph();
+        if !call_graph.is_recursive(self.def_id) {
+            return Ok(());
+        }
+        for member in call_graph.scc(self.def_id).unwrap() {
+            let member = member.to_def_id();
+            if member == self.def_id {
+                continue;
+            }
+            let name = env.name.get_absolute_item_name(member);
+            if !self.encoder.terminates(member, None) {
+                let mut error = SpannedEncodingError::incorrect(
+                    format!(
+                        "this function might be called recursively by `{name}`, which is not \
+                        marked with `#[terminates]`"
+                    ),
+                    span,
+                );
+                error.add_note(
+                    "the functions that might call each other recursively need to share a \
+                    termination measure",
+                    Some(env.query.get_def_span(member).into()),
+                );
+                return Err(error);
+            }
+            let substs = env.query.identity_substs(member);
+            let contract = self
+                .encoder
+                .get_mir_procedure_contract_for_def(member, substs)
+                .with_span(span)?;
+            if let Some((components, measure_substs)) =
+                contract.functional_termination_measure(env, substs)
+            {
+                if components.len() != count
+                    && !self.is_trusted_termination_measure(components, measure_substs)
+                {
+                    let mut error = SpannedEncodingError::incorrect(
+                        format!(
+                            "the termination measure of this function has a different number \
+                            of components ({}) than the one of `{name}` ({}), which might call \
+                            it recursively",
+                            count,
+                            components.len()
+                        ),
+                        span,
+                    );
+                    error.add_note(
+                        "the functions that might call each other recursively need to share a \
+                        termination measure",
+                        Some(env.query.get_def_span(member).into()),
+                    );
+                    return Err(error);
+                }
+            }
+        }
         Ok(())
     }
+
+    /// Checks whether a termination measure is `#[terminates(trusted)]`.
+    fn is_trusted_termination_measure(
+        &self,
+        components: &[LocalDefId],
+        substs: SubstsRef<'tcx>,
+    ) -> bool {
+        let env = self.encoder.env();
+        components.iter().any(|component| {
+            let body = env
+                .body
+                .get_spec_body(component.to_def_id(), substs, self.def_id);
+            body.basic_blocks.iter().any(|block| {
+                matches!(
+                    &block.terminator().kind,
+                    TerminatorKind::Call { func, .. } if func
+                        .const_fn_def()
+                        .map_or(false, |(def_id, _)| {
+                            env.name.get_absolute_item_name(def_id)
+                                == "prusti_contracts::prusti_terminates_trusted"
+                        })
+                )
+            })
+        })
+    }
+
+    /// Describes a call to a function that might call the caller back.
+    fn recursive_call(&self, called_def_id: DefId, call_substs: SubstsRef<'tcx>) -> RecursiveCall {
+        let env = self.encoder.env();
+        let (callee_def_id, _) =
+            env.query
+                .resolve_method_call(self.def_id, called_def_id, call_substs);
+        let cycle = env
+            .call_graph()
+            .scc(self.def_id)
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|member| env.name.get_absolute_item_name(member.to_def_id()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        RecursiveCall {
+            caller: env.name.get_absolute_item_name(self.def_id),
+            callee: env.name.get_absolute_item_name(callee_def_id),
+            cycle,
+        }
+    }
 }
@@ -0,0 +1,51 @@
+//! Helpers for encoding lexicographic termination measures and loop variants.
+
+use vir_crate::{common::expression::BinaryOperationHelpers, high as vir_high};
+
+/// Splits a comparison of two lexicographic measures into one condition per
+/// component, so that a failure can be reported for the component that caused
+/// it. The condition of a component `i` is `compare(greater[i], lower[i])`,
+/// guarded by the measures being equal in all preceding components and, unless
+/// `i` is the last component, different in component `i`.
+///
+/// With `compare` being `>`, the conjunction of the conditions holds iff
+/// `greater` is lexicographically greater than `lower`. The condition of a
+/// measure with a single component is not guarded.
+pub(super) fn lexicographic_conditions(
+    greater: &[vir_high::Expression],
+    lower: &[vir_high::Expression],
+    compare: impl Fn(&vir_high::Expression, &vir_high::Expression) -> vir_high::Expression,
+) -> Vec<vir_high::Expression> {
+    assert_eq!(greater.len(), lower.len());
+    let mut conditions = Vec::with_capacity(greater.len());
+    let mut preceding_equal: Option<vir_high::Expression> = None;
+    for (index, (greater_component, lower_component)) in greater.iter().zip(lower).enumerate() {
+        let mut guard = preceding_equal.clone();
+        if index + 1 < greater.len() {
+            let different = vir_high::Expression::not_equals(
+                greater_component.clone(),
+                lower_component.clone(),
+            );
+            guard = Some(conjoin(guard, different));
+        }
+        let condition = compare(greater_component, lower_component);
+        conditions.push(match guard {
+            Some(guard) => vir_high::Expression::implies(guard, condition),
+            None => condition,
+        });
+        let equal =
+            vir_high::Expression::equals(greater_component.clone(), lower_component.clone());
+        preceding_equal = Some(conjoin(preceding_equal, equal));
+    }
+    conditions
+}
+
+fn conjoin(
+    left: Option<vir_high::Expression>,
+    right: vir_high::Expression,
+) -> vir_high::Expression {
+    match left {
+        Some(left) => vir_high::Expression::and(left, right),
+        None => right,
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod encoder;
 mod interface;
+mod measures;
 mod passes;
 
 pub(crate) use self::interface::{MirProcedureEncoderInterface, MirProcedureEncoderState};
@@ -1,5 +1,6 @@
+use super::super::measures::lexicographic_conditions;
 use crate::encoder::{
-    errors::{ErrorCtxt, SpannedEncodingResult},
+    errors::{ErrorCtxt, MeasureComponent, SpannedEncodingResult},
     mir::errors::ErrorInterface,
     Encoder,
 };
@@ -9,6 +10,7 @@ use vir_crate::{
     high::{
         self as vir_high,
         ast::{expression::visitors::ExpressionFolder, statement::visitors::StatementFolder},
+        operations::ty::Typed,
     },
 };
 
@@ -52,7 +54,7 @@ pub(in super::super) fn desugar_loops<'v, 'tcx: 'v>(
             .unwrap()
             .unwrap_loop_invariant();
 
-        if let Some(variant) = &loop_invariant.variant {
+        for variant in &loop_invariant.variants {
             let stmt = encoder.set_surrounding_error_context_for_statement(
                 vir_high::Statement::ghost_assign_no_pos(
                     vir_high::Expression::local_no_pos(variant.var.clone()),
@@ -78,17 +80,26 @@ pub(in super::super) fn desugar_loops<'v, 'tcx: 'v>(
             invariant_block.statements.push(statement);
         }
 
-        if let Some(variant) = &loop_invariant.variant {
+        let (variant_vars, variant_exprs) = split_variants(&loop_invariant.variants);
+        let count = variant_vars.len();
+        let zeros: Vec<_> = loop_invariant
+            .variants
+            .iter()
+            .map(|variant| {
+                vir_high::Expression::constant_no_pos(
+                    vir_high::expression::ConstantValue::Int(0),
+                    variant.var.ty.clone(),
+                )
+            })
+            .collect();
+        let on_entry_conds = lexicographic_conditions(&variant_vars, &zeros, |var, zero| {
+            vir_high::Expression::greater_than(var.clone(), zero.clone())
+        });
+        for (index, cond) in on_entry_conds.into_iter().enumerate() {
             let stmt = encoder.set_surrounding_error_context_for_statement(
-                vir_high::Statement::assert_no_pos(vir_high::Expression::greater_than(
-                    vir_high::Expression::local_no_pos(variant.var.clone()),
-                    vir_high::Expression::constant_no_pos(
-                        vir_high::expression::ConstantValue::Int(0),
-                        variant.var.ty.clone(),
-                    ),
-                )),
+                vir_high::Statement::assert_no_pos(cond),
                 loop_invariant.position,
-                ErrorCtxt::LoopVariantOnEntry,
+                ErrorCtxt::LoopVariantOnEntry(MeasureComponent { index, count }),
             )?;
             invariant_block.statements.push(stmt);
         }
@@ -111,7 +122,7 @@ pub(in super::super) fn desugar_loops<'v, 'tcx: 'v>(
             invariant_block.statements.push(statement);
         }
 
-        if let Some(variant) = &loop_invariant.variant {
+        for variant in &loop_invariant.variants {
             let stmt = encoder.set_surrounding_error_context_for_statement(
                 vir_high::Statement::ghost_havoc_no_pos(variant.var.clone()),
                 loop_invariant.position,
@@ -129,11 +140,11 @@ pub(in super::super) fn desugar_loops<'v, 'tcx: 'v>(
             invariant_block.statements.push(statement);
         }
 
-        if let Some(variant) = &loop_invariant.variant {
+        for (variant_var, variant_expr) in variant_vars.into_iter().zip(variant_exprs) {
             let stmt = encoder.set_surrounding_error_context_for_statement(
                 vir_high::Statement::assume_no_pos(vir_high::Expression::equals(
-                    vir_high::Expression::local_no_pos(variant.var.clone()),
-                    variant.expr.clone(),
+                    variant_var,
+                    variant_expr,
                 )),
                 loop_invariant.position,
                 ErrorCtxt::LoopVariant,
@@ -144,6 +155,22 @@ pub(in super::super) fn desugar_loops<'v, 'tcx: 'v>(
     Ok(procedure)
 }
 
+/// Splits the components of a loop variant into their variables and
+/// expressions.
+fn split_variants(
+    variants: &[vir_high::ast::statement::LoopVariant],
+) -> (Vec<vir_high::Expression>, Vec<vir_high::Expression>) {
+    variants
+        .iter()
+        .map(|variant| {
+            (
+                vir_high::Expression::local_no_pos(variant.var.clone()),
+                variant.expr.clone(),
+            )
+        })
+        .unzip()
+}
+
 fn find_loop_invariant(
     procedure: &vir_high::ProcedureDecl,
 ) -> Option<(vir_high::BasicBlockId, &vir_high::LoopInvariant)> {
@@ -204,30 +231,38 @@ fn duplicate_blocks<'v, 'tcx: 'v>(
                 )?;
                 block.statements.push(statement);
             }
-            if let Some(variant) = &loop_invariant.variant {
+            let (variant_vars, variant_exprs) = split_variants(&loop_invariant.variants);
+            let count = variant_vars.len();
+            let non_decreased_conds =
+                lexicographic_conditions(&variant_vars, &variant_exprs, |var, expr| {
+                    vir_high::Expression::less_than(expr.clone(), var.clone())
+                });
+            for (index, cond) in non_decreased_conds.into_iter().enumerate() {
                 block
                     .statements
                     .push(encoder.set_surrounding_error_context_for_statement(
-                        vir_high::Statement::assert_no_pos(vir_high::Expression::less_than(
-                            variant.expr.clone(),
-                            vir_high::Expression::local_no_pos(variant.var.clone()),
-                        )),
+                        vir_high::Statement::assert_no_pos(cond),
                         loop_invariant.position,
-                        ErrorCtxt::LoopVariantNonDecreased,
+                        ErrorCtxt::LoopVariantNonDecreased(MeasureComponent { index, count }),
                     )?);
-
+            }
+            let after_iteration_conds =
+                lexicographic_conditions(&variant_vars, &variant_exprs, |var, expr| {
+                    vir_high::Expression::greater_equals(
+                        expr.clone(),
+                        vir_high::Expression::constant_no_pos(
+                            vir_high::expression::ConstantValue::Int(0),
+                            var.get_type().clone(),
+                        ),
+                    )
+                });
+            for (index, cond) in after_iteration_conds.into_iter().enumerate() {
                 block
                     .statements
                     .push(encoder.set_surrounding_error_context_for_statement(
-                        vir_high::Statement::assert_no_pos(vir_high::Expression::greater_equals(
-                            variant.expr.clone(),
-                            vir_high::Expression::constant_no_pos(
-                                vir_high::expression::ConstantValue::Int(0),
-                                variant.var.ty.clone(),
-                            ),
-                        )),
+                        vir_high::Statement::assert_no_pos(cond),
                         loop_invariant.position,
-                        ErrorCtxt::LoopVariantAfterIteration,
+                        ErrorCtxt::LoopVariantAfterIteration(MeasureComponent { index, count }),
                     )?);
             }
             let statement = encoder.set_surrounding_error_context_for_statement(
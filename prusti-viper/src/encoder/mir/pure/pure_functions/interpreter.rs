@@ -12,7 +12,10 @@ use crate::encoder::{
         specifications::SpecificationsInterface,
         types::MirTypeEncoderInterface,
     },
-    mir_encoder::{MirEncoder, PlaceEncoder, PlaceEncoding, PRECONDITION_LABEL, WAND_LHS_LABEL},
+    mir_encoder::{
+        MirEncoder, PlaceEncoder, PlaceEncoding, FOR_LOOP_VISITED_VAR, PRECONDITION_LABEL,
+        WAND_LHS_LABEL,
+    },
     mir_interpreter::{BackwardMirInterpreter, ExprBackwardInterpreterState},
     snapshot::interface::SnapshotEncoderInterface,
     Encoder,
@@ -413,6 +416,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                             .map(|arg| self.mir_encoder.encode_operand_expr(arg))
                             .collect::<Result<_, _>>()
                             .with_span(span)?;
+                        let float_method_call = self
+                            .mir_encoder
+                            .encode_float_method_call(full_func_proc_name, args)
+                            .with_span(span)?;
+                        let spec_ent_extraction =
+                            self.encoder.encode_spec_ent_extraction(def_id, span, ty)?;
 
                         match full_func_proc_name {
                             "prusti_contracts::old" => {
@@ -583,6 +592,31 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            // the items visited by a `for` loop, resolved by the procedure encoder
+                            "prusti_contracts::visited" => {
+                                let visited = vir::LocalVar::new(
+                                    FOR_LOOP_VISITED_VAR,
+                                    encoded_lhs.get_type().clone(),
+                                );
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, visited.into());
+                                state
+                            }
+
+                            // arguments and result of the callable in a specification entailment
+                            _ if spec_ent_extraction.is_some() => {
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, spec_ent_extraction.unwrap());
+                                state
+                            }
+
+                            // methods of `f32` and `f64` with IEEE semantics
+                            _ if float_method_call.is_some() => {
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, float_method_call.unwrap());
+                                state
+                            }
+
                             // simple function call
                             _ => {
                                 let (called_def_id, call_substs) = self
@@ -1164,7 +1198,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                         state.substitute_value(&encoded_lhs, encoded_ref);
                     }
 
-                    mir::Rvalue::Cast(mir::CastKind::IntToInt, ref operand, dst_ty) => {
+                    mir::Rvalue::Cast(mir::CastKind::IntToInt, ref operand, dst_ty)
+                    | mir::Rvalue::Cast(mir::CastKind::FloatToInt, ref operand, dst_ty)
+                    | mir::Rvalue::Cast(mir::CastKind::FloatToFloat, ref operand, dst_ty)
+                    | mir::Rvalue::Cast(mir::CastKind::IntToFloat, ref operand, dst_ty) => {
                         let encoded_val = self.mir_encoder
                             .encode_cast_expr(operand, *dst_ty, span)?;
 
@@ -5,7 +5,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::encoder::{
-    errors::{EncodingError, EncodingResult, SpannedEncodingResult, WithSpan},
+    errors::{
+        EncodingError, EncodingResult, SpannedEncodingError, SpannedEncodingResult, WithSpan,
+    },
     high::types::HighTypeEncoderInterface,
     mir::{
         pure::{specifications::utils::extract_closure_from_ty, PureFunctionEncoderInterface},
@@ -280,6 +282,197 @@ pub(super) fn encode_quantifier<'tcx>(
     }
 }
 
+/// The variable that is bound by a specification entailment for the argument
+/// with index `arg_index` of the callable.
+pub(super) fn spec_ent_arg_var<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    arg_index: usize,
+    arg_ty: ty::Ty<'tcx>,
+) -> EncodingResult<vir_crate::polymorphic::LocalVar> {
+    Ok(vir_crate::polymorphic::LocalVar::new(
+        format!("_{}_ent_arg", arg_index),
+        encoder.encode_snapshot_type(arg_ty)?,
+    ))
+}
+
+/// The variable that is bound by a specification entailment for the result
+/// of the callable.
+pub(super) fn spec_ent_result_var<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    result_ty: ty::Ty<'tcx>,
+) -> EncodingResult<vir_crate::polymorphic::LocalVar> {
+    Ok(vir_crate::polymorphic::LocalVar::new(
+        "_ent_result",
+        encoder.encode_snapshot_type(result_ty)?,
+    ))
+}
+
+pub(super) fn encode_spec_entailment<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    span: Span,
+    encoded_args: Vec<vir_crate::polymorphic::Expr>,
+    parent_def_id: DefId,
+    substs: ty::subst::SubstsRef<'tcx>,
+) -> SpannedEncodingResult<vir_crate::polymorphic::Expr> {
+    // Specification entailments are encoded as:
+    //   specification_entailment(
+    //     <once>,
+    //     &<callable>,
+    //     ( // preconditions
+    //       || -> bool { <pre expr 1> },
+    //       ...,
+    //     ),
+    //     ( // postconditions
+    //       || -> bool { <post expr 1> },
+    //       ...,
+    //     ),
+    //   )
+    // where the arguments and the result of the callable are bound by
+    // the extraction functions generated next to the call. For a function
+    // pointer `f`, the entailment holds if
+    //   (forall args :: <pre> ==> fn_ptr$pre(f, args)) &&
+    //   (forall args, res :: <pre> && fn_ptr$post(f, args, res) ==> <post>)
+    // For closures and type parameters bounded by `Fn*` traits, the state
+    // of the callable may change between calls (`FnMut`), so the contract
+    // is required to hold in every state `s` of the callable:
+    //   (forall s, args :: <pre> ==> closure$pre(s, args)) &&
+    //   (forall s, args, res :: <pre> && closure$post(s, args, res) ==> <post>)
+
+    let callable_ty = substs.type_at(0);
+    let sig = match callable_ty.kind() {
+        ty::TyKind::FnPtr(_) | ty::TyKind::Closure(..) | ty::TyKind::Param(_) => encoder
+            .env()
+            .query
+            .get_callable_sig(callable_ty, parent_def_id),
+        _ => None,
+    };
+    let sig = match sig {
+        Some((sig, _)) => sig,
+        None => {
+            return Err(SpannedEncodingError::unsupported(
+                format!(
+                    "specification entailments are only supported for function pointers, \
+                    closures and type parameters bounded by `Fn`, `FnMut` or `FnOnce`, \
+                    not for {}",
+                    callable_ty
+                ),
+                span,
+            ));
+        }
+    };
+    let is_fn_ptr = callable_ty.is_fn_ptr();
+
+    let mut arg_vars = vec![];
+    let mut bounds = vec![];
+    for (arg_index, arg_ty) in sig.inputs().iter().enumerate() {
+        let arg_var = spec_ent_arg_var(encoder, arg_index, *arg_ty).with_span(span)?;
+        if config::check_overflows() {
+            bounds.extend(encoder.encode_type_bounds(&arg_var.clone().into(), *arg_ty));
+        } else if config::encode_unsigned_num_constraint() {
+            if let ty::TyKind::Uint(_) = arg_ty.kind() {
+                let expr =
+                    vir_crate::polymorphic::Expr::le_cmp(0u32.into(), arg_var.clone().into());
+                bounds.push(expr);
+            }
+        }
+        arg_vars.push(arg_var);
+    }
+    let result_var = spec_ent_result_var(encoder, sig.output()).with_span(span)?;
+
+    let encode_conditions = |conditions: vir_crate::polymorphic::Expr,
+                             conditions_ty: ty::Ty<'tcx>|
+     -> SpannedEncodingResult<vir_crate::polymorphic::Expr> {
+        let mut encoded_conditions = vec![];
+        for (condition_idx, ty_condition) in conditions_ty.tuple_fields().into_iter().enumerate() {
+            let (condition_def_id, condition_substs, condition_span, _, _) =
+                extract_closure_from_ty(encoder.env().query, ty_condition);
+            let condition_field = encoder
+                .encode_raw_ref_field(format!("tuple_{}", condition_idx), ty_condition)
+                .with_span(condition_span)?;
+            encoded_conditions.push(inline_closure(
+                encoder,
+                condition_def_id,
+                conditions.clone().field(condition_field),
+                vec![],
+                parent_def_id,
+                condition_substs,
+            )?);
+        }
+        Ok(encoded_conditions.into_iter().conjoin())
+    };
+    let encoded_pres = encode_conditions(encoded_args[2].clone(), substs.type_at(1))?;
+    let encoded_posts = encode_conditions(encoded_args[3].clone(), substs.type_at(2))?;
+
+    // the state of the callable (fixed for function pointers)
+    let (callable, state_vars) = if is_fn_ptr {
+        (
+            vir_crate::polymorphic::Expr::snap_app(encoded_args[1].clone()),
+            vec![],
+        )
+    } else {
+        let state_var = vir_crate::polymorphic::LocalVar::new(
+            "_ent_callable",
+            encoder.encode_snapshot_type(callable_ty).with_span(span)?,
+        );
+        (state_var.clone().into(), vec![state_var])
+    };
+    let encoded_arg_vars: Vec<vir_crate::polymorphic::Expr> =
+        arg_vars.iter().cloned().map(|var| var.into()).collect();
+    let callable_pre = encoder
+        .encode_callable_spec_func_app(
+            callable_ty,
+            sig,
+            callable.clone(),
+            encoded_arg_vars.clone(),
+            None,
+        )
+        .with_span(span)?;
+    let callable_post = encoder
+        .encode_callable_spec_func_app(
+            callable_ty,
+            sig,
+            callable,
+            encoded_arg_vars,
+            Some(result_var.clone().into()),
+        )
+        .with_span(span)?;
+
+    let required_pre = if bounds.is_empty() {
+        encoded_pres
+    } else {
+        vir_crate::polymorphic::Expr::and(bounds.into_iter().conjoin(), encoded_pres)
+    };
+    let pre_entailment =
+        vir_crate::polymorphic::Expr::implies(required_pre.clone(), callable_pre.clone());
+    let mut pre_vars = state_vars;
+    pre_vars.extend(arg_vars);
+    let pre_entailment = if pre_vars.is_empty() {
+        pre_entailment
+    } else {
+        vir_crate::polymorphic::Expr::forall(
+            pre_vars.clone(),
+            vec![vir_crate::polymorphic::Trigger::new(vec![callable_pre])],
+            pre_entailment,
+        )
+    };
+    let mut post_vars = pre_vars;
+    post_vars.push(result_var);
+    let post_entailment = vir_crate::polymorphic::Expr::forall(
+        post_vars,
+        vec![vir_crate::polymorphic::Trigger::new(vec![
+            callable_post.clone()
+        ])],
+        vir_crate::polymorphic::Expr::implies(
+            vir_crate::polymorphic::Expr::and(required_pre, callable_post),
+            encoded_posts,
+        ),
+    );
+    Ok(vir_crate::polymorphic::Expr::and(
+        pre_entailment,
+        post_entailment,
+    ))
+}
+
 fn find_quantifier_depth(expr: &vir_crate::polymorphic::Expr) -> usize {
     use vir_crate::polymorphic::ExprWalker;
     struct DepthChecker {
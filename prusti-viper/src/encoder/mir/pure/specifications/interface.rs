@@ -14,7 +14,10 @@ use crate::encoder::{
                 encoder_high::{
                     encode_quantifier_high, inline_closure_high, inline_spec_item_high,
                 },
-                encoder_poly::{encode_quantifier, inline_closure, inline_spec_item},
+                encoder_poly::{
+                    encode_quantifier, encode_spec_entailment, inline_closure, inline_spec_item,
+                    spec_ent_arg_var, spec_ent_result_var,
+                },
             },
             PureEncodingContext, PureFunctionBackwardInterpreter,
         },
@@ -23,9 +26,13 @@ use crate::encoder::{
     mir_interpreter::run_backward_interpretation_point_to_point,
     snapshot::interface::SnapshotEncoderInterface,
 };
+use prusti_interface::utils::{has_prusti_attr, read_prusti_attr};
 use prusti_rustc_interface::{
     hir::def_id::DefId,
-    middle::{mir, ty::subst::SubstsRef},
+    middle::{
+        mir,
+        ty::{subst::SubstsRef, Ty},
+    },
     span::Span,
 };
 use vir_crate::{
@@ -71,6 +78,16 @@ pub(crate) trait SpecificationEncoderInterface<'tcx> {
         substs: SubstsRef<'tcx>,
     ) -> SpannedEncodingResult<vir_poly::Expr>;
 
+    /// Encodes a call to one of the functions that extract an argument or
+    /// the result of the callable in a specification entailment. Returns
+    /// `None` if `def_id` is not such a function.
+    fn encode_spec_ent_extraction(
+        &self,
+        def_id: DefId,
+        span: Span,
+        ty: Ty<'tcx>,
+    ) -> SpannedEncodingResult<Option<vir_poly::Expr>>;
+
     #[allow(clippy::too_many_arguments)]
     fn encode_assertion(
         &self,
@@ -246,6 +263,9 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
                 parent_def_id,
                 substs,
             ),
+            "prusti_contracts::specification_entailment" => {
+                encode_spec_entailment(self, span, encoded_args, parent_def_id, substs)
+            }
             "prusti_contracts::snap" => Ok(vir_poly::Expr::snap_app(encoded_args[0].clone())),
             "prusti_contracts::snapshot_equality" => Ok(vir_poly::Expr::eq_cmp(
                 vir_poly::Expr::snap_app(encoded_args[0].clone()),
@@ -255,6 +275,25 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
         }
     }
 
+    fn encode_spec_ent_extraction(
+        &self,
+        def_id: DefId,
+        span: Span,
+        ty: Ty<'tcx>,
+    ) -> SpannedEncodingResult<Option<vir_poly::Expr>> {
+        let attrs = self.env().query.get_attributes(def_id);
+        if let Some(arg_index) = read_prusti_attr("spec_ent_arg", attrs) {
+            let arg_index = arg_index.parse().unwrap();
+            let arg_var = spec_ent_arg_var(self, arg_index, ty).with_span(span)?;
+            Ok(Some(arg_var.into()))
+        } else if has_prusti_attr(attrs, "spec_ent_result") {
+            let result_var = spec_ent_result_var(self, ty).with_span(span)?;
+            Ok(Some(result_var.into()))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn encode_assertion(
         &self,
         assertion: &DefId,
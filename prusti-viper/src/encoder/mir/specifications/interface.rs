@@ -201,10 +201,9 @@ impl<'v, 'tcx: 'v> SpecificationsInterface<'tcx> for super::super::super::Encode
             .and_then(|spec| {
                 spec.terminates
                     .extract_with_selective_replacement()
-                    .copied()
+                    .map(Option::is_some)
             })
-            .unwrap_or(None)
-            .is_some();
+            .unwrap_or(false);
         trace!("terminates {:?} = {}", query, result);
         result
     }
@@ -20,7 +20,10 @@ use prusti_rustc_interface::{
     hir::def_id::DefId,
     middle::{mir, ty},
 };
-use vir_crate::high::{self as vir, operations::ty::Typed};
+use vir_crate::{
+    common::identifier::WithIdentifier,
+    high::{self as vir, operations::ty::Typed},
+};
 
 pub struct TypeEncoder<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
@@ -241,9 +244,28 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 lifetimes,
             ),
 
-            ty::TyKind::Dynamic(..) => vir::Type::unsupported("dynamic".to_string()),
+            ty::TyKind::Dynamic(predicates, ..) => match predicates.principal() {
+                Some(principal) => {
+                    let tcx = self.encoder.env().tcx();
+                    let principal = tcx.erase_late_bound_regions(principal);
+                    let mut arguments = self.encode_substs(principal.substs);
+                    for projection in predicates.projection_bounds() {
+                        let projection = tcx.erase_late_bound_regions(projection);
+                        if let Some(ty) = projection.term.ty() {
+                            arguments.push(self.encoder.encode_type_high(ty)?);
+                        }
+                    }
+                    vir::Type::trait_object(
+                        self.encoder.encode_item_name(principal.def_id),
+                        arguments,
+                        lifetimes,
+                    )
+                }
+                // Only auto traits such as `dyn Send`.
+                None => vir::Type::unsupported("dynamic".to_string()),
+            },
 
-            ty::TyKind::FnPtr(..) => vir::Type::unsupported("fnptr".to_string()),
+            ty::TyKind::FnPtr(..) => vir::Type::FnPointer,
 
             ty::TyKind::Foreign(..) => vir::Type::unsupported("foreign".to_string()),
 
@@ -362,7 +384,8 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
             ty::TyKind::Float(_) => {
                 let mut lower_bound = None;
                 let mut upper_bound = None;
-                if config::check_overflows() {
+                // With IEEE semantics, floats might also be infinite or NaN.
+                if config::check_overflows() && !config::encode_ieee_floats() {
                     if let Some((lower, upper)) = self.get_float_bounds() {
                         lower_bound = Some(Box::new(lower));
                         upper_bound = Some(Box::new(upper));
@@ -467,6 +490,18 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
             ty::TyKind::Param(param_ty) => {
                 vir::TypeDecl::type_var(param_ty.name.as_str().to_string())
             }
+            ty::TyKind::Dynamic(predicates, ..) if predicates.principal_def_id().is_some() => {
+                // The concrete type behind a trait object is unknown, so it
+                // is encoded in the same way as a type parameter.
+                let ty = self.encoder.encode_type_high(self.ty)?;
+                vir::TypeDecl::type_var(ty.get_identifier())
+            }
+            ty::TyKind::FnPtr(_) => {
+                // The function behind a pointer is unknown; its contract is
+                // modelled by abstract functions over the pointer snapshot.
+                let ty = self.encoder.encode_type_high(self.ty)?;
+                vir::TypeDecl::type_var(ty.get_identifier())
+            }
             ty::TyKind::Closure(def_id, internal_substs) => {
                 let cl_substs = internal_substs.as_closure();
                 let arguments = cl_substs
@@ -35,6 +35,9 @@ pub use place_encoding::{PlaceEncoding, ExprOrArrayBase};
 
 pub static PRECONDITION_LABEL: &str = "pre";
 pub static WAND_LHS_LABEL: &str = "lhs";
+/// Placeholder for `prusti_contracts::visited()` in encoded loop invariants,
+/// which is replaced by the procedure encoder once the loop is known.
+pub static FOR_LOOP_VISITED_VAR: &str = "_for_loop_visited";
 
 pub trait PlaceEncoder<'v, 'tcx: 'v> {
 
@@ -512,6 +515,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
             mir::BinOp::Le => vir::Expr::le_cmp(left, right),
             mir::BinOp::Add => vir::Expr::add(left, right),
             mir::BinOp::Sub => vir::Expr::sub(left, right),
+            mir::BinOp::Rem if matches!(ty.kind(), ty::TyKind::Float(_)) => {
+                return Err(EncodingError::unsupported(
+                    "the remainder of floating-point numbers is not supported"
+                ))
+            }
             mir::BinOp::Rem => vir::Expr::rem(left, right),
             mir::BinOp::Div => vir::Expr::div(left, right),
             mir::BinOp::Mul => vir::Expr::mul(left, right),
@@ -621,6 +629,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
                         vir::Expr::gt_cmp(result, std::isize::MAX.into()),
                     ),
                     //Floats
+                    // With IEEE semantics, results that are too large round to infinity.
+                    ty::TyKind::Float(_) if config::encode_ieee_floats() => false.into(),
                     ty::TyKind::Float(ty::FloatTy::F32) => vir::Expr::or(
                         vir::Expr::lt_cmp(result.clone(), std::f32::MIN.into()),
                         vir::Expr::gt_cmp(result, std::f32::MAX.into()),
@@ -765,6 +775,25 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
                 }
             }
 
+            // Numeric casts from and to floats, which round or saturate
+            (ty::TyKind::Float(_), ty::TyKind::Float(_))
+            | (ty::TyKind::Int(_), ty::TyKind::Float(_))
+            | (ty::TyKind::Uint(_), ty::TyKind::Float(_))
+            | (ty::TyKind::Float(_), ty::TyKind::Int(_))
+            | (ty::TyKind::Float(_), ty::TyKind::Uint(_))
+                if config::encode_ieee_floats() =>
+            {
+                let encoded_operand = self.encode_operand_expr(operand).with_span(span)?;
+                self.encode_float_cast(encoded_operand, src_ty, dst_ty)
+            }
+
+            (ty::TyKind::Float(_), _) | (_, ty::TyKind::Float(_)) => {
+                return Err(SpannedEncodingError::unsupported(
+                    "casts from and to floats are disabled by default; use `encode_ieee_floats` to enable",
+                    span,
+                ));
+            }
+
             _ => {
                 return Err(SpannedEncodingError::unsupported(
                     format!(
@@ -780,6 +809,156 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         Ok(encoded_val)
     }
 
+    /// Encodes an `as` cast from or to a float. Like in Rust, casts into a
+    /// float round to the nearest representable number, while casts into an
+    /// integer round towards zero, saturate at the bounds of the integer type
+    /// and map NaN to zero.
+    fn encode_float_cast(
+        &self,
+        operand: vir::Expr,
+        src_ty: ty::Ty<'tcx>,
+        dst_ty: ty::Ty<'tcx>,
+    ) -> vir::Expr {
+        let cast = |kind| vir::Expr::Cast(vir::Cast {
+            kind,
+            base: box operand.clone(),
+            position: vir::Position::default(),
+        });
+        match (src_ty.kind(), dst_ty.kind()) {
+            (ty::TyKind::Float(src), ty::TyKind::Float(dst)) if src == dst => operand,
+            (ty::TyKind::Float(src), ty::TyKind::Float(dst)) => {
+                cast(vir::CastKind::FloatIntoFloat(encode_float(*src), encode_float(*dst)))
+            }
+            (_, ty::TyKind::Float(dst)) => {
+                let bitvector = self.encode_bitvector_type(src_ty);
+                cast(vir::CastKind::IntIntoFloat(bitvector, encode_float(*dst)))
+            }
+            (ty::TyKind::Float(src), _) => {
+                let bitvector = self.encode_bitvector_type(dst_ty);
+                let (signed, bits) = match bitvector {
+                    vir::BitVector::Signed(size) => (true, bitvector_bits(size)),
+                    vir::BitVector::Unsigned(size) => (false, bitvector_bits(size)),
+                };
+                let (min, max): (vir::Expr, vir::Expr) = if signed {
+                    ((i128::MIN >> (128 - bits)).into(), (i128::MAX >> (128 - bits)).into())
+                } else {
+                    (0.into(), (u128::MAX >> (128 - bits)).into())
+                };
+                // The powers of two just outside of the bounds, which are
+                // exactly representable as floats (or infinity for `u128` as
+                // `f32`).
+                let exponent = if signed { bits - 1 } else { bits };
+                let (min_float, max_float): (vir::Expr, vir::Expr) = match src {
+                    ty::FloatTy::F32 => (
+                        if signed { (-2f32.powi(exponent)).into() } else { 0f32.into() },
+                        2f32.powi(exponent).into(),
+                    ),
+                    ty::FloatTy::F64 => (
+                        if signed { (-2f64.powi(exponent)).into() } else { 0f64.into() },
+                        2f64.powi(exponent).into(),
+                    ),
+                };
+                // Values between the bounds and the powers of two round
+                // towards the bounds, so it does not matter which branch
+                // handles them.
+                vir::Expr::ite(
+                    vir::Expr::is_nan(operand.clone()),
+                    0.into(),
+                    vir::Expr::ite(
+                        vir::Expr::ge_cmp(operand.clone(), max_float),
+                        max,
+                        vir::Expr::ite(
+                            vir::Expr::le_cmp(operand.clone(), min_float),
+                            min,
+                            cast(vir::CastKind::FloatIntoInt(encode_float(*src), bitvector)),
+                        ),
+                    ),
+                )
+            }
+            _ => unreachable!("not a float cast: {:?} as {:?}", src_ty, dst_ty),
+        }
+    }
+
+    /// Returns the bitvector with the size and signedness of an integer type.
+    fn encode_bitvector_type(&self, ty: ty::Ty<'tcx>) -> vir::BitVector {
+        let pointer_size = match self.encoder.env().tcx().data_layout.pointer_size.bits() {
+            16 => vir::BitVectorSize::BV16,
+            32 => vir::BitVectorSize::BV32,
+            _ => vir::BitVectorSize::BV64,
+        };
+        match ty.kind() {
+            ty::TyKind::Int(ty::IntTy::I8) => vir::BitVector::Signed(vir::BitVectorSize::BV8),
+            ty::TyKind::Int(ty::IntTy::I16) => vir::BitVector::Signed(vir::BitVectorSize::BV16),
+            ty::TyKind::Int(ty::IntTy::I32) => vir::BitVector::Signed(vir::BitVectorSize::BV32),
+            ty::TyKind::Int(ty::IntTy::I64) => vir::BitVector::Signed(vir::BitVectorSize::BV64),
+            ty::TyKind::Int(ty::IntTy::I128) => vir::BitVector::Signed(vir::BitVectorSize::BV128),
+            ty::TyKind::Int(ty::IntTy::Isize) => vir::BitVector::Signed(pointer_size),
+            ty::TyKind::Uint(ty::UintTy::U8) => vir::BitVector::Unsigned(vir::BitVectorSize::BV8),
+            ty::TyKind::Uint(ty::UintTy::U16) => vir::BitVector::Unsigned(vir::BitVectorSize::BV16),
+            ty::TyKind::Uint(ty::UintTy::U32) => vir::BitVector::Unsigned(vir::BitVectorSize::BV32),
+            ty::TyKind::Uint(ty::UintTy::U64) => vir::BitVector::Unsigned(vir::BitVectorSize::BV64),
+            ty::TyKind::Uint(ty::UintTy::U128) => vir::BitVector::Unsigned(vir::BitVectorSize::BV128),
+            ty::TyKind::Uint(ty::UintTy::Usize) => vir::BitVector::Unsigned(pointer_size),
+            _ => unreachable!("not an integer type: {:?}", ty),
+        }
+    }
+
+    /// Encodes a call of a method of `f32` or `f64` that checks for NaN or
+    /// infinity, or that computes the absolute value, minimum or maximum.
+    /// Returns `None` if the called function is not one of these methods.
+    pub fn encode_float_method_call(
+        &self,
+        full_func_proc_name: &str,
+        args: &[mir::Operand<'tcx>],
+    ) -> EncodingResult<Option<vir::Expr>> {
+        if !config::encode_ieee_floats() {
+            return Ok(None);
+        }
+        let method = [
+            "core::f32::<impl f32>::",
+            "std::f32::<impl f32>::",
+            "core::f64::<impl f64>::",
+            "std::f64::<impl f64>::",
+        ]
+        .iter()
+        .find_map(|prefix| full_func_proc_name.strip_prefix(prefix));
+        let method = match method {
+            Some(method @ ("is_nan" | "is_infinite" | "is_finite" | "abs" | "min" | "max")) => method,
+            _ => return Ok(None),
+        };
+        let args = args
+            .iter()
+            .map(|arg| Ok(vir::Expr::snap_app(self.encode_operand_expr(arg)?)))
+            .collect::<EncodingResult<Vec<_>>>()?;
+        Ok(Some(match method {
+            "is_nan" => vir::Expr::is_nan(args[0].clone()),
+            "is_infinite" => vir::Expr::is_infinite(args[0].clone()),
+            "is_finite" => vir::Expr::not(vir::Expr::or(
+                vir::Expr::is_nan(args[0].clone()),
+                vir::Expr::is_infinite(args[0].clone()),
+            )),
+            "abs" => vir::Expr::abs(args[0].clone()),
+            // If one of the arguments is NaN, the other one is returned.
+            _ => {
+                let (first, second) = (args[0].clone(), args[1].clone());
+                let first_is_chosen = if method == "min" {
+                    vir::Expr::le_cmp(first.clone(), second.clone())
+                } else {
+                    vir::Expr::ge_cmp(first.clone(), second.clone())
+                };
+                vir::Expr::ite(
+                    vir::Expr::is_nan(first.clone()),
+                    second.clone(),
+                    vir::Expr::ite(
+                        vir::Expr::is_nan(second),
+                        first.clone(),
+                        vir::Expr::ite(first_is_chosen, first, second.clone()),
+                    ),
+                )
+            }
+        }))
+    }
+
     pub fn encode_operand_place(
         &self,
         operand: &mir::Operand<'tcx>,
@@ -863,3 +1042,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         }
     }
 }
+
+fn encode_float(float_ty: ty::FloatTy) -> vir::Float {
+    match float_ty {
+        ty::FloatTy::F32 => vir::Float::F32,
+        ty::FloatTy::F64 => vir::Float::F64,
+    }
+}
+
+fn bitvector_bits(size: vir::BitVectorSize) -> i32 {
+    match size {
+        vir::BitVectorSize::BV8 => 8,
+        vir::BitVectorSize::BV16 => 16,
+        vir::BitVectorSize::BV32 => 32,
+        vir::BitVectorSize::BV64 => 64,
+        vir::BitVectorSize::BV128 => 128,
+    }
+}
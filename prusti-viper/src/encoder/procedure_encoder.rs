@@ -16,7 +16,7 @@ use crate::encoder::high::types::HighTypeEncoderInterface;
 use crate::encoder::initialisation::InitInfo;
 use crate::encoder::loop_encoder::{LoopEncoder, LoopEncoderError};
 use crate::encoder::mir_encoder::{MirEncoder, FakeMirEncoder, PlaceEncoder, PlaceEncoding, ExprOrArrayBase};
-use crate::encoder::mir_encoder::PRECONDITION_LABEL;
+use crate::encoder::mir_encoder::{FOR_LOOP_VISITED_VAR, PRECONDITION_LABEL};
 use crate::encoder::mir_successor::MirSuccessor;
 use crate::encoder::places::{Local, LocalVariableManager, Place};
 use crate::encoder::Encoder;
@@ -44,6 +44,7 @@ use prusti_interface::{
             LoanPlaces, PoloniusInfo, PoloniusInfoError, ReborrowingDAG, ReborrowingDAGNode,
             ReborrowingKind, ReborrowingZombity,
         },
+        mir_analyses::loop_invariants::{infer_loop_invariants, InferredBound},
         BasicBlockIndex, LoopAnalysisError, PermissionKind, Procedure,
     },
     PrustiError,
@@ -57,7 +58,7 @@ use prusti_rustc_interface::middle::ty::{self, layout::IntegerExt, ParamEnv, sub
 use prusti_rustc_interface::target::abi::Integer;
 use rustc_hash::{FxHashMap, FxHashSet};
 use prusti_rustc_interface::attr::IntType::SignedInt;
-use prusti_rustc_interface::span::Span;
+use prusti_rustc_interface::span::{DesugaringKind, Span};
 use prusti_rustc_interface::errors::MultiSpan;
 use prusti_interface::specs::typed;
 use ::log::{trace, debug};
@@ -81,7 +82,7 @@ use crate::encoder::mir::{
 };
 use super::high::generics::HighGenericsEncoderInterface;
 use super::counterexamples::DiscriminantsStateInterface;
-use prusti_interface::environment::mir_utils::SliceOrArrayRef;
+use prusti_interface::environment::mir_utils::{SliceOrArrayRef, TraitObjectRef};
 
 pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
@@ -121,6 +122,9 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     /// A map that stores local variables used to preserve the value of a place accross the loop
     /// when we cannot do that by using permissions.
     pure_var_for_preserving_value_map: FxHashMap<BasicBlockIndex, FxHashMap<vir::Expr, vir::LocalVar>>,
+    /// For each `for` loop over a range, the ghost variables that store the bounds of the range
+    /// when the loop is entered, together with the places that define them.
+    for_loop_entry_values: FxHashMap<BasicBlockIndex, Vec<(vir::LocalVar, vir::Expr)>>,
     /// Information about which places are definitely initialised.
     init_info: InitInfo,
     /// Mapping from old expressions to ghost variables with which they were replaced.
@@ -129,6 +133,8 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     old_ghost_vars: FxHashMap<String, vir::Type>,
     /// For each loop head, the block at whose end the loop invariant holds
     cached_loop_invariant_block: FxHashMap<BasicBlockIndex, BasicBlockIndex>,
+    /// For each loop head, the bounds inferred by `infer_loop_invariants`
+    inferred_loop_invariants: FxHashMap<BasicBlockIndex, Vec<InferredBound>>,
     /// Type substitutions inside this procedure. Most likely identity for the
     /// given proc_def_id.
     substs: SubstsRef<'tcx>,
@@ -187,10 +193,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             slice_created_at: FxHashMap::default(),
             procedure_contracts: FxHashMap::default(),
             pure_var_for_preserving_value_map: FxHashMap::default(),
+            for_loop_entry_values: FxHashMap::default(),
             init_info,
             old_to_ghost_var: FxHashMap::default(),
             old_ghost_vars: FxHashMap::default(),
             cached_loop_invariant_block: FxHashMap::default(),
+            inferred_loop_invariants: FxHashMap::default(),
             substs,
         })
     }
@@ -396,6 +404,36 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             }
         }
 
+        // Infer bounds of integer variables as additional loop invariants
+        if config::infer_loop_invariants() {
+            let inferred_loop_invariants = infer_loop_invariants(
+                self.encoder.env().tcx(),
+                self.proc_def_id,
+                self.mir,
+                self.loop_encoder.loops(),
+                &self.cached_loop_invariant_block,
+            );
+            let mut loop_heads: Vec<_> = inferred_loop_invariants.keys().copied().collect();
+            loop_heads.sort();
+            for loop_head in loop_heads {
+                let inferred = inferred_loop_invariants[&loop_head]
+                    .iter()
+                    .map(|bound| format!("body_invariant!({});", bound.to_rust_expr()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                PrustiError::warning(
+                    format!("inferred loop invariant: `{}`", inferred),
+                    self.get_loop_span(loop_head).into(),
+                ).emit(&self.encoder.env().diagnostic);
+            }
+            // The analysis relies on the ranges of the integer types, which the
+            // verifier only knows with overflow checks. Otherwise, the bounds
+            // are only suggested.
+            if config::check_overflows() {
+                self.inferred_loop_invariants = inferred_loop_invariants;
+            }
+        }
+
         // Load Polonius info
         self.polonius_info = Some(
             PoloniusInfo::new(self.encoder.env(), self.procedure, &self.cached_loop_invariant_block)
@@ -1551,7 +1589,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             }
             mir::Rvalue::Cast(mir::CastKind::PointerExposeAddress, ref operand, dst_ty) |
             mir::Rvalue::Cast(mir::CastKind::PointerFromExposedAddress, ref operand, dst_ty) |
-            mir::Rvalue::Cast(mir::CastKind::IntToInt, ref operand, dst_ty) => {
+            mir::Rvalue::Cast(mir::CastKind::IntToInt, ref operand, dst_ty) |
+            mir::Rvalue::Cast(mir::CastKind::FloatToInt, ref operand, dst_ty) |
+            mir::Rvalue::Cast(mir::CastKind::FloatToFloat, ref operand, dst_ty) |
+            mir::Rvalue::Cast(mir::CastKind::IntToFloat, ref operand, dst_ty) => {
                 self.encode_cast(
                     operand,
                     dst_ty,
@@ -1587,6 +1628,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         cast_ty,
                         location,
                     )?
+                } else if cast_ty.is_trait_object_ref() || cast_ty.is_trait_object_box() {
+                    trace!("trait object: operand={:?}, ty={:?}", operand, cast_ty);
+                    self.encode_assign_trait_object(
+                        encoded_lhs,
+                        operand,
+                        cast_ty,
+                        location,
+                    )?
                 } else {
                     return Err(SpannedEncodingError::unsupported(
                         format!("unsizing a {} into a {} is not supported", rhs_ty, cast_ty),
@@ -1594,6 +1643,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     ));
                 }
             }
+            mir::Rvalue::Cast(mir::CastKind::Pointer(ty::adjustment::PointerCast::ReifyFnPointer), ref operand, cast_ty) => {
+                self.encode_assign_fn_ptr(
+                    encoded_lhs,
+                    operand,
+                    cast_ty,
+                    location,
+                )?
+            }
             mir::Rvalue::Cast(mir::CastKind::Pointer(_), _, _) |
             mir::Rvalue::Cast(mir::CastKind::DynStar, _, _) => {
                 return Err(EncodingError::unsupported(
@@ -2465,6 +2522,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
                     let full_func_proc_name: &str =
                         &self.encoder.env().name.get_absolute_item_name(called_def_id);
+                    let float_method_call = self.mir_encoder
+                        .encode_float_method_call(full_func_proc_name, args)
+                        .with_span(span)?;
+
+                    // The items of a slice iterator that is consumed by a `for` loop are
+                    // stored before the iterator borrows the slice. The call itself is
+                    // encoded as usual.
+                    if full_func_proc_name == "core::slice::<impl [T]>::iter" {
+                        if let Some(loop_head) = self.loop_encoder.get_for_loop_of_into_iter_arg(destination) {
+                            stmts.extend(
+                                self.encode_for_loop_items_assign(loop_head, &args[0], span)?
+                            );
+                        }
+                    }
 
                     match full_func_proc_name {
                         "std::rt::begin_panic"
@@ -2598,6 +2669,18 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                     )?);
                                 }
 
+                                ty::TyKind::Param(_) => {
+                                    stmts.extend(self.encode_closure_param_call(
+                                        location,
+                                        term.source_info.span,
+                                        args,
+                                        destination,
+                                        target,
+                                        cl_type,
+                                        ty::ClosureKind::Fn,
+                                    )?);
+                                }
+
                                 _ => {
                                     return Err(SpannedEncodingError::unsupported(
                                         format!("only calls to closures are supported. The term is a {:?}, not a closure.", cl_type.kind()),
@@ -2607,6 +2690,28 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             }
                         }
 
+                        "std::ops::FnMut::call_mut"
+                        | "core::ops::FnMut::call_mut"
+                        | "std::ops::FnOnce::call_once"
+                        | "core::ops::FnOnce::call_once"
+                            if matches!(call_substs[0].expect_ty().kind(), ty::TyKind::Param(_))
+                        => {
+                            let call_kind = if full_func_proc_name.ends_with("call_mut") {
+                                ty::ClosureKind::FnMut
+                            } else {
+                                ty::ClosureKind::FnOnce
+                            };
+                            stmts.extend(self.encode_closure_param_call(
+                                location,
+                                term.source_info.span,
+                                args,
+                                destination,
+                                target,
+                                call_substs[0].expect_ty(),
+                                call_kind,
+                            )?);
+                        }
+
                         "core::slice::<impl [T]>::len" => {
                             debug!("Encoding call of slice::len");
                             stmts.extend(
@@ -2619,6 +2724,72 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             );
                         }
 
+                        "std::iter::IntoIterator::into_iter" |
+                        "core::iter::IntoIterator::into_iter"
+                            if self.is_integer_range(call_substs[0].expect_ty())
+                        => {
+                            // A range is its own iterator
+                            debug!("Encoding call of IntoIterator::into_iter on a range");
+                            let (dst, pre_stmts, _, _) = self.encode_place(destination, ArrayAccessKind::Shared, location)?;
+                            stmts.extend(pre_stmts);
+                            stmts.extend(self.encode_assign_operand(&dst, &args[0], location)?);
+                        }
+
+                        "std::iter::Iterator::next" |
+                        "core::iter::Iterator::next"
+                            if self.is_integer_range(call_substs[0].expect_ty())
+                        => {
+                            debug!("Encoding call of Iterator::next on a range");
+                            stmts.extend(
+                                self.encode_range_next_call(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    target,
+                                    call_substs[0].expect_ty(),
+                                )?
+                            );
+                        }
+
+                        "std::iter::IntoIterator::into_iter" |
+                        "core::iter::IntoIterator::into_iter"
+                            if self.get_sequence_iterator_elem_ty(
+                                destination.ty(self.mir, self.encoder.env().tcx()).ty
+                            ).is_some() && self.loop_encoder.get_for_loop_of_into_iter(location).is_some()
+                        => {
+                            debug!("Encoding call of IntoIterator::into_iter on a sequence");
+                            stmts.extend(
+                                self.encode_sequence_into_iter_call(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    target,
+                                )?
+                            );
+                        }
+
+                        "std::iter::Iterator::next" |
+                        "core::iter::Iterator::next"
+                            if self.get_sequence_iterator_elem_ty(call_substs[0].expect_ty()).is_some()
+                                && term.source_info.span.is_desugaring(DesugaringKind::ForLoop)
+                        => {
+                            debug!("Encoding call of Iterator::next on a sequence iterator");
+                            let loop_head = self.loop_encoder.get_loop_head(location.block).unwrap();
+                            stmts.extend(
+                                self.encode_sequence_next_call(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    target,
+                                    loop_head,
+                                    call_substs[0].expect_ty(),
+                                )?
+                            );
+                        }
+
                         "std::iter::Iterator::next" |
                         "core::iter::Iterator::next" => {
                             return Err(SpannedEncodingError::unsupported(
@@ -2649,6 +2820,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             );
                         }
 
+                        // methods of `f32` and `f64` with IEEE semantics
+                        _ if float_method_call.is_some() => {
+                            debug!("Encoding call of float method {}", full_func_proc_name);
+                            stmts.extend(
+                                self.encode_float_method_call(
+                                    float_method_call.unwrap(),
+                                    location,
+                                    args,
+                                    destination,
+                                    target,
+                                )?
+                            );
+                        }
+
                         _ => {
                             // The called method might be a trait method.
                             // We try to resolve it to the concrete implementation
@@ -2716,6 +2901,30 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
             }
 
+            TerminatorKind::Call {
+                ref args,
+                destination,
+                target,
+                ref func,
+                ..
+            } if self.mir_encoder.get_operand_ty(func).is_fn_ptr() => {
+                stmts.extend(
+                    self.encode_fn_ptr_call(
+                        location,
+                        term.source_info.span,
+                        func,
+                        args,
+                        destination,
+                        target,
+                    )?
+                );
+                if let Some(target) = target {
+                    (stmts, MirSuccessor::Goto(target))
+                } else {
+                    (stmts, MirSuccessor::Kill)
+                }
+            }
+
             TerminatorKind::Call { .. } => {
                 // Other kind of calls?
                 unimplemented!();
@@ -3055,6 +3264,32 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(stmts)
     }
 
+    /// Assigns the encoding of a call of a method of `f32` or `f64` to the
+    /// destination, like a call of a pure function.
+    fn encode_float_method_call(
+        &mut self,
+        call_result: vir::Expr,
+        location: mir::Location,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let (target_value, mut stmts) = self.encode_pure_function_call_lhs_value(destination, target, location)?;
+        let inhaled_expr = vir::Expr::eq_cmp(target_value, call_result);
+
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            target,
+            inhaled_expr,
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
+
+        Ok(stmts)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn encode_cmp_function_call(
         &mut self,
@@ -3517,27 +3752,523 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 unchecked: true,
             }));
         }
-        stmts.push(vir::Stmt::Inhale( vir::Inhale {
-            expr: replace_fake_exprs(post_invs_spec),
-        }));
-        stmts.push(vir::Stmt::Inhale( vir::Inhale {
-            expr: replace_fake_exprs(post_func_spec),
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: replace_fake_exprs(post_invs_spec),
+        }));
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: replace_fake_exprs(post_func_spec),
+        }));
+
+        // Exhale the permissions that were moved into magic wands.
+        assert!(!pos.is_default());
+        stmts.push(vir::Stmt::Exhale( vir::Exhale {
+            expr: pre_mandatory_perm_spec,
+            position: pos,
+        }));
+
+        // Emit the label and magic wands
+        stmts.push(vir::Stmt::label(post_label.clone()));
+
+        stmts.extend(stmts_after);
+
+        self.procedure_contracts
+            .insert(location, (procedure_contract, fake_exprs));
+
+        Ok(stmts)
+    }
+
+    /// Encodes a call through a function pointer. The contract of the call
+    /// is given by the abstract functions `fn_ptr$pre` and `fn_ptr$post`,
+    /// which are constrained by specification entailments and by coercions
+    /// of functions into function pointers.
+    fn encode_fn_ptr_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        func: &mir::Operand<'tcx>,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let fn_ptr_ty = self.mir_encoder.get_operand_ty(func);
+        debug!("Encoding call through function pointer of type {:?}", fn_ptr_ty);
+        let tcx = self.encoder.env().tcx();
+        let sig = tcx.erase_late_bound_regions(fn_ptr_ty.fn_sig(tcx));
+        if target.is_none() {
+            return Err(SpannedEncodingError::unsupported(
+                "calls through diverging function pointers are not supported",
+                call_site_span,
+            ));
+        }
+        let mut arg_exprs = vec![];
+        for operand in args.iter() {
+            let arg_ty = self.mir_encoder.get_operand_ty(operand);
+            if arg_ty.is_mutable_ptr() {
+                return Err(SpannedEncodingError::unsupported(
+                    format!("calls through function pointers with an argument of type {} are not supported", arg_ty),
+                    call_site_span,
+                ));
+            }
+            let arg_expr = self.mir_encoder.encode_operand_expr(operand)
+                .with_span(call_site_span)?;
+            arg_exprs.push(vir::Expr::snap_app(arg_expr));
+        }
+        let fn_ptr = vir::Expr::snap_app(
+            self.mir_encoder.encode_operand_expr(func).with_span(call_site_span)?
+        );
+
+        // Check the precondition of the pointer
+        let fn_ptr_pre = self.encoder
+            .encode_callable_spec_func_app(fn_ptr_ty, sig, fn_ptr.clone(), arg_exprs.clone(), None)
+            .with_span(call_site_span)?;
+        let pos = self.register_error(call_site_span, ErrorCtxt::ExhaleMethodPrecondition);
+        let mut stmts = vec![vir::Stmt::Assert( vir::Assert {
+            expr: fn_ptr_pre,
+            position: pos,
+        })];
+
+        // Assume the postcondition of the pointer for the new result
+        let (target_value, pre_stmts) = self.encode_pure_function_call_lhs_value(destination, target, location)?;
+        stmts.extend(pre_stmts);
+        let fn_ptr_post = self.encoder
+            .encode_callable_spec_func_app(
+                fn_ptr_ty,
+                sig,
+                fn_ptr,
+                arg_exprs,
+                Some(vir::Expr::snap_app(target_value)),
+            )
+            .with_span(call_site_span)?;
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            target,
+            fn_ptr_post,
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
+
+        Ok(stmts)
+    }
+
+    /// Encodes a call `Fn::call`, `FnMut::call_mut` or `FnOnce::call_once`
+    /// on a callable whose type is a type parameter. The contract of the
+    /// call is given by the abstract functions `closure$pre` and
+    /// `closure$post`, which are constrained by specification entailments.
+    /// Calls through `FnMut` may change the state of the callable, which is
+    /// havocked; the postcondition refers to the state before the call.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_closure_param_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+        cl_ty: ty::Ty<'tcx>,
+        call_kind: ty::ClosureKind,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        debug!("Encoding {:?} call of type parameter {:?}", call_kind, cl_ty);
+        if target.is_none() {
+            return Err(SpannedEncodingError::unsupported(
+                "calls to diverging closures are not supported",
+                call_site_span,
+            ));
+        }
+        let sig = match self.encoder.env().query.get_callable_sig(cl_ty, self.proc_def_id) {
+            Some((sig, _)) => sig,
+            None => {
+                return Err(SpannedEncodingError::unsupported(
+                    format!("the signature of calls to {} could not be determined", cl_ty),
+                    call_site_span,
+                ));
+            }
+        };
+        assert_eq!(args.len(), 2);
+
+        // The callable is passed by reference (`Fn`, `FnMut`) or by value
+        // (`FnOnce`), the arguments are passed as a tuple.
+        let callable_place = self.mir_encoder.encode_operand_place(&args[0])
+            .with_span(call_site_span)?
+            .ok_or_else(|| SpannedEncodingError::unsupported(
+                "calls to constant closures are not supported",
+                call_site_span,
+            ))?;
+        let callable_place = match call_kind {
+            ty::ClosureKind::Fn | ty::ClosureKind::FnMut => {
+                let deref_field = self.encoder.encode_dereference_field(cl_ty)
+                    .with_span(call_site_span)?;
+                callable_place.field(deref_field)
+            }
+            ty::ClosureKind::FnOnce => callable_place,
+        };
+        let args_tuple = self.mir_encoder.encode_operand_place(&args[1])
+            .with_span(call_site_span)?
+            .ok_or_else(|| SpannedEncodingError::unsupported(
+                "calls to closures with constant arguments are not supported",
+                call_site_span,
+            ))?;
+        let mut arg_exprs = vec![];
+        for (field_num, arg_ty) in sig.inputs().iter().enumerate() {
+            if arg_ty.is_mutable_ptr() {
+                return Err(SpannedEncodingError::unsupported(
+                    format!("calls to closures with an argument of type {} are not supported", arg_ty),
+                    call_site_span,
+                ));
+            }
+            let arg_field = self.encoder
+                .encode_raw_ref_field(format!("tuple_{}", field_num), *arg_ty)
+                .with_span(call_site_span)?;
+            arg_exprs.push(vir::Expr::snap_app(args_tuple.clone().field(arg_field)));
+        }
+
+        let pre_label = self.cfg_method.get_fresh_label_name();
+        let mut stmts = vec![vir::Stmt::label(pre_label.clone())];
+
+        // Check the precondition of the callable
+        let callable = vir::Expr::snap_app(callable_place.clone());
+        let closure_pre = self.encoder
+            .encode_callable_spec_func_app(cl_ty, sig, callable.clone(), arg_exprs.clone(), None)
+            .with_span(call_site_span)?;
+        let pos = self.register_error(call_site_span, ErrorCtxt::ExhaleMethodPrecondition);
+        stmts.push(vir::Stmt::Assert( vir::Assert {
+            expr: closure_pre,
+            position: pos,
+        }));
+
+        // Havoc the state of a `FnMut` callable
+        if call_kind == ty::ClosureKind::FnMut {
+            let callable_perm = self.mir_encoder
+                .encode_place_predicate_permission(callable_place, vir::PermAmount::Write)
+                .unwrap();
+            stmts.push(vir::Stmt::Exhale( vir::Exhale {
+                expr: callable_perm.clone(),
+                position: pos,
+            }));
+            stmts.push(vir::Stmt::Inhale( vir::Inhale {
+                expr: callable_perm,
+            }));
+        }
+
+        // Assume the postcondition of the callable for the new result
+        let (target_value, pre_stmts) = self.encode_pure_function_call_lhs_value(destination, target, location)?;
+        stmts.extend(pre_stmts);
+        let closure_post = self.encoder
+            .encode_callable_spec_func_app(
+                cl_ty,
+                sig,
+                callable.old(&pre_label),
+                arg_exprs,
+                Some(vir::Expr::snap_app(target_value)),
+            )
+            .with_span(call_site_span)?;
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            target,
+            closure_post,
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
+
+        Ok(stmts)
+    }
+
+    /// Encodes a call of `Iterator::next` on a range of integers. If the
+    /// range is not empty, the call returns its start and increments it;
+    /// otherwise, it returns `None` and leaves the range unchanged.
+    fn encode_range_next_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+        range_ty: ty::Ty<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        assert_eq!(args.len(), 1);
+        let tcx = self.encoder.env().tcx();
+        let elem_ty = match range_ty.kind() {
+            ty::TyKind::Adt(_, substs) => substs.type_at(0),
+            _ => unreachable!(),
+        };
+        let option_ty = destination.ty(self.mir, tcx).ty;
+        let (some_variant, none_variant) = self.get_option_variants(option_ty);
+
+        // The range is passed by mutable reference
+        let range_place = self.mir_encoder.encode_operand_place(&args[0])
+            .with_span(call_site_span)?
+            .unwrap();
+        let deref_field = self.encoder.encode_dereference_field(range_ty)
+            .with_span(call_site_span)?;
+        let range_place = range_place.field(deref_field);
+        let start = self.encoder.encode_struct_field_value(range_place.clone(), "start", elem_ty)
+            .with_span(call_site_span)?;
+        let end = self.encoder.encode_struct_field_value(range_place.clone(), "end", elem_ty)
+            .with_span(call_site_span)?;
+
+        let pre_label = self.cfg_method.get_fresh_label_name();
+        let mut stmts = vec![vir::Stmt::label(pre_label.clone())];
+
+        // Havoc the range
+        let range_perm = self.mir_encoder
+            .encode_place_predicate_permission(range_place, vir::PermAmount::Write)
+            .unwrap();
+        let pos = self.register_error(call_site_span, ErrorCtxt::Unexpected);
+        stmts.push(vir::Stmt::Exhale( vir::Exhale {
+            expr: range_perm.clone(),
+            position: pos,
+        }));
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: range_perm,
+        }));
+
+        // Relate the result and the new range to the old range
+        let old_start = start.clone().old(&pre_label);
+        let old_end = end.clone().old(&pre_label);
+        let some = self.encoder
+            .encode_snapshot(option_ty, Some(some_variant), vec![old_start.clone()])
+            .with_span(call_site_span)?;
+        let none = self.encoder
+            .encode_snapshot(option_ty, Some(none_variant), vec![])
+            .with_span(call_site_span)?;
+        let (target_value, pre_stmts) = self.encode_pure_function_call_lhs_value(destination, target, location)?;
+        stmts.extend(pre_stmts);
+        let result = vir::Expr::snap_app(target_value);
+        let one = vir::Expr::from(1);
+        let next_result = vir::Expr::ite(
+            vir_expr!{ [old_start] < [old_end] },
+            vir_expr!{ ([result] == [some]) && ([start] == ([old_start] + [one])) },
+            vir_expr!{ ([result] == [none]) && ([start] == [old_start]) },
+        );
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            target,
+            vir_expr!{ [next_result] && ([end] == [old_end]) },
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
+
+        Ok(stmts)
+    }
+
+    /// Is `ty` a `Range` of integers?
+    fn is_integer_range(&self, ty: ty::Ty<'tcx>) -> bool {
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) => {
+                Some(adt_def.did()) == self.encoder.env().tcx().lang_items().range_struct()
+                    && substs.type_at(0).is_integral()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the indices of the `Some` and `None` variants of `option_ty`.
+    fn get_option_variants(&self, option_ty: ty::Ty<'tcx>) -> (usize, usize) {
+        match option_ty.kind() {
+            ty::TyKind::Adt(adt_def, _) => {
+                let lang_items = self.encoder.env().tcx().lang_items();
+                (
+                    adt_def.variant_index_with_id(lang_items.option_some_variant().unwrap()).as_usize(),
+                    adt_def.variant_index_with_id(lang_items.option_none_variant().unwrap()).as_usize(),
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// If `ty` is the iterator of a slice (`slice::Iter`) or of a vector
+    /// (`vec::IntoIter`), returns the type of the elements of the sequence.
+    fn get_sequence_iterator_elem_ty(&self, ty: ty::Ty<'tcx>) -> Option<ty::Ty<'tcx>> {
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) => {
+                match self.encoder.env().name.get_absolute_item_name(adt_def.did()).as_str() {
+                    "core::slice::Iter" |
+                    "std::slice::Iter" => Some(substs.type_at(1)),
+                    "alloc::vec::IntoIter" |
+                    "std::vec::IntoIter" => Some(substs.type_at(0)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the ghost variables that model the iterator of a `for` loop
+    /// over a sequence: the snapshot of the items of the sequence and the
+    /// number of items consumed so far.
+    fn get_for_loop_sequence_vars(
+        &mut self,
+        loop_head: BasicBlockIndex,
+        elem_ty: ty::Ty<'tcx>,
+    ) -> EncodingResult<(vir::LocalVar, vir::LocalVar)> {
+        let slice_ty = self.encoder.env().tcx().mk_slice(elem_ty);
+        let items_type = self.encoder.encode_snapshot_type(slice_ty)?;
+        let items = self.get_auxiliary_local_var(&format!("for_items_{:?}", loop_head), items_type);
+        let consumed = self.get_auxiliary_local_var(
+            &format!("for_consumed_{:?}", loop_head),
+            vir::Type::Int,
+        );
+        Ok((items, consumed))
+    }
+
+    /// Stores the items of the slice referenced by `operand` as the items
+    /// over which the `for` loop at `loop_head` iterates.
+    fn encode_for_loop_items_assign(
+        &mut self,
+        loop_head: BasicBlockIndex,
+        operand: &mir::Operand<'tcx>,
+        span: Span,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let slice_ty = match self.mir_encoder.get_operand_ty(operand).kind() {
+            ty::TyKind::Ref(_, slice_ty, _) if slice_ty.is_slice() => *slice_ty,
+            _ => return Ok(vec![]),
+        };
+        let elem_ty = slice_ty.sequence_element_type(self.encoder.env().tcx());
+        let (items, _) = self.get_for_loop_sequence_vars(loop_head, elem_ty)
+            .with_span(span)?;
+        let slice_place = self.mir_encoder.encode_operand_place(operand)
+            .with_span(span)?
+            .unwrap();
+        let deref_field = self.encoder.encode_dereference_field(slice_ty)
+            .with_span(span)?;
+        Ok(vec![vir::Stmt::Assign( vir::Assign {
+            target: items.into(),
+            source: vir::Expr::snap_app(slice_place.field(deref_field)),
+            kind: vir::AssignKind::Ghost,
+        })])
+    }
+
+    /// Encodes the call of `IntoIterator::into_iter` that creates the
+    /// iterator of a `for` loop over a slice or a vector. The iterator itself
+    /// is opaque; the items that it produces are stored in ghost variables.
+    fn encode_sequence_into_iter_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        assert_eq!(args.len(), 1);
+        let loop_head = self.loop_encoder.get_for_loop_of_into_iter(location).unwrap();
+        let iter_ty = destination.ty(self.mir, self.encoder.env().tcx()).ty;
+        let elem_ty = self.get_sequence_iterator_elem_ty(iter_ty).unwrap();
+        let (_, consumed) = self.get_for_loop_sequence_vars(loop_head, elem_ty)
+            .with_span(call_site_span)?;
+        let mut stmts = vec![];
+        if self.mir_encoder.get_operand_ty(&args[0]) == iter_ty {
+            // An iterator is its own iterator, whose items were stored when
+            // it was created
+            let (dst, pre_stmts, _, _) = self.encode_place(destination, ArrayAccessKind::Shared, location)?;
+            stmts.extend(pre_stmts);
+            stmts.extend(self.encode_assign_operand(&dst, &args[0], location)?);
+        } else {
+            // Only the items of a slice are known; those of a vector are
+            // left unconstrained
+            stmts.extend(self.encode_for_loop_items_assign(loop_head, &args[0], call_site_span)?);
+            let (call_stmts, label) = self.encode_pure_function_call_site(
+                location,
+                destination,
+                target,
+                true.into(),
+            )?;
+            stmts.extend(call_stmts);
+            self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
+        }
+        stmts.push(vir::Stmt::Assign( vir::Assign {
+            target: consumed.into(),
+            source: 0.into(),
+            kind: vir::AssignKind::Ghost,
         }));
+        Ok(stmts)
+    }
 
-        // Exhale the permissions that were moved into magic wands.
-        assert!(!pos.is_default());
+    /// Encodes the call of `Iterator::next` in a `for` loop over a slice or a
+    /// vector. If not all items have been consumed, the call returns the next
+    /// one; otherwise, it returns `None`.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_sequence_next_call(
+        &mut self,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: mir::Place<'tcx>,
+        target: Option<BasicBlockIndex>,
+        loop_head: BasicBlockIndex,
+        iter_ty: ty::Ty<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        assert_eq!(args.len(), 1);
+        let tcx = self.encoder.env().tcx();
+        let elem_ty = self.get_sequence_iterator_elem_ty(iter_ty).unwrap();
+        let slice_ty = tcx.mk_slice(elem_ty);
+        let option_ty = destination.ty(self.mir, tcx).ty;
+        let (some_variant, none_variant) = self.get_option_variants(option_ty);
+        let (items, consumed) = self.get_for_loop_sequence_vars(loop_head, elem_ty)
+            .with_span(call_site_span)?;
+        let items: vir::Expr = items.into();
+        let consumed: vir::Expr = consumed.into();
+
+        // The iterator is passed by mutable reference
+        let iter_place = self.mir_encoder.encode_operand_place(&args[0])
+            .with_span(call_site_span)?
+            .unwrap();
+        let deref_field = self.encoder.encode_dereference_field(iter_ty)
+            .with_span(call_site_span)?;
+        let iter_place = iter_place.field(deref_field);
+
+        // Havoc the iterator
+        let mut stmts = vec![];
+        let iter_perm = self.mir_encoder
+            .encode_place_predicate_permission(iter_place, vir::PermAmount::Write)
+            .unwrap();
+        let pos = self.register_error(call_site_span, ErrorCtxt::Unexpected);
         stmts.push(vir::Stmt::Exhale( vir::Exhale {
-            expr: pre_mandatory_perm_spec,
+            expr: iter_perm.clone(),
             position: pos,
         }));
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: iter_perm,
+        }));
 
-        // Emit the label and magic wands
-        stmts.push(vir::Stmt::label(post_label.clone()));
-
-        stmts.extend(stmts_after);
+        // Return the next item, if any
+        let len = self.encoder.encode_snapshot_slice_len(slice_ty, items.clone())
+            .with_span(call_site_span)?;
+        let item = self.encoder.encode_snapshot_slice_idx(slice_ty, items, consumed.clone())
+            .with_span(call_site_span)?;
+        let some = self.encoder
+            .encode_snapshot(option_ty, Some(some_variant), vec![item])
+            .with_span(call_site_span)?;
+        let none = self.encoder
+            .encode_snapshot(option_ty, Some(none_variant), vec![])
+            .with_span(call_site_span)?;
+        let (target_value, pre_stmts) = self.encode_pure_function_call_lhs_value(destination, target, location)?;
+        stmts.extend(pre_stmts);
+        let result = vir::Expr::snap_app(target_value);
+        let has_next = vir_expr!{ [consumed.clone()] < [len] };
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            target,
+            vir::Expr::ite(
+                has_next.clone(),
+                vir_expr!{ [result.clone()] == [some] },
+                vir_expr!{ [result] == [none] },
+            ),
+        )?;
+        stmts.extend(call_stmts);
+        let one = vir::Expr::from(1);
+        stmts.push(vir::Stmt::Assign( vir::Assign {
+            target: consumed.clone(),
+            source: vir::Expr::ite(has_next, vir_expr!{ [consumed.clone()] + [one] }, consumed),
+            kind: vir::AssignKind::Ghost,
+        }));
 
-        self.procedure_contracts
-            .insert(location, (procedure_contract, fake_exprs));
+        self.encode_transfer_args_permissions(location, args, &mut stmts, &label, false)?;
 
         Ok(stmts)
     }
@@ -5282,7 +6013,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
     /// Encode the functional specification of a loop
     fn encode_loop_invariant_specs(
-        &self,
+        &mut self,
         loop_head: BasicBlockIndex,
         _loop_inv_block: BasicBlockIndex,
     ) -> SpannedEncodingResult<(Vec<vir::Expr>, MultiSpan)> {
@@ -5293,10 +6024,27 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             spec_blocks
         );
 
-        // `body_invariant!(..)` is desugared to a closure with special attributes,
-        // which we can detect and use to retrieve the specification.
+        // `for` loops over ranges and sequences have a built-in invariant, which comes first
+        // because it is needed to encode the items visited by the loop.
         let mut encoded_specs = vec![];
+        let mut visited = None;
+        if let Some((invariant, visited_items)) = self.encode_for_loop_invariant(loop_head)? {
+            encoded_specs.push(invariant);
+            visited = Some(visited_items);
+        }
+
+        // The inferred bounds are checked like any other invariant, and their
+        // failures are reported at the loop
         let mut encoded_spec_spans = vec![];
+        let inferred_bounds = self.inferred_loop_invariants.get(&loop_head).cloned().unwrap_or_default();
+        for bound in inferred_bounds {
+            let span = self.get_loop_span(loop_head);
+            encoded_specs.extend(self.encode_inferred_bound(&bound, span)?);
+            encoded_spec_spans.push(span);
+        }
+
+        // `body_invariant!(..)` is desugared to a closure with special attributes,
+        // which we can detect and use to retrieve the specification.
         for bbi in spec_blocks {
             for stmt in &self.mir.basic_blocks[bbi].statements {
                 if let mir::StatementKind::Assign(box (
@@ -5304,14 +6052,18 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
                 )) = stmt.kind {
                     if let Some(spec) = self.encoder.get_loop_specs(cl_def_id.to_def_id()) {
-                        encoded_specs.push(self.encoder.encode_invariant(
+                        let encoded_spec = self.encoder.encode_invariant(
                             self.mir,
                             bbi,
                             self.proc_def_id,
                             cl_substs,
-                        )?);
+                        )?;
+                        let spec_span = self.encoder.env().tcx().def_span(cl_def_id.to_def_id());
+                        encoded_specs.push(
+                            replace_for_loop_visited(encoded_spec, visited.as_ref(), spec_span)?
+                        );
                         let invariant = match spec {
-                            prusti_interface::specs::typed::LoopSpecification::Invariant(inv) => inv,
+                            typed::LoopSpecification::Invariant(inv) => inv,
                             _ => continue,
                         };
                         encoded_spec_spans.push(self.encoder.env().tcx().def_span(invariant));
@@ -5324,6 +6076,114 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((encoded_specs, MultiSpan::from_spans(encoded_spec_spans)))
     }
 
+    /// Encodes the bounds on the value of a local inferred for a loop.
+    fn encode_inferred_bound(
+        &self,
+        bound: &InferredBound,
+        span: Span,
+    ) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        let local = self.mir_encoder.encode_local(bound.local)?;
+        let ty = self.mir_encoder.get_local_ty(bound.local);
+        let value = self.encoder
+            .encode_value_expr(vir::Expr::local(local), ty)
+            .with_span(span)?;
+        let mut encoded_bounds = vec![];
+        if let Some(lower) = bound.interval.lower {
+            let lower = vir::Expr::from(lower);
+            encoded_bounds.push(vir_expr!{ [value.clone()] >= [lower] });
+        }
+        if let Some(upper) = bound.interval.upper {
+            let upper = vir::Expr::from(upper);
+            encoded_bounds.push(vir_expr!{ [value] <= [upper] });
+        }
+        Ok(encoded_bounds)
+    }
+
+    /// For a `for` loop over a range of integers, encodes the invariant that
+    /// the range only advances from its state when the loop was entered,
+    /// together with the snapshot of the items visited by the loop, which
+    /// is valid wherever a `body_invariant!` can be placed. Loops over
+    /// slices and vectors are handled by `encode_for_loop_sequence_invariant`.
+    fn encode_for_loop_invariant(
+        &mut self,
+        loop_head: BasicBlockIndex,
+    ) -> SpannedEncodingResult<Option<(vir::Expr, vir::Expr)>> {
+        let iter_place = match self.loop_encoder.get_for_loop_iterator(loop_head) {
+            Some(place) => place,
+            None => return Ok(None),
+        };
+        let range_ty = iter_place.ty(self.mir, self.encoder.env().tcx()).ty;
+        if let Some(elem_ty) = self.get_sequence_iterator_elem_ty(range_ty) {
+            return self.encode_for_loop_sequence_invariant(loop_head, elem_ty).map(Some);
+        }
+        if !self.is_integer_range(range_ty) {
+            return Ok(None);
+        }
+        let elem_ty = match range_ty.kind() {
+            ty::TyKind::Adt(_, substs) => substs.type_at(0),
+            _ => unreachable!(),
+        };
+        let span = self.mir_encoder.get_span_of_basic_block(loop_head);
+        let (range_place, _, _) = self.mir_encoder.encode_place(iter_place).with_span(span)?;
+        let range_place = range_place.try_into_expr().with_span(span)?;
+        let start = self.encoder.encode_struct_field_value(range_place.clone(), "start", elem_ty)
+            .with_span(span)?;
+        let end = self.encoder.encode_struct_field_value(range_place, "end", elem_ty)
+            .with_span(span)?;
+
+        // The bounds of the range when the loop is entered are stored in ghost
+        // variables, which are set in encode_loop_invariant_exhale_stmts
+        if !self.for_loop_entry_values.contains_key(&loop_head) {
+            let entry_start = self.get_auxiliary_local_var(
+                &format!("for_start_{:?}", loop_head),
+                vir::Type::Int,
+            );
+            let entry_end = self.get_auxiliary_local_var(
+                &format!("for_end_{:?}", loop_head),
+                vir::Type::Int,
+            );
+            self.for_loop_entry_values.insert(
+                loop_head,
+                vec![(entry_start, start.clone()), (entry_end, end.clone())],
+            );
+        }
+        let entry_values = &self.for_loop_entry_values[&loop_head];
+        let entry_start: vir::Expr = entry_values[0].0.clone().into();
+        let entry_end: vir::Expr = entry_values[1].0.clone().into();
+
+        let invariant = vir_expr!{ ([entry_start] <= [start]) && ([end] == [entry_end]) };
+        // The first item is produced before the loop is entered
+        let one = vir::Expr::from(1);
+        let visited = self.encoder
+            .encode_range_visited_func_app(elem_ty, vir_expr!{ [entry_start] - [one] }, start)
+            .with_span(span)?;
+        Ok(Some((invariant, visited)))
+    }
+
+    /// For a `for` loop over a slice or a vector, encodes the invariant that
+    /// bounds the number of consumed items, together with the snapshot of
+    /// the consumed items, which are the items visited by the loop.
+    fn encode_for_loop_sequence_invariant(
+        &mut self,
+        loop_head: BasicBlockIndex,
+        elem_ty: ty::Ty<'tcx>,
+    ) -> SpannedEncodingResult<(vir::Expr, vir::Expr)> {
+        let span = self.mir_encoder.get_span_of_basic_block(loop_head);
+        let slice_ty = self.encoder.env().tcx().mk_slice(elem_ty);
+        let (items, consumed) = self.get_for_loop_sequence_vars(loop_head, elem_ty)
+            .with_span(span)?;
+        let items: vir::Expr = items.into();
+        let consumed: vir::Expr = consumed.into();
+        let len = self.encoder.encode_snapshot_slice_len(slice_ty, items.clone())
+            .with_span(span)?;
+        let zero = vir::Expr::from(0);
+        let invariant = vir_expr!{ ([zero.clone()] <= [consumed.clone()]) && ([consumed.clone()] <= [len]) };
+        let visited = self.encoder
+            .encode_snapshot_slicing(slice_ty, items, slice_ty, zero, consumed)
+            .with_span(span)?;
+        Ok((invariant, visited))
+    }
+
     fn encode_loop_invariant_exhale_stmts(
         &mut self,
         loop_head: BasicBlockIndex,
@@ -5384,6 +6244,15 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     kind: vir::AssignKind::Ghost,
                 }));
             }
+            if let Some(entry_values) = self.for_loop_entry_values.get(&loop_head) {
+                for (var, place) in entry_values {
+                    stmts.push(vir::Stmt::Assign( vir::Assign {
+                        target: var.clone().into(),
+                        source: place.clone(),
+                        kind: vir::AssignKind::Ghost,
+                    }));
+                }
+            }
         }
         assert!(!assert_pos.is_default());
         let obtain_predicates = permissions.iter().map(|p| {
@@ -6331,6 +7200,268 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(stmts)
     }
 
+    /// Encodes the creation of a reference or box to a trait object, e.g.
+    /// `&S as &dyn Trait`. The concrete type behind the trait object is
+    /// forgotten, so only the permission to the trait object is obtained.
+    ///
+    /// The writes through a `&mut dyn Trait` cannot be related to the
+    /// concrete object, so its value is havocked at the coercion. This is
+    /// equivalent to havocking it when the borrow expires, because the
+    /// concrete object cannot be accessed while the borrow is alive.
+    fn encode_assign_trait_object(
+        &mut self,
+        encoded_lhs: vir::Expr,
+        operand: &mir::Operand<'tcx>,
+        ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        trace!("encode_assign_trait_object(lhs={:?}, ty={:?})", encoded_lhs, ty);
+        let span = self.mir_encoder.get_span_of_location(location);
+        let mut stmts = Vec::new();
+
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::label(label.clone()));
+
+        let (object_ty, is_mut) = match ty.kind() {
+            ty::TyKind::Ref(_, object_ty, m) => (*object_ty, m == &mir::Mutability::Mut),
+            ty::TyKind::Adt(adt_def, _) if adt_def.is_box() => (ty.boxed_ty(), true),
+            _ => unreachable!("encode_assign_trait_object on a non-ref?!"),
+        };
+
+        stmts.extend(self.encode_havoc(&encoded_lhs));
+        let val_ref_field = self.encoder.encode_dereference_field(object_ty).with_span(span)?;
+        let object_expr = encoded_lhs.field(val_ref_field);
+        stmts.push(vir_stmt!{ inhale [vir::Expr::FieldAccessPredicate( vir::FieldAccessPredicate {
+            base: box object_expr.clone(),
+            permission: vir::PermAmount::Write,
+            position: vir::Position::default(),
+        })]});
+
+        let object_perm = vir::Expr::predicate_access_predicate(
+            self.encoder.encode_type(object_ty).with_span(span)?,
+            object_expr,
+            if is_mut { vir::PermAmount::Write } else { vir::PermAmount::Read },
+        );
+        stmts.push(vir_stmt!{ inhale [object_perm] });
+
+        if is_mut && ty.is_trait_object_ref() {
+            let rhs_place = match operand {
+                mir::Operand::Move(place) | mir::Operand::Copy(place) => *place,
+                mir::Operand::Constant(_) => unreachable!("mutable reference is a constant?!"),
+            };
+            let (rhs_place, rhs_ty, ..) = self.mir_encoder.encode_place(rhs_place).with_span(span)?;
+            let rhs_place = rhs_place.try_into_expr().with_span(span)?;
+            let val_ref_field = self.encoder.encode_value_field(rhs_ty).with_span(span)?;
+            let rhs_object_perm = self.mir_encoder
+                .encode_place_predicate_permission(rhs_place.field(val_ref_field), vir::PermAmount::Write)
+                .unwrap();
+            stmts.push(vir_stmt!{ exhale [rhs_object_perm.clone()] });
+            stmts.push(vir_stmt!{ inhale [rhs_object_perm] });
+        }
+
+        // Store a label for permissions got back from the call
+        self.label_after_location.insert(location, label);
+
+        Ok(stmts)
+    }
+
+    /// Encodes the coercion of a function item into a function pointer. The
+    /// abstract functions that model the contract of the pointer are defined
+    /// to be equivalent to the contract of the function.
+    fn encode_assign_fn_ptr(
+        &mut self,
+        encoded_lhs: vir::Expr,
+        operand: &mir::Operand<'tcx>,
+        fn_ptr_ty: ty::Ty<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        trace!("encode_assign_fn_ptr(lhs={:?}, operand={:?}, ty={:?})", encoded_lhs, operand, fn_ptr_ty);
+        let span = self.mir_encoder.get_span_of_location(location);
+        let (called_def_id, call_substs) = match self.mir_encoder.get_operand_ty(operand).kind() {
+            ty::TyKind::FnDef(def_id, substs) => (*def_id, *substs),
+            _ => {
+                return Err(SpannedEncodingError::unsupported(
+                    format!("coercing {:?} into a function pointer is not supported", operand),
+                    span,
+                ));
+            }
+        };
+        let (called_def_id, call_substs) = self.encoder.env().query
+            .resolve_method_call(self.proc_def_id, called_def_id, call_substs);
+        let sig = match fn_ptr_ty.kind() {
+            ty::TyKind::FnPtr(sig) => self.encoder.env().tcx().erase_late_bound_regions(*sig),
+            _ => unreachable!("encode_assign_fn_ptr on a non-fn-ptr?!"),
+        };
+        if let Some(arg_ty) = sig.inputs().iter().find(|arg_ty| arg_ty.is_mutable_ptr()) {
+            return Err(SpannedEncodingError::unsupported(
+                format!("function pointers with an argument of type {} are not supported", arg_ty),
+                span,
+            ));
+        }
+
+        let mut stmts = vec![];
+        let label = self.cfg_method.get_fresh_label_name();
+        stmts.push(vir::Stmt::label(label.clone()));
+        stmts.extend(self.encode_havoc_and_initialization(&encoded_lhs));
+        stmts.extend(self.encode_callable_contract_definition(
+            fn_ptr_ty,
+            sig,
+            vir::Expr::snap_app(encoded_lhs),
+            vec![],
+            called_def_id,
+            call_substs,
+            &label,
+            span,
+        )?);
+
+        self.label_after_location.insert(location, label);
+
+        Ok(stmts)
+    }
+
+    /// Encodes the contract of a newly created closure as the definition of
+    /// the abstract functions `closure$pre` and `closure$post`. The closure
+    /// contract is given in terms of the closure state, which is quantified
+    /// over since the state of `FnMut` closures changes between calls.
+    fn encode_closure_contract_definition(
+        &mut self,
+        cl_ty: ty::Ty<'tcx>,
+        cl_def_id: ProcedureDefId,
+        cl_substs: SubstsRef<'tcx>,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let span = self.mir_encoder.get_span_of_location(location);
+        let sig = match self.encoder.env().query.get_callable_sig(cl_ty, self.proc_def_id) {
+            Some((sig, _)) => sig,
+            None => return Ok(vec![]),
+        };
+        if sig.inputs().iter().any(|arg_ty| arg_ty.is_mutable_ptr()) {
+            // Calls with `&mut` arguments through closure specifications are
+            // not supported, so there is nothing to define.
+            return Ok(vec![]);
+        }
+        let state_var = vir::LocalVar::new(
+            "_closure_state",
+            self.encoder.encode_snapshot_type(cl_ty).with_span(span)?,
+        );
+        let label = self.cfg_method.get_fresh_label_name();
+        let mut stmts = vec![vir::Stmt::label(label.clone())];
+        stmts.extend(self.encode_callable_contract_definition(
+            cl_ty,
+            sig,
+            vir::Expr::local(state_var.clone()),
+            vec![state_var],
+            cl_def_id,
+            cl_substs,
+            &label,
+            span,
+        )?);
+        Ok(stmts)
+    }
+
+    /// Inhales the definition of the abstract functions that model the
+    /// contract of calls to `callable`: for all arguments and results (and
+    /// all states in `state_vars`), they are equal to the functional pre-
+    /// and postcondition of `called_def_id`.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_callable_contract_definition(
+        &mut self,
+        callable_ty: ty::Ty<'tcx>,
+        sig: ty::FnSig<'tcx>,
+        callable: vir::Expr,
+        state_vars: Vec<vir::LocalVar>,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        label: &str,
+        span: Span,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut arg_vars = vec![];
+        for (arg_index, arg_ty) in sig.inputs().iter().enumerate() {
+            arg_vars.push(vir::LocalVar::new(
+                format!("_{}_callable_arg", arg_index),
+                self.encoder.encode_snapshot_type(*arg_ty).with_span(span)?,
+            ));
+        }
+        let result_var = vir::LocalVar::new(
+            "_callable_result",
+            self.encoder.encode_snapshot_type(sig.output()).with_span(span)?,
+        );
+        let arg_exprs: Vec<vir::Expr> = arg_vars.iter().cloned().map(vir::Expr::local).collect();
+        let result_expr = vir::Expr::local(result_var.clone());
+
+        // The contract of a closure additionally takes the closure itself
+        let mut contract_args = vec![];
+        if self.encoder.env().query.is_closure(called_def_id) {
+            contract_args.push(callable.clone());
+        }
+        contract_args.extend(arg_exprs.iter().cloned());
+
+        let contract = self.encoder.get_procedure_contract_for_def(called_def_id, call_substs)
+            .with_span(span)?;
+        let pre_spec = contract.functional_precondition(self.encoder.env(), call_substs)
+            .iter()
+            .map(|(assertion, assertion_substs)| self.encoder.encode_assertion(
+                assertion,
+                Some(label),
+                &contract_args,
+                None,
+                true,
+                self.proc_def_id,
+                assertion_substs,
+            ))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .conjoin();
+        let post_spec = contract.functional_postcondition(self.encoder.env(), call_substs)
+            .iter()
+            .map(|(assertion, assertion_substs)| self.encoder.encode_assertion(
+                assertion,
+                Some(label),
+                &contract_args,
+                Some(&result_expr),
+                true,
+                self.proc_def_id,
+                assertion_substs,
+            ))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .conjoin();
+
+        let callable_pre = self.encoder
+            .encode_callable_spec_func_app(callable_ty, sig, callable.clone(), arg_exprs.clone(), None)
+            .with_span(span)?;
+        let callable_post = self.encoder
+            .encode_callable_spec_func_app(callable_ty, sig, callable, arg_exprs, Some(result_expr))
+            .with_span(span)?;
+
+        let mut stmts = vec![];
+        let mut pre_vars = state_vars;
+        pre_vars.extend(arg_vars);
+        let pre_def = vir::Expr::eq_cmp(callable_pre.clone(), pre_spec);
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: if pre_vars.is_empty() {
+                pre_def
+            } else {
+                vir::Expr::forall(
+                    pre_vars.clone(),
+                    vec![vir::Trigger::new(vec![callable_pre])],
+                    pre_def,
+                )
+            },
+        }));
+        let mut post_vars = pre_vars;
+        post_vars.push(result_var);
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: vir::Expr::forall(
+                post_vars,
+                vec![vir::Trigger::new(vec![callable_post.clone()])],
+                vir::Expr::eq_cmp(callable_post, post_spec),
+            ),
+        }));
+
+        Ok(stmts)
+    }
+
     fn encode_assign_sequence_len(
         &mut self,
         encoded_lhs: vir::Expr,
@@ -6620,6 +7751,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             | ty::TyKind::Closure(_, _)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Param(_)
+            | ty::TyKind::FnPtr(_)
             | ty::TyKind::Array(_, _) => {
                 self.encode_copy_snapshot_value(src, dst)?
             }
@@ -6762,6 +7894,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         location,
                     )?);
                 }
+                // The definition is only needed to check specification
+                // entailments, which do not hold without it. Hence, closures
+                // whose contract cannot be encoded can still be used otherwise,
+                // but the user is warned about the missing definition.
+                match self.encode_closure_contract_definition(
+                    ty,
+                    def_id.to_def_id(),
+                    substs,
+                    location,
+                ) {
+                    Ok(contract_stmts) => stmts.extend(contract_stmts),
+                    Err(error) => {
+                        let mut warning = PrustiError::from(error).add_note(
+                            "the contract of this closure cannot be used to prove specification entailments",
+                            Some(span),
+                        );
+                        warning.set_warning();
+                        warning.emit(&self.encoder.env().diagnostic);
+                    }
+                }
             }
 
             mir::AggregateKind::Array(..) => {
@@ -7047,6 +8199,54 @@ fn assert_one_magic_wand(len: usize) -> EncodingResult<()> {
     } else { Ok(()) }
 }
 
+/// Replaces the placeholder of `prusti_contracts::visited()` in a loop
+/// invariant with the items visited by the loop, if the loop is a `for` loop
+/// over a range, a slice or a vector.
+fn replace_for_loop_visited(
+    expr: vir::Expr,
+    visited: Option<&vir::Expr>,
+    span: Span,
+) -> SpannedEncodingResult<vir::Expr> {
+    struct VisitedReplacer<'a> {
+        visited: Option<&'a vir::Expr>,
+        found: bool,
+    }
+    impl<'a> vir::ExprFolder for VisitedReplacer<'a> {
+        fn fold_field(&mut self, vir::FieldExpr {base, field, position}: vir::FieldExpr) -> vir::Expr {
+            // The placeholder is a reference, of which `visited` is the target
+            if let vir::Expr::Local(vir::Local {ref variable, ..}) = *base {
+                if variable.name == FOR_LOOP_VISITED_VAR && self.visited.is_some() {
+                    self.found = true;
+                    return self.visited.unwrap().clone();
+                }
+            }
+            vir::Expr::Field( vir::FieldExpr {
+                base: self.fold_boxed(base),
+                field,
+                position,
+            })
+        }
+        fn fold_local(&mut self, local: vir::Local) -> vir::Expr {
+            if local.variable.name == FOR_LOOP_VISITED_VAR {
+                self.found = true;
+                if let Some(visited) = self.visited {
+                    return visited.clone();
+                }
+            }
+            vir::Expr::Local(local)
+        }
+    }
+    let mut replacer = VisitedReplacer { visited, found: false };
+    let expr = vir::ExprFolder::fold(&mut replacer, expr);
+    if replacer.found && visited.is_none() {
+        return Err(SpannedEncodingError::unsupported(
+            "`visited()` can only be used in the body invariant of a `for` loop over a range of integers, a slice or a vector",
+            span,
+        ));
+    }
+    Ok(expr)
+}
+
 // Checks if a type is a reference to a string, or a reference to a reference to a string, etc.
 fn is_str(ty: ty::Ty<'_>) -> bool {
     match ty.kind() {
@@ -6,22 +6,25 @@
 
 use prusti_common::vir::{optimizations::optimize_program};
 use prusti_common::{
-    config, report::log, Stopwatch, vir::program::Program,
+    config, report::{log, user}, Stopwatch, vir::program::Program,
 };
 use vir_crate::common::check_mode::CheckMode;
 use crate::encoder::Encoder;
 use crate::encoder::counterexamples::counterexample_translation;
 use crate::encoder::counterexamples::counterexample_translation_refactored;
-use prusti_interface::data::VerificationResult;
+use prusti_interface::data::{ProcedureDefId, ProcedureVerificationResult, VerificationResult};
 use prusti_interface::data::VerificationTask;
+use prusti_common::report::verification::ItemStatus;
 use prusti_interface::environment::Environment;
 use prusti_interface::PrustiError;
-use viper::{self, PersistentCache, Viper};
+use viper::{self, Viper};
 use prusti_interface::specs::typed;
 use ::log::{info, debug, error};
-use prusti_server::{VerificationRequest, PrustiClient, process_verification_request, spawn_server_thread, ViperBackendConfig};
+use prusti_server::{VerificationRequest, PrustiClient, process_verification_request, check_backend_configuration, load_verification_cache, spawn_server_thread, ServerMessage, ViperBackendConfig};
 use prusti_rustc_interface::span::DUMMY_SP;
 use prusti_server::tokio::runtime::Builder;
+use rustc_hash::FxHashMap;
+use std::time::{Duration, Instant};
 
 /// A verifier is an object for verifying a single crate, potentially
 /// many times.
@@ -31,6 +34,23 @@ where
 {
     env: &'v Environment<'tcx>,
     encoder: Encoder<'v, 'tcx>,
+    procedure_results: Vec<ProcedureVerificationResult>,
+}
+
+/// The outcome of the verification of a single program.
+struct ProgramVerificationResult {
+    program_name: String,
+    result: ProgramOutcome,
+    duration: Duration,
+}
+
+enum ProgramOutcome {
+    Finished(viper::VerificationResult),
+    /// The verification did not finish in time.
+    TimedOut,
+    /// The server did not verify the program, e.g. because the request was
+    /// cancelled or its worker stopped.
+    Aborted(String),
 }
 
 impl<'v, 'tcx> Verifier<'v, 'tcx> {
@@ -41,15 +61,26 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         Verifier {
             env,
             encoder: Encoder::new(env, def_spec),
+            procedure_results: Vec::new(),
         }
     }
 
+    /// The outcome of each procedure of the last verified task.
+    pub fn procedure_results(&self) -> &[ProcedureVerificationResult] {
+        &self.procedure_results
+    }
+
     pub fn verify(&mut self, task: &VerificationTask<'tcx>) -> VerificationResult {
         info!(
             "Received {} functions to be verified:",
             task.procedures.len()
         );
 
+        if let Err(message) = check_backend_configuration() {
+            PrustiError::invalid_configuration(message).emit(&self.env.diagnostic);
+            return VerificationResult::Failure;
+        }
+
         let mut stopwatch = Stopwatch::start("prusti-viper", "encoding to Viper");
 
         // Dump the configuration
@@ -91,55 +122,114 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         };
         programs.extend(self.encoder.get_core_proof_programs());
 
-        stopwatch.start_next("verifying Viper program");
-        let verification_results = verify_programs(self.env, programs);
-        stopwatch.finish();
-
-        // Group verification results
-        let mut verification_errors : Vec<_> = vec![];
-        let mut consistency_errors : Vec<_> = vec![];
-        let mut java_exceptions : Vec<_> = vec![];
-        for (method_name, result) in verification_results.into_iter() {
-            match result {
-                viper::VerificationResult::Success => {}
-                viper::VerificationResult::ConsistencyErrors(errors) => {
-                    for error in errors.into_iter() {
-                        consistency_errors.push((method_name.clone(), error));
-                    }
-                }
-                viper::VerificationResult::Failure(errors) => {
-                    for error in errors.into_iter() {
-                        verification_errors.push((method_name.clone(), error));
-                    }
-                }
-                viper::VerificationResult::JavaException(exception) => {
-                    java_exceptions.push((method_name, exception));
-                }
-            }
+        // The programs of a procedure are named after it.
+        let mut program_procedures = FxHashMap::default();
+        for &proc_id in &task.procedures {
+            program_procedures.insert(self.env.name.get_unique_item_name(proc_id), proc_id);
+            program_procedures.insert(self.env.name.get_absolute_item_name(proc_id), proc_id);
         }
+        // Report the programs in source order, so that the errors appear in a
+        // deterministic order.
+        programs.sort_by_cached_key(|program| {
+            let span = program_procedures
+                .get(program.get_name())
+                .map(|&proc_id| self.env.query.get_def_span(proc_id));
+            (span.is_none(), span)
+        });
 
-        // Convert verification results to Prusti errors
-        let error_manager = self.encoder.error_manager();
+        stopwatch.start_next("verifying Viper program");
+        let mut program_outcomes: FxHashMap<ProcedureDefId, (ItemStatus, Duration)> = FxHashMap::default();
         let mut result = VerificationResult::Success;
+        // The errors of each program are reported as soon as the results of it
+        // and of the programs before it have arrived, without waiting for the
+        // programs after it.
+        verify_programs(self.env, programs, |program_result| {
+            if let Some(&proc_id) = program_procedures.get(&program_result.program_name) {
+                let status = match &program_result.result {
+                    ProgramOutcome::Finished(viper::VerificationResult::Success) => ItemStatus::Verified,
+                    ProgramOutcome::Finished(_) | ProgramOutcome::Aborted(_) => ItemStatus::Failed,
+                    ProgramOutcome::TimedOut => ItemStatus::TimedOut,
+                };
+                let outcome = program_outcomes
+                    .entry(proc_id)
+                    .or_insert((ItemStatus::Verified, Duration::ZERO));
+                outcome.0 = combine_program_statuses(outcome.0, status);
+                outcome.1 += program_result.duration;
+            }
+            if !self.report_program_result(program_result, &program_procedures) {
+                result = VerificationResult::Failure;
+            }
+        });
+        stopwatch.finish();
 
-        for (method, error) in consistency_errors.into_iter() {
-            PrustiError::internal(
-                format!("consistency error in {}: {}", method, error), DUMMY_SP.into()
-            ).emit(&self.env.diagnostic);
-            result = VerificationResult::Failure;
-        }
+        self.procedure_results = task.procedures.iter().map(|&proc_id| {
+            let (status, duration) = program_outcomes
+                .get(&proc_id)
+                .copied()
+                .unwrap_or((ItemStatus::Skipped, Duration::ZERO));
+            let status = match self.encoder.get_unverified_procedure_status(proc_id) {
+                // Some programs of the procedure could not be encoded.
+                Some(ItemStatus::Failed) => ItemStatus::Failed,
+                Some(unverified_status) if !program_outcomes.contains_key(&proc_id) => unverified_status,
+                _ => status,
+            };
+            ProcedureVerificationResult { procedure: proc_id, status, duration }
+        }).collect();
 
-        for (method, exception) in java_exceptions.into_iter() {
-            error!("Java exception: {}", exception.get_stack_trace());
-            PrustiError::internal(
-                format!("in {}: {}", method, exception), DUMMY_SP.into()
-            ).emit(&self.env.diagnostic);
+        if encoding_errors_count != 0 {
             result = VerificationResult::Failure;
         }
 
+        result
+    }
+
+    /// Converts the result of a single program to Prusti errors and emits
+    /// them. Returns whether the program verified.
+    fn report_program_result(
+        &self,
+        program_result: ProgramVerificationResult,
+        program_procedures: &FxHashMap<String, ProcedureDefId>,
+    ) -> bool {
+        let method = program_result.program_name;
+        let error_manager = self.encoder.error_manager();
+        let span = program_procedures.get(&method)
+            .map(|&proc_id| self.env.query.get_def_span(proc_id))
+            .unwrap_or(DUMMY_SP);
+        let verification_errors = match program_result.result {
+            ProgramOutcome::Finished(viper::VerificationResult::Success) => return true,
+            ProgramOutcome::Finished(viper::VerificationResult::ConsistencyErrors(errors)) => {
+                for error in errors.into_iter() {
+                    PrustiError::internal(
+                        format!("consistency error in {}: {}", method, error), DUMMY_SP.into()
+                    ).emit(&self.env.diagnostic);
+                }
+                return false;
+            }
+            ProgramOutcome::Finished(viper::VerificationResult::JavaException(exception)) => {
+                error!("Java exception: {}", exception.get_stack_trace());
+                PrustiError::internal(
+                    format!("in {}: {}", method, exception), DUMMY_SP.into()
+                ).emit(&self.env.diagnostic);
+                return false;
+            }
+            ProgramOutcome::TimedOut => {
+                PrustiError::verification(
+                    format!("the verification of {} did not finish in time", method), span.into()
+                ).emit(&self.env.diagnostic);
+                return false;
+            }
+            ProgramOutcome::Aborted(reason) => {
+                PrustiError::verification(
+                    format!("the verification of {} did not complete: {}", method, reason), span.into()
+                ).emit(&self.env.diagnostic);
+                return false;
+            }
+            ProgramOutcome::Finished(viper::VerificationResult::Failure(errors)) => errors,
+        };
+
         // Report verification errors
         let mut prusti_errors: Vec<_> = vec![];
-        for (method, verification_error) in verification_errors.into_iter() {
+        for verification_error in verification_errors.into_iter() {
             debug!("Verification error in {}: {:?}", method, verification_error);
             let mut prusti_error = error_manager.translate_verification_error(&verification_error);
 
@@ -171,6 +261,7 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                             &self.encoder,
                             def_id,
                             silicon_counterexample,
+                            error_manager.get_failing_postcondition(&verification_error),
                         );
                         prusti_error = counterexample.annotate_error(prusti_error);
                     } else {
@@ -196,22 +287,29 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             } else {
                 prusti_error.emit(&self.env.diagnostic);
             }
-            result = VerificationResult::Failure;
         }
 
-        if encoding_errors_count != 0 {
-            result = VerificationResult::Failure;
-        }
+        false
+    }
+}
 
-        result
+/// The status of a procedure encoded into several programs.
+fn combine_program_statuses(first: ItemStatus, second: ItemStatus) -> ItemStatus {
+    match (first, second) {
+        (ItemStatus::Failed, _) | (_, ItemStatus::Failed) => ItemStatus::Failed,
+        (ItemStatus::TimedOut, _) | (_, ItemStatus::TimedOut) => ItemStatus::TimedOut,
+        _ => ItemStatus::Verified,
     }
 }
 
-/// Verify a list of programs.
-/// Returns a list of (program_name, verification_result) tuples.
-fn verify_programs(env: &Environment, programs: Vec<Program>)
-    -> Vec<(String, viper::VerificationResult)>
-{
+/// Verify a list of programs, passing their results to `on_result` in the order
+/// of `programs`. The result of each program is passed as soon as it and the
+/// results of the programs before it are available.
+fn verify_programs(
+    env: &Environment,
+    programs: Vec<Program>,
+    mut on_result: impl FnMut(ProgramVerificationResult),
+) {
     let source_path = env.name.source_path();
     let rust_program_name = source_path
         .file_name()
@@ -229,13 +327,19 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
         } else {
             config::viper_backend()
         }.parse().unwrap();
-        let request = VerificationRequest {
-            program,
-            backend_config: ViperBackendConfig::new(backend),
-        };
-        (program_name, request)
+        let requests: Vec<_> = ViperBackendConfig::portfolio(backend)
+            .into_iter()
+            .map(|backend_config| VerificationRequest {
+                program: program.clone(),
+                backend_config,
+            })
+            .collect();
+        (program_name, requests)
     });
-    if let Some(server_address) = config::server_address() {
+    // The portfolio is raced on the server, so start a local one if needed.
+    let server_address = config::server_address()
+        .or_else(|| config::portfolio().then(|| "MOCK".to_string()));
+    if let Some(server_address) = server_address {
         let server_address = if server_address == "MOCK" {
             spawn_server_thread().to_string()
         } else {
@@ -248,35 +352,121 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
                 server_address, error
             )
         });
-        // Here we construct a Tokio runtime to block until completion of the futures returned by
-        // `client.verify`. However, to report verification errors as early as possible,
-        // `verify_programs` should return an asynchronous stream of verification results.
+        // Here we construct a Tokio runtime to block until the server has answered all requests.
+        // The server verifies the requests concurrently and streams back the results as soon as
+        // they are available.
         let runtime = Builder::new_current_thread()
             .thread_name("prusti-viper")
             .enable_all()
             .build()
             .expect("failed to construct Tokio runtime");
-        verification_requests.map(|(program_name, request)| {
-            let remote_result = runtime.block_on(client.verify(request));
-            let result = remote_result.unwrap_or_else(|error| {
-                panic!(
-                    "Verification request of program {} failed: {:?}",
-                    program_name,
-                    error
-                )
+        let (program_names, portfolios): (Vec<_>, Vec<_>) = verification_requests.unzip();
+        let mut results = OrderedResults::new(portfolios.len(), on_result);
+        // For a portfolio, the first configuration to start counts.
+        let mut start_times: Vec<Option<Instant>> = vec![None; portfolios.len()];
+        let stream_result = runtime.block_on(client.verify_portfolio_stream(portfolios, |message| match message {
+            ServerMessage::Started { request_index, program_name, .. } => {
+                info!("Started verification of program {}", program_name);
+                start_times[request_index].get_or_insert_with(Instant::now);
+            }
+            ServerMessage::Finished { request_index, program_name, backend_config, result } => {
+                info!("Finished verification of program {} with {}", program_name, backend_config);
+                if config::portfolio() {
+                    user::message(format!(
+                        "Verification of {} finished with {}",
+                        program_names[request_index],
+                        backend_config,
+                    ));
+                }
+                results.insert(request_index, ProgramVerificationResult {
+                    program_name: program_names[request_index].clone(),
+                    result: ProgramOutcome::Finished(result),
+                    duration: start_times[request_index].map_or(Duration::ZERO, |start| start.elapsed()),
+                });
+            }
+            ServerMessage::Failed { request_index, program_name, timed_out: true, .. } => {
+                info!("Verification of program {} timed out", program_name);
+                results.insert(request_index, ProgramVerificationResult {
+                    program_name: program_names[request_index].clone(),
+                    result: ProgramOutcome::TimedOut,
+                    duration: start_times[request_index].map_or(Duration::ZERO, |start| start.elapsed()),
+                });
+            }
+            ServerMessage::Failed { request_index, program_name, reason, .. } => {
+                info!("Verification of program {} failed: {}", program_name, reason);
+                results.insert(request_index, ProgramVerificationResult {
+                    program_name: program_names[request_index].clone(),
+                    result: ProgramOutcome::Aborted(reason),
+                    duration: start_times[request_index].map_or(Duration::ZERO, |start| start.elapsed()),
+                });
+            }
+            ServerMessage::Completed => {}
+        }));
+        // The programs for which no message arrived are reported as failed
+        let missing_reason = match stream_result {
+            Ok(()) => "the server sent no result".to_string(),
+            Err(error) => format!("the connection to the server failed: {}", error),
+        };
+        for (index, program_name) in program_names.into_iter().enumerate() {
+            results.insert(index, ProgramVerificationResult {
+                program_name,
+                result: ProgramOutcome::Aborted(missing_reason.clone()),
+                duration: Duration::ZERO,
             });
-            (program_name, result)
-        }).collect()
+        }
     } else {
         let mut stopwatch = Stopwatch::start("prusti-viper", "JVM startup");
         let viper = Viper::new_with_args(&config::viper_home(), config::extra_jvm_args());
         stopwatch.start_next("attach current thread to the JVM");
         let viper_thread = viper.attach_current_thread();
         stopwatch.finish();
-        let mut cache = PersistentCache::load_cache(config::cache_path());
-        verification_requests.map(|(program_name, request)| {
-            let result = process_verification_request(&viper_thread, request, &mut cache);
-            (program_name, result)
-        }).collect()
+        let mut cache = load_verification_cache();
+        for (program_name, mut requests) in verification_requests {
+            // Without the portfolio, there is exactly one request per program.
+            let request = requests.pop().unwrap();
+            let start = Instant::now();
+            let result = match process_verification_request(&viper_thread, request, &mut cache) {
+                Ok(result) => ProgramOutcome::Finished(result),
+                Err(reason) => ProgramOutcome::Aborted(reason),
+            };
+            on_result(ProgramVerificationResult {
+                program_name,
+                result,
+                duration: start.elapsed(),
+            });
+        }
+    }
+}
+
+/// Passes the results of the programs to a callback in the order of the
+/// programs, holding back the results that arrive before those of the programs
+/// before them.
+struct OrderedResults<F> {
+    on_result: F,
+    pending: Vec<Option<ProgramVerificationResult>>,
+    /// The index of the first program whose result has not been passed yet.
+    next: usize,
+}
+
+impl<F: FnMut(ProgramVerificationResult)> OrderedResults<F> {
+    fn new(program_count: usize, on_result: F) -> Self {
+        OrderedResults {
+            on_result,
+            pending: (0..program_count).map(|_| None).collect(),
+            next: 0,
+        }
+    }
+
+    /// Records the result of the program at `index`, unless the program
+    /// already has one, and passes on the results that are now in order.
+    fn insert(&mut self, index: usize, result: ProgramVerificationResult) {
+        if index < self.next || self.pending[index].is_some() {
+            return;
+        }
+        self.pending[index] = Some(result);
+        while let Some(result) = self.pending.get_mut(self.next).and_then(Option::take) {
+            (self.on_result)(result);
+            self.next += 1;
+        }
     }
 }
@@ -1,16 +1,27 @@
 //! A module that invokes the verifier `prusti-viper`
 
 use log::{debug, trace, warn};
-use prusti_common::{config, report::user};
+use prusti_common::{
+    config,
+    report::{
+        user,
+        verification::{CrateReport, ItemReport},
+    },
+};
 use prusti_interface::{
-    data::{VerificationResult, VerificationTask},
+    data::{ProcedureVerificationResult, VerificationResult, VerificationTask},
     environment::Environment,
     specs::typed,
 };
 use prusti_viper::verifier::Verifier;
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
 
 pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
     trace!("[verify] enter");
+    let start = Instant::now();
 
     if env.diagnostic.has_errors() {
         warn!("The compiler reported an error, so the program will not be verified.");
@@ -44,9 +55,9 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
             }
         }
 
-        let verification_result =
+        let (verification_result, procedure_results) =
             if verification_task.procedures.is_empty() && verification_task.types.is_empty() {
-                VerificationResult::Success
+                (VerificationResult::Success, Vec::new())
             } else {
                 debug!("Dump borrow checker info...");
                 env.dump_borrowck_info(&verification_task.procedures);
@@ -55,9 +66,13 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
                 let verification_result = verifier.verify(&verification_task);
                 debug!("Verifier returned {:?}", verification_result);
 
-                verification_result
+                (verification_result, verifier.procedure_results().to_vec())
             };
 
+        if let Some(report_dir) = config::verification_report_dir() {
+            write_verification_report(&env, &report_dir, &procedure_results, start.elapsed());
+        }
+
         match verification_result {
             VerificationResult::Success => {
                 if env.diagnostic.has_errors() {
@@ -86,3 +101,37 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
 
     trace!("[verify] exit");
 }
+
+fn write_verification_report(
+    env: &Environment<'_>,
+    report_dir: &Path,
+    procedure_results: &[ProcedureVerificationResult],
+    duration: Duration,
+) {
+    let items = procedure_results
+        .iter()
+        .map(|procedure_result| {
+            let span = env.query.get_def_span(procedure_result.procedure);
+            let location = env.query.codemap().lookup_char_pos(span.lo());
+            ItemReport {
+                item: env.name.get_item_def_path(procedure_result.procedure),
+                location: format!(
+                    "{}:{}:{}",
+                    location.file.name.prefer_local(),
+                    location.line,
+                    location.col_display + 1
+                ),
+                status: procedure_result.status,
+                duration_ms: procedure_result.duration.as_millis() as u64,
+            }
+        })
+        .collect();
+    let report = CrateReport {
+        crate_name: env.name.local_crate_name(),
+        items,
+        duration_ms: duration.as_millis() as u64,
+    };
+    if let Err(error) = report.write(report_dir) {
+        warn!("Failed to write the verification report: {}", error);
+    }
+}
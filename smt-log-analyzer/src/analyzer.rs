@@ -3,19 +3,28 @@
 //! The Z3 log format is documented
 //! [here](https://github.com/viperproject/axiom-profiler/blob/master/LogDocumentation.pdf).
 
-use smt_log_analyzer::{analyze, Settings};
-use std::path::PathBuf;
+use smt_log_analyzer::{analyze, diff_reports, Settings};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "Usage: smt-log-analyzer [--report] <z3-trace> \
+    or smt-log-analyzer --diff <old.report.json> <new.report.json>";
 
 fn main() -> Result<(), std::io::Error> {
-    let mut args = std::env::args();
-    assert!(args.next().is_some());
-    let input_file = args.next().expect("Z3 log file expected");
-    assert!(args.next().is_none(), "Only one argument was expected");
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let (input_file, write_report) = match args.as_slice() {
+        [flag, old_report, new_report] if flag == "--diff" => {
+            return diff_reports(Path::new(old_report), Path::new(new_report));
+        }
+        [flag, input_file] if flag == "--report" => (input_file, true),
+        [input_file] => (input_file, false),
+        _ => panic!("{}", USAGE),
+    };
     let trace_quantifier_triggers = std::env::var("PRUSTI_SMT_TRACE_QUANTIFIER_TRIGGERS")
         .ok()
         .map(|value| value.parse().unwrap());
     let settings = Settings {
         write_statistics: true,
+        write_report,
         quantifier_instantiations_ignore_builtin: false,
         quantifier_instantiations_bound_global_kind: None,
         quantifier_instantiations_bound_trace: None,
@@ -9,16 +9,22 @@ use state::State;
 use std::{
     fs::File,
     io::{BufRead, BufReader},
+    path::Path,
 };
 use types::{QuantifierId, BUILTIN_QUANTIFIER_ID};
 
 mod error;
 mod parser;
+mod profile;
+mod report;
 mod state;
 mod types;
 
 pub struct Settings {
     pub write_statistics: bool,
+    /// Write a ranked summary of the quantifier instantiations into
+    /// `<trace>.report.json` and `<trace>.report.html`.
+    pub write_report: bool,
     pub quantifier_instantiations_ignore_builtin: bool,
     pub quantifier_instantiations_bound_global_kind: Option<u64>,
     pub quantifier_instantiations_bound_trace: Option<u64>,
@@ -97,7 +103,7 @@ fn process_line(settings: &Settings, state: &mut State, line: &str) -> Result<()
             if fingerprint != 0 {
                 // Built-in quantifiers have fingerprint 0; ignore them.
                 let quantifier_id = parser.parse_id()?;
-                state.register_matched_quantifier(quantifier_id)?;
+                state.register_matched_quantifier(fingerprint, quantifier_id)?;
                 let _trigger_id = parser.parse_id()?;
                 while let Some(_variable_instantiation) = parser.try_parse_id()? {}
                 parser.consume(';')?;
@@ -114,7 +120,7 @@ fn process_line(settings: &Settings, state: &mut State, line: &str) -> Result<()
                 }
                 parser.check_eof()?;
             } else {
-                state.register_matched_quantifier(BUILTIN_QUANTIFIER_ID)?;
+                state.register_matched_quantifier(fingerprint, BUILTIN_QUANTIFIER_ID)?;
             }
         }
         EventKind::InstDiscovered => {
@@ -126,22 +132,18 @@ fn process_line(settings: &Settings, state: &mut State, line: &str) -> Result<()
             state.register_inst_discovered(theory)?;
         }
         EventKind::Instance => {
-            state.register_instance()?;
+            let fingerprint = parser.parse_hex_number()?;
+            state.register_instance(fingerprint)?;
+        }
+        EventKind::EndOfInstance => {
+            state.register_end_of_instance();
         }
         EventKind::Unrecognized => {}
     }
     Ok(())
 }
 
-pub fn analyze(
-    z3_trace_path: &std::path::PathBuf,
-    settings: Settings,
-) -> Result<(), std::io::Error> {
-    // TODO: Collect the quantifier definitions from the smt file.
-
-    let file = File::open(z3_trace_path)?;
-    let mut reader = BufReader::new(file);
-
+fn initial_state(settings: &Settings) -> State {
     let mut state = State::default();
 
     // Builtin quantifiers have fingerprint 0.
@@ -156,6 +158,23 @@ pub fn analyze(
     // Tracing triggers.
     state.mark_quantifier_for_tracing(settings.trace_quantifier_triggers);
 
+    if settings.write_report {
+        state.enable_profiling();
+    }
+    state
+}
+
+pub fn analyze(
+    z3_trace_path: &std::path::PathBuf,
+    settings: Settings,
+) -> Result<(), std::io::Error> {
+    // TODO: Collect the quantifier definitions from the smt file.
+
+    let file = File::open(z3_trace_path)?;
+    let mut reader = BufReader::new(file);
+
+    let mut state = initial_state(&settings);
+
     let mut line = String::new();
     let mut prev_line = String::new();
     let mut line_number = 0;
@@ -175,6 +194,9 @@ pub fn analyze(
     if settings.write_statistics {
         state.write_statistics(input_file);
     }
+    if settings.write_report {
+        state.report(input_file).write(input_file)?;
+    }
     if let Some(expected_scopes_count) = settings.check_active_scopes_count {
         assert_eq!(scopes_left, expected_scopes_count);
     }
@@ -189,3 +211,65 @@ pub fn analyze(
     );
     Ok(())
 }
+
+/// Prints how the quantifier instantiations changed between the reports of
+/// two runs, which were written with `Settings::write_report`.
+pub fn diff_reports(old_report: &Path, new_report: &Path) -> Result<(), std::io::Error> {
+    report::diff(old_report, new_report)
+}
+
+/// Builds the report of a complete trace, whose scopes are all popped.
+#[cfg(test)]
+fn report_of_trace(trace: &str) -> report::Report {
+    let settings = Settings {
+        write_statistics: false,
+        write_report: true,
+        quantifier_instantiations_ignore_builtin: false,
+        quantifier_instantiations_bound_global_kind: None,
+        quantifier_instantiations_bound_trace: None,
+        quantifier_instantiations_bound_trace_kind: None,
+        unique_triggers_bound: None,
+        unique_triggers_bound_total: None,
+        check_active_scopes_count: Some(0),
+        pop_scopes_by_one: false,
+        trace_quantifier_triggers: None,
+    };
+    let mut state = initial_state(&settings);
+    // The parser expects the lines to end with a newline, as in the trace file.
+    for line in trace.split_inclusive('\n') {
+        process_line(&settings, &mut state, line).unwrap();
+    }
+    assert_eq!(state.active_scopes_count(), 0);
+    state.report("test.log")
+}
+
+/// A trace with one method, whose scope contains a nested scope. A term
+/// produced by an instance of `q_len` is used to match the other quantifiers.
+#[cfg(test)]
+const TEST_TRACE: &str = "\
+[mk-quant] #10 q_len 1 #9
+[mk-quant] #20 q_append 1 #19
+[mk-quant] #30 q_get 1 #29
+[mk-app] #1 f
+[new-match] 0x1 #10 #9 #1 ; #1
+[instance] 0x1 ; 1
+[mk-app] #2 g #1
+[end-of-instance]
+[push] 0
+[mk-app] #3 basic_block_marker_method_a
+[new-match] 0x2 #20 #19 #2 ; #2
+[instance] 0x2 ; 1
+[end-of-instance]
+[push] 1
+[new-match] 0x3 #30 #29 #2 ; #2
+[instance] 0x3 ; 2
+[end-of-instance]
+[new-match] 0x4 #30 #29 #1 ; #1
+[instance] 0x4 ; 2
+[end-of-instance]
+[new-match] 0x5 #20 #19 #1 ; #1
+[instance] 0x5 ; 2
+[end-of-instance]
+[pop] 1 2
+[pop] 1 1
+";
@@ -0,0 +1,282 @@
+//! Data collected for the quantifier instantiation report. Collecting it is
+//! more expensive than checking the bounds, so it is done only when a report
+//! was requested.
+
+use crate::types::{Fingerprint, Level, QuantifierId, TermId};
+use std::collections::HashMap;
+
+/// How many of the most instantiated quantifiers are kept for each scope.
+const SCOPE_QUANTIFIERS: usize = 5;
+
+/// A push/pop scope that is still active.
+struct OpenScope {
+    index: usize,
+    level: Level,
+    method: Option<usize>,
+    label: Option<String>,
+    matches: usize,
+    instances: usize,
+    quantifier_instances: HashMap<QuantifierId, usize>,
+}
+
+impl OpenScope {
+    fn new(index: usize, level: Level, method: Option<usize>, label: Option<String>) -> Self {
+        Self {
+            index,
+            level,
+            method,
+            label,
+            matches: 0,
+            instances: 0,
+            quantifier_instances: HashMap::new(),
+        }
+    }
+}
+
+/// A popped push/pop scope. The counts include the nested scopes.
+pub(crate) struct ClosedScope {
+    /// The position of the scope in the order in which the scopes were pushed.
+    pub(crate) index: usize,
+    pub(crate) level: Level,
+    /// The index of the top-level scope, in which Silicon verifies a single
+    /// method.
+    pub(crate) method: usize,
+    /// The basic block before the scope was pushed or, if there was none in
+    /// the same method, the first basic block in the scope.
+    pub(crate) label: Option<String>,
+    pub(crate) matches: usize,
+    pub(crate) instances: usize,
+    /// The most instantiated quantifiers in the scope.
+    pub(crate) top_quantifiers: Vec<(QuantifierId, usize)>,
+}
+
+pub(crate) struct Profile {
+    /// The quantifier of the last match with the given fingerprint.
+    fingerprints: HashMap<Fingerprint, QuantifierId>,
+    /// The quantifier whose instance is being processed: the terms created
+    /// until [end-of-instance] are produced by it.
+    current_instance: Option<QuantifierId>,
+    /// The quantifier whose instance produced the term.
+    term_creators: HashMap<TermId, QuantifierId>,
+    /// How many times a term produced by the first quantifier was used to
+    /// match the second quantifier. These are the edges of the instantiation
+    /// graph.
+    pub(crate) caused_matches: HashMap<(QuantifierId, QuantifierId), usize>,
+    /// How many times each quantifier was instantiated via [instance]
+    /// (ignoring push/pop).
+    pub(crate) quantifier_instances: HashMap<QuantifierId, usize>,
+    /// How many times each term was used to match each quantifier (ignoring
+    /// push/pop).
+    pub(crate) trigger_matches: HashMap<(QuantifierId, TermId), usize>,
+    /// The active scopes; the first one is the global scope, which is never
+    /// popped.
+    open_scopes: Vec<OpenScope>,
+    pub(crate) closed_scopes: Vec<ClosedScope>,
+    pushed_scopes_count: usize,
+    methods_count: usize,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            fingerprints: HashMap::new(),
+            current_instance: None,
+            term_creators: HashMap::new(),
+            caused_matches: HashMap::new(),
+            quantifier_instances: HashMap::new(),
+            trigger_matches: HashMap::new(),
+            open_scopes: vec![OpenScope::new(0, 0, None, None)],
+            closed_scopes: Vec::new(),
+            pushed_scopes_count: 0,
+            methods_count: 0,
+        }
+    }
+}
+
+impl Profile {
+    fn current_scope(&mut self) -> &mut OpenScope {
+        self.open_scopes.last_mut().unwrap()
+    }
+
+    /// The matches and instances that happened outside of any scope.
+    pub(crate) fn global_counts(&self) -> (usize, usize) {
+        let global_scope = &self.open_scopes[0];
+        (global_scope.matches, global_scope.instances)
+    }
+
+    pub(crate) fn register_label(&mut self, label: &str) {
+        let scope = self.current_scope();
+        if scope.label.is_none() && scope.method.is_some() {
+            scope.label = Some(label.to_string());
+        }
+    }
+
+    pub(crate) fn register_term(&mut self, term_id: TermId) {
+        if let Some(quantifier_id) = self.current_instance {
+            self.term_creators.insert(term_id, quantifier_id);
+        }
+    }
+
+    pub(crate) fn register_match(&mut self, fingerprint: Fingerprint, quantifier_id: QuantifierId) {
+        if fingerprint != 0 {
+            self.fingerprints.insert(fingerprint, quantifier_id);
+        }
+        self.current_scope().matches += 1;
+    }
+
+    pub(crate) fn register_matched_trigger_term(
+        &mut self,
+        quantifier_id: QuantifierId,
+        term_id: TermId,
+    ) {
+        *self
+            .trigger_matches
+            .entry((quantifier_id, term_id))
+            .or_default() += 1;
+        if let Some(&creator) = self.term_creators.get(&term_id) {
+            *self
+                .caused_matches
+                .entry((creator, quantifier_id))
+                .or_default() += 1;
+        }
+    }
+
+    pub(crate) fn register_instance(&mut self, fingerprint: Fingerprint) {
+        // Instances of theory axioms have fingerprint 0.
+        self.current_instance = self.fingerprints.get(&fingerprint).copied();
+        let scope = self.open_scopes.last_mut().unwrap();
+        scope.instances += 1;
+        if let Some(quantifier_id) = self.current_instance {
+            *scope.quantifier_instances.entry(quantifier_id).or_default() += 1;
+            *self.quantifier_instances.entry(quantifier_id).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn register_end_of_instance(&mut self) {
+        self.current_instance = None;
+    }
+
+    pub(crate) fn push_scope(&mut self, level: Level, previous_label: Option<&str>) {
+        let method = if level == 1 {
+            self.methods_count += 1;
+            self.methods_count - 1
+        } else {
+            self.current_scope().method.unwrap()
+        };
+        // The labels of the previous method do not describe a new method.
+        let label = previous_label
+            .filter(|_| level > 1)
+            .map(|label| label.to_string());
+        let scope = OpenScope::new(self.pushed_scopes_count, level, Some(method), label);
+        self.pushed_scopes_count += 1;
+        self.open_scopes.push(scope);
+    }
+
+    pub(crate) fn pop_scope(&mut self) {
+        assert!(
+            self.open_scopes.len() > 1,
+            "the global scope cannot be popped"
+        );
+        let scope = self.open_scopes.pop().unwrap();
+        let parent = self.current_scope();
+        // The counts of the global scope include only the events outside of
+        // any method.
+        if parent.method.is_some() {
+            parent.matches += scope.matches;
+            parent.instances += scope.instances;
+            for (quantifier_id, count) in &scope.quantifier_instances {
+                *parent
+                    .quantifier_instances
+                    .entry(*quantifier_id)
+                    .or_default() += count;
+            }
+        }
+        let mut top_quantifiers: Vec<_> = scope.quantifier_instances.into_iter().collect();
+        top_quantifiers
+            .sort_by(|(id1, count1), (id2, count2)| count2.cmp(count1).then(id1.cmp(id2)));
+        top_quantifiers.truncate(SCOPE_QUANTIFIERS);
+        self.closed_scopes.push(ClosedScope {
+            index: scope.index,
+            level: scope.level,
+            method: scope.method.unwrap(),
+            label: scope.label,
+            matches: scope.matches,
+            instances: scope.instances,
+            top_quantifiers,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Profile, SCOPE_QUANTIFIERS};
+    use crate::{report::ScopeReport, report_of_trace, TEST_TRACE};
+
+    #[test]
+    fn nested_scopes_are_counted_in_their_method() {
+        let report = report_of_trace(TEST_TRACE);
+        assert_eq!((report.global_matches, report.global_instances), (1, 1));
+        assert_eq!((report.total_matches, report.total_instances), (5, 5));
+
+        assert_eq!(report.methods.len(), 1);
+        let method = &report.methods[0];
+        assert_eq!((method.index, method.level, method.method), (0, 1, 0));
+        assert_eq!(method.label.as_deref(), Some("basic_block_marker_method_a"));
+        assert_eq!((method.matches, method.instances), (4, 4));
+
+        assert_eq!(report.scopes.len(), 1);
+        let scope = &report.scopes[0];
+        assert_eq!((scope.index, scope.level, scope.method), (1, 2, 0));
+        // The label of a nested scope is the basic block before the push.
+        assert_eq!(scope.label.as_deref(), Some("basic_block_marker_method_a"));
+        assert_eq!((scope.matches, scope.instances), (3, 3));
+    }
+
+    #[test]
+    fn top_quantifiers_are_ordered_by_instances_then_id() {
+        let report = report_of_trace(TEST_TRACE);
+        fn quantifiers(scope: &ScopeReport) -> Vec<(u32, &str, usize)> {
+            scope
+                .quantifiers
+                .iter()
+                .map(|quantifier| (quantifier.id, quantifier.name.as_str(), quantifier.count))
+                .collect()
+        }
+        assert_eq!(
+            quantifiers(&report.scopes[0]),
+            [(30, "q_get", 2), (20, "q_append", 1)]
+        );
+        // Both quantifiers have two instances in the method.
+        assert_eq!(
+            quantifiers(&report.methods[0]),
+            [(20, "q_append", 2), (30, "q_get", 2)]
+        );
+    }
+
+    #[test]
+    fn top_quantifiers_are_truncated() {
+        let mut profile = Profile::default();
+        profile.push_scope(1, None);
+        for quantifier_id in 0..10 {
+            let fingerprint = u64::from(quantifier_id) + 1;
+            profile.register_match(fingerprint, quantifier_id);
+            for _ in 0..quantifier_id {
+                profile.register_instance(fingerprint);
+            }
+        }
+        profile.pop_scope();
+        let scope = &profile.closed_scopes[0];
+        assert_eq!((scope.matches, scope.instances), (10, 45));
+        assert_eq!(scope.top_quantifiers.len(), SCOPE_QUANTIFIERS);
+        assert_eq!(scope.top_quantifiers[0], (9, 9));
+        assert_eq!(scope.top_quantifiers[SCOPE_QUANTIFIERS - 1], (5, 5));
+        // The global scope does not include the counts of the methods.
+        assert_eq!(profile.global_counts(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "the global scope cannot be popped")]
+    fn global_scope_is_not_popped() {
+        Profile::default().pop_scope();
+    }
+}
@@ -0,0 +1,391 @@
+//! A ranked summary of the quantifier instantiations in a Z3 trace, written as
+//! JSON (for comparing runs) and HTML (for reading).
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// How many of the most used triggers are reported.
+pub(crate) const REPORTED_TRIGGERS: usize = 100;
+/// How many of the scopes with the most instances are reported.
+pub(crate) const REPORTED_SCOPES: usize = 100;
+/// How many successors in the instantiation graph are reported for each
+/// quantifier.
+pub(crate) const REPORTED_SUCCESSORS: usize = 5;
+
+/// All lists are sorted deterministically, so that the reports of two runs
+/// can also be compared with a textual diff.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Report {
+    pub(crate) input_file: String,
+    pub(crate) total_matches: usize,
+    pub(crate) total_instances: usize,
+    /// The matches and instances that happened outside of any push/pop scope.
+    pub(crate) global_matches: usize,
+    pub(crate) global_instances: usize,
+    /// The quantifiers with at least one match, the most instantiated first.
+    pub(crate) quantifiers: Vec<QuantifierReport>,
+    /// The terms most often used to match a quantifier.
+    pub(crate) triggers: Vec<TriggerReport>,
+    /// The top-level scopes, in each of which Silicon verifies one method.
+    pub(crate) methods: Vec<ScopeReport>,
+    /// The nested scopes with the most instances.
+    pub(crate) scopes: Vec<ScopeReport>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct QuantifierReport {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    /// The number of [instance] events (ignoring push/pop).
+    pub(crate) instances: usize,
+    /// The number of [new-match] events (ignoring push/pop).
+    pub(crate) matches: usize,
+    /// The largest number of matches on a single trace.
+    pub(crate) max_trace_matches: usize,
+    pub(crate) unique_triggers: usize,
+    /// How many matches used a term that was already used to match the
+    /// quantifier on the same trace.
+    pub(crate) repeated_triggers: usize,
+    /// The number of matches of any quantifier that used a term produced by
+    /// an instance of this quantifier.
+    pub(crate) caused_matches: usize,
+    /// The number of quantifiers matched with terms produced by this one, that
+    /// is, the fan-out of the quantifier in the instantiation graph.
+    pub(crate) fan_out: usize,
+    /// The quantifiers that were matched most often with terms produced by
+    /// this one.
+    pub(crate) successors: Vec<QuantifierCount>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct QuantifierCount {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    pub(crate) count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TriggerReport {
+    pub(crate) quantifier_id: u32,
+    pub(crate) quantifier_name: String,
+    pub(crate) term_id: u32,
+    pub(crate) term: String,
+    pub(crate) matches: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ScopeReport {
+    /// The position of the scope in the order in which the scopes were pushed.
+    pub(crate) index: usize,
+    pub(crate) level: u32,
+    pub(crate) method: usize,
+    /// The basic block (`basic_block_marker`) that identifies the scope.
+    pub(crate) label: Option<String>,
+    /// The counts include the nested scopes.
+    pub(crate) matches: usize,
+    pub(crate) instances: usize,
+    pub(crate) quantifiers: Vec<QuantifierCount>,
+}
+
+impl ScopeReport {
+    /// The key used to match the methods of two runs. The labels are stable,
+    /// but not every method has one.
+    fn method_key(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("method #{}", self.method),
+        }
+    }
+}
+
+impl Report {
+    pub(crate) fn write(&self, input_file: &str) -> std::io::Result<()> {
+        let writer = BufWriter::new(File::create(format!("{}.report.json", input_file))?);
+        serde_json::to_writer_pretty(writer, self)?;
+        std::fs::write(format!("{}.report.html", input_file), self.to_html())
+    }
+
+    fn read(path: &Path) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        self.write_html(&mut html).unwrap();
+        html
+    }
+
+    fn write_html(&self, f: &mut String) -> std::fmt::Result {
+        let title = format!("Quantifier instantiations in {}", self.input_file);
+        writeln!(
+            f,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">"
+        )?;
+        writeln!(f, "<title>{}</title>", escape(&title))?;
+        writeln!(
+            f,
+            "<style>body {{ font-family: sans-serif; }} \
+            table {{ border-collapse: collapse; }} \
+            td, th {{ border: 1px solid #ccc; padding: 2px 6px; text-align: left; }} \
+            td.number {{ text-align: right; }} code {{ white-space: pre-wrap; }}</style>"
+        )?;
+        writeln!(f, "</head>\n<body>\n<h1>{}</h1>", escape(&title))?;
+        writeln!(
+            f,
+            "<p>{} instances and {} matches in total; {} instances and {} matches \
+            outside of any scope.</p>",
+            self.total_instances, self.total_matches, self.global_instances, self.global_matches
+        )?;
+
+        writeln!(f, "<h2>Quantifiers</h2>")?;
+        table_header(
+            f,
+            &[
+                "ID",
+                "Name",
+                "Instances",
+                "Matches",
+                "Max Trace Matches",
+                "Unique Triggers",
+                "Repeated Triggers",
+                "Caused Matches",
+                "Fan-out",
+                "Most Caused Matches",
+            ],
+        )?;
+        for quantifier in &self.quantifiers {
+            writeln!(
+                f,
+                "<tr><td class=\"number\">{}</td><td>{}</td>{}{}{}{}{}{}{}<td>{}</td></tr>",
+                quantifier.id,
+                escape(&quantifier.name),
+                number_cell(quantifier.instances),
+                number_cell(quantifier.matches),
+                number_cell(quantifier.max_trace_matches),
+                number_cell(quantifier.unique_triggers),
+                number_cell(quantifier.repeated_triggers),
+                number_cell(quantifier.caused_matches),
+                number_cell(quantifier.fan_out),
+                quantifier_counts(&quantifier.successors),
+            )?;
+        }
+        writeln!(f, "</table>")?;
+
+        writeln!(f, "<h2>Triggers</h2>")?;
+        table_header(f, &["Quantifier", "Term ID", "Term", "Matches"])?;
+        for trigger in &self.triggers {
+            writeln!(
+                f,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td><code>{}</code></td>{}</tr>",
+                escape(&trigger.quantifier_name),
+                trigger.term_id,
+                escape(&trigger.term),
+                number_cell(trigger.matches),
+            )?;
+        }
+        writeln!(f, "</table>")?;
+
+        writeln!(f, "<h2>Methods</h2>")?;
+        write_scope_table(f, &self.methods)?;
+        writeln!(f, "<h2>Scopes</h2>")?;
+        write_scope_table(f, &self.scopes)?;
+        writeln!(f, "</body>\n</html>")
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn table_header(f: &mut String, columns: &[&str]) -> std::fmt::Result {
+    write!(f, "<table>\n<tr>")?;
+    for column in columns {
+        write!(f, "<th>{}</th>", column)?;
+    }
+    writeln!(f, "</tr>")
+}
+
+fn number_cell(number: usize) -> String {
+    format!("<td class=\"number\">{}</td>", number)
+}
+
+fn quantifier_counts(quantifiers: &[QuantifierCount]) -> String {
+    quantifiers
+        .iter()
+        .map(|quantifier| format!("{} ({})", escape(&quantifier.name), quantifier.count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn write_scope_table(f: &mut String, scopes: &[ScopeReport]) -> std::fmt::Result {
+    table_header(
+        f,
+        &[
+            "Scope",
+            "Method",
+            "Level",
+            "Basic Block",
+            "Instances",
+            "Matches",
+            "Most Instantiated Quantifiers",
+        ],
+    )?;
+    for scope in scopes {
+        writeln!(
+            f,
+            "<tr><td class=\"number\">{}</td><td class=\"number\">{}</td>\
+            <td class=\"number\">{}</td><td>{}</td>{}{}<td>{}</td></tr>",
+            scope.index,
+            scope.method,
+            scope.level,
+            escape(scope.label.as_deref().unwrap_or("")),
+            number_cell(scope.instances),
+            number_cell(scope.matches),
+            quantifier_counts(&scope.quantifiers),
+        )?;
+    }
+    writeln!(f, "</table>")
+}
+
+/// Prints how the instances of each quantifier and method changed between two
+/// reports. Quantifiers are identified by their names because their ids
+/// depend on the order in which Z3 creates them.
+pub(crate) fn diff(old_report_path: &Path, new_report_path: &Path) -> std::io::Result<()> {
+    let old_report = Report::read(old_report_path)?;
+    let new_report = Report::read(new_report_path)?;
+    let mut changes = String::new();
+    write_diff(&mut changes, &old_report, &new_report).unwrap();
+    print!("{}", changes);
+    Ok(())
+}
+
+fn write_diff(f: &mut String, old_report: &Report, new_report: &Report) -> std::fmt::Result {
+    writeln!(
+        f,
+        "Total instances: {}",
+        format_change(old_report.total_instances, new_report.total_instances)
+    )?;
+    writeln!(
+        f,
+        "Total matches: {}",
+        format_change(old_report.total_matches, new_report.total_matches)
+    )?;
+    let instances_by_name = |report: &Report| {
+        let mut instances = BTreeMap::new();
+        for quantifier in &report.quantifiers {
+            *instances.entry(quantifier.name.clone()).or_default() += quantifier.instances;
+        }
+        instances
+    };
+    write_changes(
+        f,
+        "Quantifier instances",
+        instances_by_name(old_report),
+        instances_by_name(new_report),
+    )?;
+    let instances_by_method = |report: &Report| {
+        let mut instances = BTreeMap::new();
+        for method in &report.methods {
+            *instances.entry(method.method_key()).or_default() += method.instances;
+        }
+        instances
+    };
+    write_changes(
+        f,
+        "Method instances",
+        instances_by_method(old_report),
+        instances_by_method(new_report),
+    )
+}
+
+fn format_change(old: usize, new: usize) -> String {
+    format!("{} -> {} ({:+})", old, new, new as i128 - old as i128)
+}
+
+fn write_changes(
+    f: &mut String,
+    title: &str,
+    old: BTreeMap<String, usize>,
+    new: BTreeMap<String, usize>,
+) -> std::fmt::Result {
+    let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    let mut changes: Vec<_> = keys
+        .into_iter()
+        .map(|key| {
+            let old_count = old.get(key).copied().unwrap_or_default();
+            let new_count = new.get(key).copied().unwrap_or_default();
+            (key, old_count, new_count)
+        })
+        .filter(|(_, old_count, new_count)| old_count != new_count)
+        .collect();
+    // The largest changes first; the keys are already sorted.
+    changes
+        .sort_by_key(|(_, old_count, new_count)| std::cmp::Reverse(old_count.abs_diff(*new_count)));
+    writeln!(f, "{}: {} changed", title, changes.len())?;
+    for (key, old_count, new_count) in changes {
+        writeln!(f, "  {}: {}", key, format_change(old_count, new_count))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_diff;
+    use crate::{report_of_trace, TEST_TRACE};
+
+    #[test]
+    fn diff_of_identical_reports_is_empty() {
+        let report = report_of_trace(TEST_TRACE);
+        let mut changes = String::new();
+        write_diff(&mut changes, &report, &report).unwrap();
+        assert_eq!(
+            changes,
+            "Total instances: 5 -> 5 (+0)\n\
+            Total matches: 5 -> 5 (+0)\n\
+            Quantifier instances: 0 changed\n\
+            Method instances: 0 changed\n"
+        );
+    }
+
+    #[test]
+    fn diff_reports_largest_changes_first() {
+        let old_report = report_of_trace(TEST_TRACE);
+        // The quantifier ids differ between runs, so the quantifiers are
+        // matched by name.
+        let new_trace = TEST_TRACE.replace("#10 q_len", "#10 q_length").replace(
+            "[pop] 1 2",
+            "[instance] 0x3 ; 2\n[instance] 0x3 ; 2\n[pop] 1 2",
+        );
+        let new_report = report_of_trace(&new_trace);
+        let mut changes = String::new();
+        write_diff(&mut changes, &old_report, &new_report).unwrap();
+        assert_eq!(
+            changes,
+            "Total instances: 5 -> 7 (+2)\n\
+            Total matches: 5 -> 5 (+0)\n\
+            Quantifier instances: 3 changed\n\
+            \x20 q_get: 2 -> 4 (+2)\n\
+            \x20 q_len: 1 -> 0 (-1)\n\
+            \x20 q_length: 0 -> 1 (+1)\n\
+            Method instances: 1 changed\n\
+            \x20 basic_block_marker_method_a: 4 -> 6 (+2)\n"
+        );
+    }
+}
@@ -3,7 +3,12 @@ use csv::Writer;
 use crate::{
     error::Error,
     parser::TheoryKind,
-    types::{Level, QuantifierId, TermId, BUILTIN_QUANTIFIER_ID},
+    profile::{ClosedScope, Profile},
+    report::{
+        QuantifierCount, QuantifierReport, Report, ScopeReport, TriggerReport, REPORTED_SCOPES,
+        REPORTED_SUCCESSORS, REPORTED_TRIGGERS,
+    },
+    types::{Fingerprint, Level, QuantifierId, TermId, BUILTIN_QUANTIFIER_ID},
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -118,10 +123,19 @@ pub(crate) struct State {
     current_active_scopes_count: Level,
     traced_quantifier: Option<QuantifierId>,
     traced_quantifier_triggers: Option<String>,
+    /// The data for the report; collected only if a report was requested.
+    profile: Option<Profile>,
 }
 
 impl State {
+    pub(crate) fn enable_profiling(&mut self) {
+        self.profile = Some(Profile::default());
+    }
+
     pub(crate) fn register_label(&mut self, label: String) {
+        if let Some(profile) = &mut self.profile {
+            profile.register_label(&label);
+        }
         self.trace.push(BasicBlockVisitedEvent {
             level: self.current_active_scopes_count,
             label,
@@ -146,8 +160,12 @@ impl State {
 
     pub(crate) fn register_matched_quantifier(
         &mut self,
+        fingerprint: Fingerprint,
         quantifier_id: QuantifierId,
     ) -> Result<(), Error> {
+        if let Some(profile) = &mut self.profile {
+            profile.register_match(fingerprint, quantifier_id);
+        }
         *self
             .total_quantifiers_matched_counters
             .get_mut(&quantifier_id)
@@ -207,6 +225,9 @@ impl State {
         term_id: TermId,
     ) -> Result<(), Error> {
         self.trace_quantifier_trigger(quantifier_id, term_id)?;
+        if let Some(profile) = &mut self.profile {
+            profile.register_matched_trigger_term(quantifier_id, term_id);
+        }
         self.unique_quantifier_triggers
             .get_mut(&quantifier_id)
             .unwrap()
@@ -261,7 +282,10 @@ impl State {
         }
     }
 
-    pub(crate) fn register_instance(&mut self) -> Result<(), Error> {
+    pub(crate) fn register_instance(&mut self, fingerprint: Fingerprint) -> Result<(), Error> {
+        if let Some(profile) = &mut self.profile {
+            profile.register_instance(fingerprint);
+        }
         self.total_quantifiers_instance_counters += 1;
         let events = &mut self.quantifiers_instance_events;
         if let Some(last) = events.last_mut() {
@@ -276,12 +300,21 @@ impl State {
         Ok(())
     }
 
+    pub(crate) fn register_end_of_instance(&mut self) {
+        if let Some(profile) = &mut self.profile {
+            profile.register_end_of_instance();
+        }
+    }
+
     pub(crate) fn register_term_function_application(
         &mut self,
         term_id: TermId,
         name: String,
         args: Vec<TermId>,
     ) {
+        if let Some(profile) = &mut self.profile {
+            profile.register_term(term_id);
+        }
         self.terms
             .insert(term_id, Term::FunctionApplication { name, args });
     }
@@ -306,10 +339,19 @@ impl State {
 
     pub(crate) fn push_scope(&mut self) {
         self.current_active_scopes_count += 1;
+        if let Some(profile) = &mut self.profile {
+            let previous_label = self.trace.last().map(|event| event.label.as_str());
+            profile.push_scope(self.current_active_scopes_count, previous_label);
+        }
     }
 
     pub(crate) fn pop_scopes(&mut self, scopes_to_pop: u32) {
         self.current_active_scopes_count -= scopes_to_pop;
+        if let Some(profile) = &mut self.profile {
+            for _ in 0..scopes_to_pop {
+                profile.pop_scope();
+            }
+        }
 
         let max_instances = self
             .quantifiers_instance_events
@@ -675,6 +717,132 @@ impl State {
         }
     }
 
+    pub(crate) fn report(&self, input_file: &str) -> Report {
+        let profile = self.profile.as_ref().expect("profiling is not enabled");
+        let quantifier_count = |(quantifier_id, count): (QuantifierId, usize)| QuantifierCount {
+            id: quantifier_id,
+            name: self.quantifiers[&quantifier_id].name.clone(),
+            count,
+        };
+
+        let mut successors: HashMap<QuantifierId, Vec<(QuantifierId, usize)>> = HashMap::new();
+        for (&(creator, matched), &count) in &profile.caused_matches {
+            successors
+                .entry(creator)
+                .or_default()
+                .push((matched, count));
+        }
+        let mut quantifiers: Vec<_> = self
+            .total_quantifiers_matched_counters
+            .iter()
+            .filter(|(_, &matches)| matches > 0)
+            .map(|(&quantifier_id, &matches)| {
+                let mut successors = successors.remove(&quantifier_id).unwrap_or_default();
+                successors
+                    .sort_by(|(id1, count1), (id2, count2)| count2.cmp(count1).then(id1.cmp(id2)));
+                QuantifierReport {
+                    id: quantifier_id,
+                    name: self.quantifiers[&quantifier_id].name.clone(),
+                    instances: profile
+                        .quantifier_instances
+                        .get(&quantifier_id)
+                        .copied()
+                        .unwrap_or_default(),
+                    matches,
+                    max_trace_matches: self.max_quantifier_matched_event_counters[&quantifier_id],
+                    unique_triggers: self.unique_quantifier_triggers[&quantifier_id].len(),
+                    repeated_triggers: self
+                        .multi_term_quantifiers
+                        .get(&quantifier_id)
+                        .map_or(0, Vec::len),
+                    caused_matches: successors.iter().map(|(_, count)| count).sum(),
+                    fan_out: successors.len(),
+                    successors: successors
+                        .into_iter()
+                        .take(REPORTED_SUCCESSORS)
+                        .map(quantifier_count)
+                        .collect(),
+                }
+            })
+            .collect();
+        quantifiers.sort_by(|quantifier1, quantifier2| {
+            quantifier2
+                .instances
+                .cmp(&quantifier1.instances)
+                .then(quantifier2.matches.cmp(&quantifier1.matches))
+                .then(quantifier1.name.cmp(&quantifier2.name))
+                .then(quantifier1.id.cmp(&quantifier2.id))
+        });
+
+        let mut trigger_matches: Vec<_> = profile.trigger_matches.iter().collect();
+        trigger_matches
+            .sort_by(|(key1, count1), (key2, count2)| count2.cmp(count1).then(key1.cmp(key2)));
+        let triggers = trigger_matches
+            .into_iter()
+            .take(REPORTED_TRIGGERS)
+            .map(|(&(quantifier_id, term_id), &matches)| {
+                let mut term = String::new();
+                self.render_term(term_id, &mut term, 10).unwrap();
+                TriggerReport {
+                    quantifier_id,
+                    quantifier_name: self.quantifiers[&quantifier_id].name.clone(),
+                    term_id,
+                    term,
+                    matches,
+                }
+            })
+            .collect();
+
+        let scope_report = |scope: &ClosedScope| ScopeReport {
+            index: scope.index,
+            level: scope.level,
+            method: scope.method,
+            label: scope.label.clone(),
+            matches: scope.matches,
+            instances: scope.instances,
+            quantifiers: scope
+                .top_quantifiers
+                .iter()
+                .copied()
+                .map(quantifier_count)
+                .collect(),
+        };
+        let mut methods: Vec<_> = profile
+            .closed_scopes
+            .iter()
+            .filter(|scope| scope.level == 1)
+            .collect();
+        methods.sort_by_key(|scope| scope.index);
+        let mut scopes: Vec<_> = profile
+            .closed_scopes
+            .iter()
+            .filter(|scope| scope.level > 1)
+            .collect();
+        scopes.sort_by(|scope1, scope2| {
+            scope2
+                .instances
+                .cmp(&scope1.instances)
+                .then(scope1.index.cmp(&scope2.index))
+        });
+
+        let (global_matches, global_instances) = profile.global_counts();
+        Report {
+            input_file: input_file.to_string(),
+            total_matches: self.total_quantifiers_matched_counters.values().sum(),
+            total_instances: self.total_quantifiers_instance_counters,
+            global_matches,
+            global_instances,
+            quantifiers,
+            triggers,
+            methods: methods.into_iter().map(scope_report).collect(),
+            scopes: scopes
+                .into_iter()
+                .take(REPORTED_SCOPES)
+                .map(scope_report)
+                .collect(),
+        }
+    }
+
     fn check_bounds_explanatory_quantifier_name(&self, quantifier_id: QuantifierId) -> String {
         if self.quantifiers[&quantifier_id].name.starts_with("k!") {
             format!(
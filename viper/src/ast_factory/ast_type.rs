@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::ast_factory::{structs::Type, AstFactory};
+use crate::ast_factory::{structs::Type, AstFactory, FloatSizeViper};
 use jni::objects::JObject;
 use viper_sys::wrappers::viper::silver::ast;
 
@@ -80,11 +80,7 @@ impl<'a> AstFactory<'a> {
     }
 
     pub fn backend_f32_type(&self) -> Type<'a> {
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap();
-        let float_factory_ = ast::utility::FloatFactory::with(self.env);
-        let float_factory = ast::utility::FloatFactory::new(&float_factory_, 24, 8, rm).unwrap();
+        let (float_factory_, float_factory) = self.float_factory(FloatSizeViper::F32);
 
         let obj = self.jni.unwrap_result(ast::utility::FloatFactory::call_typ(
             &float_factory_,
@@ -94,11 +90,7 @@ impl<'a> AstFactory<'a> {
     }
 
     pub fn backend_f64_type(&self) -> Type<'a> {
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap();
-        let float_factory_ = ast::utility::FloatFactory::with(self.env);
-        let float_factory = ast::utility::FloatFactory::new(&float_factory_, 52, 12, rm).unwrap();
+        let (float_factory_, float_factory) = self.float_factory(FloatSizeViper::F64);
 
         let obj = self.jni.unwrap_result(ast::utility::FloatFactory::call_typ(
             &float_factory_,
@@ -40,12 +40,23 @@ pub enum BinOpFloat {
 }
 
 // Floating-Point Size
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum FloatSizeViper {
     F32,
     F64,
 }
 
+impl FloatSizeViper {
+    /// The number of bits of the significand (including the hidden bit) and
+    /// of the exponent of the IEEE 754 binary format.
+    fn significand_and_exponent_bits(self) -> (i32, i32) {
+        match self {
+            FloatSizeViper::F32 => (24, 8),
+            FloatSizeViper::F64 => (53, 11),
+        }
+    }
+}
+
 // Bitwise Operations on Backend Bitvectors
 pub enum UnOpBv {
     Not,
@@ -409,6 +420,21 @@ impl<'a> AstFactory<'a> {
         (factory_, factory)
     }
 
+    pub fn float_factory(
+        &self,
+        f_size: FloatSizeViper,
+    ) -> (ast::utility::FloatFactory<'a>, JObject<'a>) {
+        let rm = ast::utility::RoundingMode::with(self.env)
+            .call_RNE()
+            .unwrap(); // Rounding mode
+        let factory_ = ast::utility::FloatFactory::with(self.env); // FloatFactory
+        let (significand_bits, exponent_bits) = f_size.significand_and_exponent_bits();
+        let factory =
+            ast::utility::FloatFactory::new(&factory_, significand_bits, exponent_bits, rm)
+                .unwrap(); // FloatFactory JObject
+        (factory_, factory)
+    }
+
     pub fn int_to_backend_bv(&self, bv_size: BvSize, expr: Expr<'a>) -> Expr<'a> {
         let (factory_, factory) = self.bv_factory(bv_size);
         let from_int = ast::utility::BVFactory::call_to__int(
@@ -492,16 +518,7 @@ impl<'a> AstFactory<'a> {
         left: Expr,
         right: Expr,
     ) -> Expr<'a> {
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap(); // Rounding mode
-        let factory_ = ast::utility::FloatFactory::with(self.env); // FloatFactory
-        let factory = match f_size {
-            //
-            FloatSizeViper::F32 => ast::utility::FloatFactory::new(&factory_, 24, 8, rm),
-            FloatSizeViper::F64 => ast::utility::FloatFactory::new(&factory_, 52, 12, rm),
-        }
-        .unwrap();
+        let (factory_, factory) = self.float_factory(f_size);
         let op = match op_kind {
             // create FloatFactory function to call
             BinOpFloat::Add => ast::utility::FloatFactory::call_add(
@@ -565,16 +582,7 @@ impl<'a> AstFactory<'a> {
     }
 
     pub fn float_unop(&self, op_kind: UnOpFloat, f_size: FloatSizeViper, arg: Expr) -> Expr<'a> {
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap(); // Rounding mode
-        let factory_ = ast::utility::FloatFactory::with(self.env); // FloatFactory
-        let factory = match f_size {
-            // FloatFactory JObject
-            FloatSizeViper::F32 => ast::utility::FloatFactory::new(&factory_, 24, 8, rm),
-            FloatSizeViper::F64 => ast::utility::FloatFactory::new(&factory_, 52, 12, rm),
-        }
-        .unwrap();
+        let (factory_, factory) = self.float_factory(f_size);
 
         let op = match op_kind {
             UnOpFloat::Neg => ast::utility::FloatFactory::call_neg(
@@ -621,13 +629,82 @@ impl<'a> AstFactory<'a> {
         self.backend_func_app(op, &[arg], self.no_position())
     }
 
+    fn backend_float_type(&self, f_size: FloatSizeViper) -> Type<'a> {
+        match f_size {
+            FloatSizeViper::F32 => self.backend_f32_type(),
+            FloatSizeViper::F64 => self.backend_f64_type(),
+        }
+    }
+
+    fn backend_bv_type(&self, bv_size: BvSize) -> Type<'a> {
+        let (factory_, factory) = self.bv_factory(bv_size);
+        Type::new(
+            self.jni
+                .unwrap_result(ast::utility::BVFactory::call_typ(&factory_, factory)),
+        )
+    }
+
+    /// Converts a floating-point number into another format, rounding to the
+    /// nearest representable number (ties to even).
+    pub fn float_to_float(&self, from: FloatSizeViper, to: FloatSizeViper, arg: Expr) -> Expr<'a> {
+        let (significand_bits, exponent_bits) = to.significand_and_exponent_bits();
+        let function = self.backend_func(
+            &format!("fp_{from:?}_to_{to:?}"),
+            &format!("(_ to_fp {exponent_bits} {significand_bits}) RNE"),
+            self.backend_float_type(to),
+            &[self.local_var_decl("x", self.backend_float_type(from))],
+        );
+        self.backend_func_app(function, &[arg], self.no_position())
+    }
+
+    /// Converts a bitvector, interpreted as a signed or unsigned integer, into
+    /// a floating-point number, rounding to the nearest representable number
+    /// (ties to even).
+    pub fn bv_to_float(
+        &self,
+        bv_size: BvSize,
+        signed: bool,
+        to: FloatSizeViper,
+        arg: Expr,
+    ) -> Expr<'a> {
+        let (significand_bits, exponent_bits) = to.significand_and_exponent_bits();
+        let (name, conversion) = if signed {
+            ("sbv", "to_fp")
+        } else {
+            ("ubv", "to_fp_unsigned")
+        };
+        let function = self.backend_func(
+            &format!("{name}{}_to_{to:?}", bv_size.to_i32()),
+            &format!("(_ {conversion} {exponent_bits} {significand_bits}) RNE"),
+            self.backend_float_type(to),
+            &[self.local_var_decl("x", self.backend_bv_type(bv_size))],
+        );
+        self.backend_func_app(function, &[arg], self.no_position())
+    }
+
+    /// Converts a floating-point number into a bitvector, interpreted as a
+    /// signed or unsigned integer, rounding towards zero. The result is
+    /// unspecified if the rounded number does not fit into the bitvector.
+    pub fn float_to_bv(
+        &self,
+        from: FloatSizeViper,
+        signed: bool,
+        bv_size: BvSize,
+        arg: Expr,
+    ) -> Expr<'a> {
+        let name = if signed { "sbv" } else { "ubv" };
+        let function = self.backend_func(
+            &format!("fp_{from:?}_to_{name}{}", bv_size.to_i32()),
+            &format!("(_ fp.to_{name} {}) RTZ", bv_size.to_i32()),
+            self.backend_bv_type(bv_size),
+            &[self.local_var_decl("x", self.backend_float_type(from))],
+        );
+        self.backend_func_app(function, &[arg], self.no_position())
+    }
+
     pub fn backend_f32_lit(&self, bits: u32) -> Expr<'a> {
         let bv = self.backend_bv32_lit(bits);
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap(); // Rounding mode
-        let float_factory_ = ast::utility::FloatFactory::with(self.env); // FloatFactory
-        let float_factory = ast::utility::FloatFactory::new(&float_factory_, 24, 8, rm).unwrap(); // FloatFactory JObject
+        let (float_factory_, float_factory) = self.float_factory(FloatSizeViper::F32);
         let from_bv = ast::utility::FloatFactory::call_from__bv(
             &float_factory_,
             float_factory,
@@ -639,11 +716,7 @@ impl<'a> AstFactory<'a> {
 
     pub fn backend_f64_lit(&self, bits: u64) -> Expr<'a> {
         let bv = self.backend_bv64_lit(bits);
-        let rm = ast::utility::RoundingMode::with(self.env)
-            .call_RNE()
-            .unwrap(); // Rounding mode
-        let float_factory_ = ast::utility::FloatFactory::with(self.env); // FloatFactory
-        let float_factory = ast::utility::FloatFactory::new(&float_factory_, 52, 12, rm).unwrap(); // FloatFactory JObject
+        let (float_factory_, float_factory) = self.float_factory(FloatSizeViper::F64);
         let from_bv = ast::utility::FloatFactory::call_from__bv(
             &float_factory_,
             float_factory,
@@ -989,6 +1062,27 @@ impl<'a> AstFactory<'a> {
         Expr::new(obj)
     }
 
+    /// Creates a function that is interpreted by the SMT solver: an application
+    /// of the function is translated to `(smt_name args...)`.
+    pub fn backend_func(
+        &self,
+        name: &str,
+        smt_name: &str,
+        typ: Type,
+        formal_args: &[LocalVarDecl],
+    ) -> JObject<'a> {
+        self.jni.unwrap_result(ast::BackendFunc::with(self.env).new(
+            self.jni.new_string(name),
+            self.jni.new_string(smt_name),
+            typ.to_jobject(),
+            self.jni.new_seq(&map_to_jobjects!(formal_args)),
+            self.no_position().to_jobject(),
+            self.no_info(),
+            self.jni.new_string(name),
+            self.no_trafos(),
+        ))
+    }
+
     pub fn backend_func_app(
         &self,
         backend_function: JObject,
@@ -6,115 +6,279 @@
 
 use log::{error, info, warn};
 
-use crate::verification_result::VerificationResult;
+use crate::{remote_cache::RemoteCache, verification_result::VerificationResult};
+use fs2::FileExt;
 use std::{
     collections::HashMap,
     fs, io,
     ops::DerefMut,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::SystemTime,
 };
 
 pub trait Cache {
     fn get(&self, request: u64) -> Option<VerificationResult>;
     fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult>;
 }
-// We can change the representation here (e.g. adding fields):
-#[derive(Debug, Clone)]
+
+/// A verification cache that keeps its entries in a directory on disk, one
+/// file per request fingerprint. Several processes can share the same
+/// directory: entries are written atomically, and migrations and evictions
+/// are done while holding a lock file next to the directory.
+///
+/// With an empty path, the cache is only kept in memory. Optionally, results
+/// that are not found locally are looked up in a `RemoteCache`.
+#[derive(Debug)]
 pub struct PersistentCache {
     updated: bool,
-    load_loc: PathBuf,
+    /// Results that were loaded or inserted during this session.
     data: HashMap<u64, VerificationResult>,
+    store: Option<DirectoryStore>,
+    remote: Option<RemoteCache>,
 }
 
-const RESULT_CACHE_VERSION: u64 = 2;
+/// The version of the on-disk layout and of the remote cache protocol.
+/// Versions up to 2 stored all results in a single bincode file, keyed by the
+/// hash of the whole request.
+pub const RESULT_CACHE_VERSION: u64 = 3;
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct ResultCache {
-    data: HashMap<u64, VerificationResult>,
-    version: u64,
+const VERSION_FILE_NAME: &str = "VERSION";
+const ENTRIES_DIR_NAME: &str = "entries";
+
+#[derive(Debug)]
+struct DirectoryStore {
+    root: PathBuf,
+    /// The size (in bytes) above which the least recently used entries are
+    /// evicted when the cache is saved.
+    max_size: u64,
 }
 
-impl From<(PathBuf, ResultCache)> for PersistentCache {
-    /// Used when loading cache from disk
-    fn from((load_loc, rc): (PathBuf, ResultCache)) -> Self {
-        assert_eq!(rc.version, RESULT_CACHE_VERSION);
-        let data = rc.data;
-        PersistentCache {
-            updated: false,
-            load_loc,
-            data,
+/// Holds an exclusive lock on the lock file of a cache directory.
+struct StoreLock(fs::File);
+
+impl StoreLock {
+    fn acquire(root: &Path) -> io::Result<Self> {
+        let mut lock_path = root.as_os_str().to_owned();
+        lock_path.push(".lock");
+        if let Some(parent) = root.parent() {
+            fs::create_dir_all(parent)?;
         }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(lock_path)?;
+        file.lock_exclusive()?;
+        Ok(StoreLock(file))
     }
 }
-impl From<&PersistentCache> for ResultCache {
-    /// Used when saving cache to disk
-    fn from(cache: &PersistentCache) -> Self {
-        ResultCache {
-            data: cache.data.clone(),
-            version: RESULT_CACHE_VERSION,
-        }
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
     }
 }
 
-impl PersistentCache {
-    pub fn load_cache(cache_loc: PathBuf) -> Self {
-        let mut data_res: Option<ResultCache> = None;
-        if !cache_loc.as_os_str().is_empty() {
-            if let Ok(f) = fs::File::open(&cache_loc) {
-                match bincode::deserialize_from::<_, ResultCache>(&mut io::BufReader::new(f)) {
-                    Ok(data) => {
-                        if data.version == RESULT_CACHE_VERSION {
-                            info!("Loaded cache from \"{}\"", cache_loc.display());
-                            data_res = Some(data);
-                        } else {
-                            error!(
-                                "Cache version mismatch when reading from \"{}\": \
-                                    expected={} actual={}",
-                                cache_loc.display(),
-                                RESULT_CACHE_VERSION,
-                                data.version
-                            )
-                        }
-                    }
-                    Err(e) => error!("Failed to read cache from \"{}\": {e}", cache_loc.display()),
-                }
+impl DirectoryStore {
+    /// Opens the cache directory, creating or migrating it if needed. Returns
+    /// `None` if the directory cannot be used, e.g. because it was written by
+    /// a newer version of Prusti.
+    fn open(root: PathBuf, max_size: u64) -> io::Result<Option<Self>> {
+        let _lock = StoreLock::acquire(&root)?;
+        if root.is_file() {
+            // Versions up to 2 used a single file. Its keys are hashes of
+            // whole requests, which cannot be converted to fingerprints.
+            info!(
+                "Discarding the cache file \"{}\" of an older Prusti version",
+                root.display()
+            );
+            fs::remove_file(&root)?;
+        }
+        fs::create_dir_all(root.join(ENTRIES_DIR_NAME))?;
+        let version_path = root.join(VERSION_FILE_NAME);
+        let version = match fs::read_to_string(&version_path) {
+            Ok(content) => content.trim().parse::<u64>().ok(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        let store = DirectoryStore { root, max_size };
+        match version {
+            Some(RESULT_CACHE_VERSION) => {}
+            Some(version) if version > RESULT_CACHE_VERSION => {
+                warn!(
+                    "The cache in \"{}\" has version {}, but this Prusti only supports \
+                    version {}. The cache will not be used.",
+                    store.root.display(),
+                    version,
+                    RESULT_CACHE_VERSION
+                );
+                return Ok(None);
+            }
+            version => {
+                store.migrate_from(version)?;
+                fs::write(&version_path, RESULT_CACHE_VERSION.to_string())?;
             }
         }
-        PersistentCache::from((
-            cache_loc,
-            data_res.unwrap_or_else(|| {
-                info!("Cache file doesn't exist or is invalid. Using fresh cache.");
-                ResultCache {
-                    data: HashMap::new(),
-                    version: RESULT_CACHE_VERSION,
-                }
-            }),
-        ))
-    }
-    pub fn save_cache(&self, cache_loc: &Path) {
-        match fs::File::create(cache_loc) {
-            Ok(f) => {
-                info!("Saving cache to \"{}\"", cache_loc.display());
-                bincode::serialize_into(&mut io::BufWriter::new(f), &ResultCache::from(self))
-                    .unwrap_or_else(|e| error!("Failed to write cache: {e}"));
+        info!("Using cache directory \"{}\"", store.root.display());
+        Ok(Some(store))
+    }
+
+    /// Converts the entries of an older layout to the current one. `None`
+    /// stands for a directory without a version file, i.e. a new or broken
+    /// cache.
+    fn migrate_from(&self, version: Option<u64>) -> io::Result<()> {
+        match version {
+            // Nothing to convert: there was no directory layout before
+            // version 3.
+            None | Some(0..=2) => {
+                info!(
+                    "Initializing cache directory \"{}\" (found version {:?}, expected {})",
+                    self.root.display(),
+                    version,
+                    RESULT_CACHE_VERSION
+                );
+                let entries_dir = self.root.join(ENTRIES_DIR_NAME);
+                fs::remove_dir_all(&entries_dir)?;
+                fs::create_dir_all(&entries_dir)
+            }
+            Some(version) => unreachable!("no migration from cache version {}", version),
+        }
+    }
+
+    fn entry_path(&self, key: u64) -> PathBuf {
+        let name = format!("{:016x}", key);
+        self.root
+            .join(ENTRIES_DIR_NAME)
+            .join(&name[..2])
+            .join(format!("{}.bin", name))
+    }
+
+    fn read(&self, key: u64) -> Option<VerificationResult> {
+        let path = self.entry_path(key);
+        let file = fs::File::open(&path).ok()?;
+        match bincode::deserialize_from(io::BufReader::new(file)) {
+            Ok(result) => {
+                // Mark the entry as recently used.
+                let _ = filetime::set_file_mtime(&path, filetime::FileTime::now());
+                Some(result)
+            }
+            Err(e) => {
+                warn!("Removing invalid cache entry \"{}\": {e}", path.display());
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    fn write(&self, key: u64, result: &VerificationResult) -> io::Result<()> {
+        let path = self.entry_path(key);
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir)?;
+        // Write to a temporary file first, so that other processes never see
+        // partially written entries.
+        let tmp_path = dir.join(format!(
+            ".{}.{}.tmp",
+            path.file_name().unwrap().to_string_lossy(),
+            std::process::id()
+        ));
+        let bytes =
+            bincode::serialize(result).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path).or_else(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            // Another process might have stored the same entry concurrently.
+            if path.exists() {
+                Ok(())
+            } else {
+                Err(e)
             }
-            Err(e) => error!("Failed to create cache file: {e}"),
+        })
+    }
+
+    /// Removes the least recently used entries until the total size of the
+    /// entries is at most `max_size`.
+    fn evict(&self) -> io::Result<()> {
+        let _lock = StoreLock::acquire(&self.root)?;
+        let mut entries = vec![];
+        let mut total_size = 0;
+        for shard in fs::read_dir(self.root.join(ENTRIES_DIR_NAME))? {
+            for entry in fs::read_dir(shard?.path())? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                let last_used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                total_size += metadata.len();
+                entries.push((last_used, metadata.len(), entry.path()));
+            }
+        }
+        if total_size <= self.max_size {
+            return Ok(());
+        }
+        entries.sort();
+        let mut evicted = 0;
+        for (_, size, path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total_size -= size;
+            evicted += 1;
+        }
+        info!(
+            "Evicted {} entries from cache directory \"{}\"",
+            evicted,
+            self.root.display()
+        );
+        Ok(())
+    }
+}
+
+impl PersistentCache {
+    /// Opens the cache directory `cache_loc`. Entries beyond `max_size_mb`
+    /// megabytes are evicted, least recently used first, whenever the cache
+    /// is saved.
+    pub fn load_cache(cache_loc: PathBuf, max_size_mb: u64) -> Self {
+        let store = if cache_loc.as_os_str().is_empty() {
+            None
+        } else {
+            let max_size = max_size_mb.saturating_mul(1024 * 1024);
+            DirectoryStore::open(cache_loc.clone(), max_size).unwrap_or_else(|e| {
+                error!(
+                    "Failed to open cache directory \"{}\": {e}",
+                    cache_loc.display()
+                );
+                None
+            })
+        };
+        if store.is_none() {
+            info!("Using a fresh in-memory cache.");
+        }
+        PersistentCache {
+            updated: false,
+            data: HashMap::new(),
+            store,
+            remote: None,
         }
     }
+
+    /// Shares the results with a remote cache: local misses are looked up in
+    /// the remote cache and new results are uploaded to it.
+    pub fn with_remote(mut self, remote: RemoteCache) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
     pub fn save(&mut self) {
-        // Save cache to disk, if changed and save path is valid
+        // Entries are written to disk as soon as they are inserted, so only
+        // the eviction is left to do.
         if self.updated {
-            if self.load_loc.as_os_str().is_empty() {
-                warn!("Cannot save cache, because the cache path is empty.")
-            } else {
-                let mut save_dir = self.load_loc.clone();
-                save_dir.pop();
-                match fs::create_dir_all(&save_dir) {
-                    Ok(()) => self.save_cache(&self.load_loc),
-                    Err(e) => error!("Failed to create cache dir: {e}"),
+            if let Some(store) = &self.store {
+                if let Err(e) = store.evict() {
+                    error!("Failed to evict cache entries: {e}");
                 }
+            } else {
+                warn!("Cannot save cache, because the cache path is empty.")
             }
+            self.updated = false;
         }
     }
 }
@@ -127,10 +291,31 @@ impl Drop for PersistentCache {
 
 impl Cache for &mut PersistentCache {
     fn get(&self, request: u64) -> Option<VerificationResult> {
-        self.data.get(&request).cloned()
+        if let Some(result) = self.data.get(&request) {
+            return Some(result.clone());
+        }
+        if let Some(result) = self.store.as_ref().and_then(|store| store.read(request)) {
+            return Some(result);
+        }
+        let result = self.remote.as_ref()?.get(request)?;
+        if let Some(store) = &self.store {
+            // Keep a local copy, so that the next lookup does not need the network.
+            if let Err(e) = store.write(request, &result) {
+                error!("Failed to write cache entry: {e}");
+            }
+        }
+        Some(result)
     }
     fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
         self.updated = true;
+        if let Some(store) = &self.store {
+            if let Err(e) = store.write(request, &result) {
+                error!("Failed to write cache entry: {e}");
+            }
+        }
+        if let Some(remote) = &self.remote {
+            remote.insert(request, result.clone());
+        }
         self.data.insert(request, result)
     }
 }
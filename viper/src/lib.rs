@@ -14,8 +14,10 @@ mod jni_utils;
 pub mod utils;
 mod cache;
 mod java_exception;
+mod remote_cache;
 pub mod silicon_counterexample;
 pub mod smt_manager;
+mod smt_solver_kind;
 mod verification_backend;
 mod verification_context;
 mod verification_result;
@@ -23,7 +25,7 @@ mod verifier;
 mod viper;
 
 pub use crate::{
-    ast_factory::*, ast_utils::*, cache::*, java_exception::*, silicon_counterexample::*,
-    verification_backend::*, verification_context::*, verification_result::*, verifier::*,
-    viper::*,
+    ast_factory::*, ast_utils::*, cache::*, java_exception::*, remote_cache::*,
+    silicon_counterexample::*, smt_solver_kind::*, verification_backend::*,
+    verification_context::*, verification_result::*, verifier::*, viper::*,
 };
@@ -0,0 +1,129 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{cache::RESULT_CACHE_VERSION, verification_result::VerificationResult, Cache};
+use log::{info, warn};
+use reqwest::{blocking::Client, StatusCode};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// A verification cache on a remote server, which can be shared by several
+/// machines.
+///
+/// The server has to implement a simple key-value protocol: a result is
+/// stored with `PUT <url>/<version>/<key>` and retrieved with
+/// `GET <url>/<version>/<key>`, where `<version>` is `RESULT_CACHE_VERSION`
+/// and `<key>` is the fingerprint of the request as 16 hexadecimal digits.
+/// Bodies are bincode-encoded `VerificationResult`s; a missing entry is
+/// answered with `404 Not Found`. Uploads carry the bearer token of the
+/// cache, and are skipped if it has none. The `prusti-cache-server` binary of
+/// `prusti-server` implements this protocol.
+///
+/// Network errors are treated as cache misses. After the first one, the
+/// cache is disabled for the rest of the session, so that an unreachable
+/// server does not slow down every verification request.
+#[derive(Debug)]
+pub struct RemoteCache {
+    url: String,
+    client: Client,
+    upload_token: Option<String>,
+    disabled: AtomicBool,
+}
+
+impl RemoteCache {
+    /// Note: the blocking HTTP client must not be created, used or dropped
+    /// from within an asynchronous runtime.
+    pub fn new(url: &str, timeout: Duration, upload_token: Option<String>) -> Self {
+        info!("Using remote cache at \"{}\"", url);
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("failed to build the HTTP client of the remote cache");
+        RemoteCache {
+            url: url.trim_end_matches('/').to_string(),
+            client,
+            upload_token,
+            disabled: AtomicBool::new(false),
+        }
+    }
+
+    fn entry_url(&self, key: u64) -> String {
+        format!("{}/{}/{:016x}", self.url, RESULT_CACHE_VERSION, key)
+    }
+
+    fn disable(&self, message: String) {
+        if !self.disabled.swap(true, Ordering::Relaxed) {
+            warn!("{}; disabling the remote cache", message);
+        }
+    }
+}
+
+impl Cache for &RemoteCache {
+    fn get(&self, request: u64) -> Option<VerificationResult> {
+        if self.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let response = match self.client.get(self.entry_url(request)).send() {
+            Ok(response) => response,
+            Err(e) => {
+                self.disable(format!("Failed to reach the remote cache: {e}"));
+                return None;
+            }
+        };
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::NOT_FOUND => return None,
+            status => {
+                self.disable(format!("The remote cache answered with status {status}"));
+                return None;
+            }
+        }
+        let bytes = match response.bytes() {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to read the response of the remote cache: {e}");
+                return None;
+            }
+        };
+        match bincode::deserialize(&bytes) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                warn!(
+                    "Ignoring invalid entry {:016x} of the remote cache: {e}",
+                    request
+                );
+                None
+            }
+        }
+    }
+    fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
+        let upload_token = match &self.upload_token {
+            Some(upload_token) => upload_token,
+            None => return None,
+        };
+        if self.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let body = bincode::serialize(&result).expect("could not encode verification result");
+        let request = self
+            .client
+            .put(self.entry_url(request))
+            .bearer_auth(upload_token)
+            .body(body);
+        match request.send() {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => self.disable(format!(
+                "The remote cache rejected a result with status {}",
+                response.status()
+            )),
+            Err(e) => self.disable(format!("Failed to reach the remote cache: {e}")),
+        }
+        // The previous value is not known without another request.
+        None
+    }
+}
@@ -112,11 +112,17 @@ impl SmtManager {
             let trace_files = connection.smt_listener.join().unwrap();
             for trace_file in trace_files {
                 let trace_file = PathBuf::from(trace_file);
+                if !trace_file.exists() {
+                    // Only Z3 writes traces; the wrapper ignores the trace
+                    // file for other solvers.
+                    continue;
+                }
                 // Since Silicon kills the SMT solver, there is no guarantee
                 // that the scopes will be fully popped.
                 let expected_scopes_count = None;
                 let settings = smt_log_analyzer::Settings {
                     write_statistics: self.write_smt_statistics,
+                    write_report: self.write_smt_statistics,
                     quantifier_instantiations_ignore_builtin: self
                         .quantifier_instantiations_ignore_builtin,
                     quantifier_instantiations_bound_global_kind: self
@@ -0,0 +1,48 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+/// The SMT solver used by the Silicon backend. Carbon always uses the solver
+/// that is configured in Boogie.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SmtSolverKind {
+    Z3,
+    Cvc5,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnknownSmtSolverError(String);
+
+impl fmt::Display for UnknownSmtSolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid SMT solver: '{}'. Allowed values are 'Z3' and 'cvc5'",
+            self.0
+        )
+    }
+}
+
+impl std::str::FromStr for SmtSolverKind {
+    type Err = UnknownSmtSolverError;
+    fn from_str(solver: &str) -> Result<Self, Self::Err> {
+        match solver.to_lowercase().as_str() {
+            "z3" => Ok(SmtSolverKind::Z3),
+            "cvc5" => Ok(SmtSolverKind::Cvc5),
+            _ => Err(UnknownSmtSolverError(solver.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for SmtSolverKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SmtSolverKind::Z3 => write!(f, "Z3"),
+            SmtSolverKind::Cvc5 => write!(f, "cvc5"),
+        }
+    }
+}
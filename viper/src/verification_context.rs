@@ -5,7 +5,12 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::{
-    ast_factory::*, ast_utils::*, verification_backend::VerificationBackend, verifier::Verifier,
+    ast_factory::*,
+    ast_utils::*,
+    jni_utils::JniUtils,
+    smt_solver_kind::SmtSolverKind,
+    verification_backend::VerificationBackend,
+    verifier::{Verifier, VerifierStopHandle},
 };
 use jni::AttachGuard;
 use log::{debug, info};
@@ -13,6 +18,7 @@ use std::{
     env,
     path::{Path, PathBuf},
 };
+use viper_sys::wrappers::viper::*;
 
 use crate::smt_manager::SmtManager;
 
@@ -51,31 +57,50 @@ impl<'a> VerificationContext<'a> {
             backend,
             extra_args,
             None,
+            SmtSolverKind::Z3,
             z3_exe,
             boogie_exe,
             SmtManager::default(),
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_verifier(
         &self,
         backend: VerificationBackend,
         extra_args: Vec<String>,
         report_path: Option<PathBuf>,
-        z3_exe: String,
+        smt_solver: SmtSolverKind,
+        smt_solver_exe: String,
         boogie_exe: Option<String>,
         smt_manager: SmtManager,
     ) -> Verifier {
         let mut verifier_args: Vec<String> = vec![];
 
-        // Set Z3 binary
-        info!("Using Z3 exe: '{}'", &z3_exe);
+        // Set SMT solver binary
+        info!("Using {} exe: '{}'", smt_solver, &smt_solver_exe);
         assert!(
-            Path::new(&z3_exe).is_file(),
-            "The Z3_EXE environment variable ({:?}) does not point to a valid file.",
-            z3_exe
+            Path::new(&smt_solver_exe).is_file(),
+            "The path to {} ({:?}) does not point to a valid file.",
+            smt_solver,
+            smt_solver_exe
         );
-        verifier_args.extend(vec!["--z3Exe".to_string(), z3_exe]);
+        match smt_solver {
+            SmtSolverKind::Z3 => verifier_args.extend(vec!["--z3Exe".to_string(), smt_solver_exe]),
+            SmtSolverKind::Cvc5 => {
+                assert_eq!(
+                    backend,
+                    VerificationBackend::Silicon,
+                    "Only Silicon supports cvc5"
+                );
+                verifier_args.extend(vec![
+                    "--prover".to_string(),
+                    "cvc5".to_string(),
+                    "--cvc5Exe".to_string(),
+                    smt_solver_exe,
+                ]);
+            }
+        }
 
         // Set Boogie binary
         if let VerificationBackend::Carbon = backend {
@@ -100,4 +125,14 @@ impl<'a> VerificationContext<'a> {
             .parse_command_line(&verifier_args)
             .start()
     }
+
+    /// Stops the verifier of the given handle, which may be running on
+    /// another thread. The ongoing verification of that verifier is aborted.
+    pub fn stop_verifier(&self, handle: &VerifierStopHandle) {
+        let jni = JniUtils::new(&self.env);
+        jni.unwrap_result(
+            silver::verifier::Verifier::with(&self.env)
+                .call_stop(handle.verifier_instance.as_obj()),
+        );
+    }
 }
@@ -13,7 +13,10 @@ use crate::{
     verification_backend::VerificationBackend,
     verification_result::{VerificationError, VerificationResult},
 };
-use jni::{objects::JObject, JNIEnv};
+use jni::{
+    objects::{GlobalRef, JObject},
+    JNIEnv,
+};
 use log::{debug, error, info};
 use std::path::PathBuf;
 use viper_sys::wrappers::{scala, viper::*};
@@ -27,6 +30,14 @@ pub struct Verifier<'a> {
     smt_manager: SmtManager,
 }
 
+/// A handle to a verifier that is not bound to the thread that created the
+/// verifier. It can be used to stop a running verification from another
+/// thread (see `VerificationContext::stop_verifier`).
+#[derive(Clone)]
+pub struct VerifierStopHandle {
+    pub(crate) verifier_instance: GlobalRef,
+}
+
 impl<'a> Verifier<'a> {
     pub fn new(
         env: &'a JNIEnv,
@@ -103,6 +114,14 @@ impl<'a> Verifier<'a> {
         self
     }
 
+    pub fn stop_handle(&self) -> VerifierStopHandle {
+        VerifierStopHandle {
+            verifier_instance: self
+                .jni
+                .unwrap_result(self.env.new_global_ref(self.verifier_instance)),
+        }
+    }
+
     pub fn verify(&mut self, program: Program) -> VerificationResult {
         self.ast_utils.with_local_frame(16, || {
             debug!(
@@ -0,0 +1,67 @@
+use std::fs;
+use viper::*;
+
+fn consistency_error(message: &str) -> VerificationResult {
+    VerificationResult::ConsistencyErrors(vec![message.to_string()])
+}
+
+#[test]
+fn entries_survive_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join("cache");
+    {
+        let mut cache = PersistentCache::load_cache(cache_path.clone(), 1024);
+        (&mut cache).insert(1, VerificationResult::Success);
+        (&mut cache).insert(2, consistency_error("error"));
+    }
+    let mut cache = PersistentCache::load_cache(cache_path, 1024);
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    assert_eq!((&mut cache).get(2), Some(consistency_error("error")));
+    assert_eq!((&mut cache).get(3), None);
+}
+
+#[test]
+fn save_evicts_entries_above_max_size() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join("cache");
+    {
+        let mut cache = PersistentCache::load_cache(cache_path.clone(), 0);
+        (&mut cache).insert(1, VerificationResult::Success);
+        cache.save();
+    }
+    let mut cache = PersistentCache::load_cache(cache_path, 0);
+    assert_eq!((&mut cache).get(1), None);
+}
+
+#[test]
+fn legacy_cache_file_is_discarded() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join("cache.bin");
+    fs::write(&cache_path, b"not a cache directory").unwrap();
+    {
+        let mut cache = PersistentCache::load_cache(cache_path.clone(), 1024);
+        assert_eq!((&mut cache).get(1), None);
+        (&mut cache).insert(1, VerificationResult::Success);
+    }
+    assert!(cache_path.is_dir());
+    let mut cache = PersistentCache::load_cache(cache_path, 1024);
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+}
+
+#[test]
+fn newer_cache_version_is_not_used() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join("cache");
+    fs::create_dir_all(&cache_path).unwrap();
+    fs::write(cache_path.join("VERSION"), "1000").unwrap();
+    {
+        let mut cache = PersistentCache::load_cache(cache_path.clone(), 1024);
+        (&mut cache).insert(1, VerificationResult::Success);
+        assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    }
+    assert_eq!(
+        fs::read_to_string(cache_path.join("VERSION")).unwrap(),
+        "1000"
+    );
+    assert!(!cache_path.join("entries").exists());
+}
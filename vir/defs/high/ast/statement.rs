@@ -129,7 +129,9 @@ pub struct LoopInvariant {
     /// memory.
     pub maybe_modified_places: Vec<Predicate>,
     pub functional_specifications: Vec<Expression>,
-    pub variant: Option<LoopVariant>,
+    /// The components of a (lexicographic) loop variant, the most
+    /// significant first. Empty if the loop has no variant.
+    pub variants: Vec<LoopVariant>,
     pub position: Position,
 }
 
@@ -38,6 +38,8 @@ pub enum Type {
     Str,
     Closure(Closure),
     FunctionDef(FunctionDef),
+    /// A trait object `dyn Trait`.
+    TraitObject(TraitObject),
     Projection(Projection),
     Unsupported(Unsupported),
     Trusted(Trusted),
@@ -228,6 +230,21 @@ pub struct FunctionDef {
     // pub arguments: Vec<Type>,
 }
 
+#[display(
+    fmt = "dyn {}<{}, {}>",
+    name,
+    "display::cjoin(arguments)",
+    "display::cjoin(lifetimes)"
+)]
+pub struct TraitObject {
+    /// The name of the principal trait.
+    pub name: String,
+    /// The type arguments of the principal trait, followed by the types of
+    /// its associated type bindings (e.g. `Item = u32`).
+    pub arguments: Vec<Type>,
+    pub lifetimes: Vec<LifetimeConst>,
+}
+
 #[display(
     fmt = "{}<{}, {}>",
     name,
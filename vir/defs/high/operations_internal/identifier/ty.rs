@@ -28,6 +28,7 @@ impl WithIdentifier for ty::Type {
             ty::Type::Str => "Str".to_string(),
             ty::Type::Closure(ty) => ty.get_identifier(),
             ty::Type::FunctionDef(ty) => ty.get_identifier(),
+            ty::Type::TraitObject(ty) => ty.get_identifier(),
             ty::Type::Projection(ty) => ty.get_identifier(),
             ty::Type::Unsupported(ty) => ty.get_identifier(),
             ty::Type::Trusted(ty) => ty.get_identifier(),
@@ -170,6 +171,14 @@ impl WithIdentifier for ty::FunctionDef {
     }
 }
 
+impl WithIdentifier for ty::TraitObject {
+    fn get_identifier(&self) -> String {
+        let mut identifier = format!("trait_object${}", self.name);
+        append_type_arguments(&mut identifier, &self.arguments);
+        identifier
+    }
+}
+
 impl WithIdentifier for ty::Projection {
     fn get_identifier(&self) -> String {
         let mut identifier = self.name.clone();
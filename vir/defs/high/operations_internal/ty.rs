@@ -187,7 +187,8 @@ impl Type {
             | Self::Struct(Struct { arguments, .. })
             | Self::Enum(Enum { arguments, .. })
             | Self::Union(Union { arguments, .. })
-            | Self::Projection(Projection { arguments, .. }) => {
+            | Self::Projection(Projection { arguments, .. })
+            | Self::TraitObject(TraitObject { arguments, .. }) => {
                 arguments.iter().any(|arg| arg.is_type_var())
             }
             Self::Closure(_) => {
@@ -123,7 +123,10 @@ macro_rules! __unary_op__ {
 
 __unary_op__! {
     not Not,
-    minus Minus
+    minus Minus,
+    is_nan IsNaN,
+    is_infinite IsInfinite,
+    abs Abs
 }
 
 macro_rules! __binary_op__ {
@@ -949,6 +952,10 @@ impl Expr {
                 variable: LocalVar { typ, .. },
                 ..
             }) => typ,
+            Expr::UnaryOp(UnaryOp {
+                op_kind: UnaryOpKind::IsNaN | UnaryOpKind::IsInfinite,
+                ..
+            }) => &Type::Bool,
             Expr::LabelledOld(LabelledOld { base, .. })
             | Expr::Unfolding(Unfolding { base, .. })
             | Expr::UnaryOp(UnaryOp { argument: base, .. }) => base.get_type(),
@@ -1100,6 +1107,13 @@ impl Expr {
                 CastKind::IntIntoBV(BitVector::Unsigned(BitVectorSize::BV128)) => {
                     &Type::BitVector(BitVector::Unsigned(BitVectorSize::BV128))
                 }
+                CastKind::FloatIntoInt(..) => &Type::Int,
+                CastKind::IntIntoFloat(_, float) | CastKind::FloatIntoFloat(_, float) => {
+                    match float {
+                        Float::F32 => &Type::Float(Float::F32),
+                        Float::F64 => &Type::Float(Float::F64),
+                    }
+                }
             },
         }
     }
@@ -1116,7 +1130,7 @@ impl Expr {
                     ..
                 })
                 | Expr::UnaryOp(UnaryOp {
-                    op_kind: UnaryOpKind::Not,
+                    op_kind: UnaryOpKind::Not | UnaryOpKind::IsNaN | UnaryOpKind::IsInfinite,
                     ..
                 })
                 | Expr::FuncApp(FuncApp {
@@ -1766,6 +1780,12 @@ pub enum PlaceComponent {
 pub enum UnaryOpKind {
     Not,
     Minus,
+    /// Whether a float is NaN.
+    IsNaN,
+    /// Whether a float is positive or negative infinity.
+    IsInfinite,
+    /// The absolute value of a float.
+    Abs,
 }
 
 #[derive(
@@ -2581,6 +2601,13 @@ impl Hash for DowncastExpr {
 pub enum CastKind {
     BVIntoInt(BitVector),
     IntIntoBV(BitVector),
+    /// Rounds an integer of the given bitvector type to the nearest float.
+    IntIntoFloat(BitVector, Float),
+    /// Rounds a float towards zero to an integer of the given bitvector type.
+    /// The result is unspecified if the float is NaN or out of range.
+    FloatIntoInt(Float, BitVector),
+    /// Rounds a float to the nearest float of another precision.
+    FloatIntoFloat(Float, Float),
 }
 
 #[derive(Debug, Clone, Eq, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
@@ -2637,6 +2664,9 @@ impl fmt::Display for UnaryOpKind {
         match self {
             UnaryOpKind::Not => write!(f, "!"),
             UnaryOpKind::Minus => write!(f, "-"),
+            UnaryOpKind::IsNaN => write!(f, "isNaN"),
+            UnaryOpKind::IsInfinite => write!(f, "isInfinite"),
+            UnaryOpKind::Abs => write!(f, "abs"),
         }
     }
 }
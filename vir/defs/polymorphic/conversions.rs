@@ -245,7 +245,7 @@ impl From<char> for Expr {
 
 impl From<f32> for Const {
     fn from(val: f32) -> Self {
-        Const::Float(FloatConst::F32(val as u32))
+        Const::Float(FloatConst::F32(val.to_bits()))
     }
 }
 
@@ -260,7 +260,7 @@ impl From<f32> for Expr {
 
 impl From<f64> for Const {
     fn from(val: f64) -> Self {
-        Const::Float(FloatConst::F64(val as u64))
+        Const::Float(FloatConst::F64(val.to_bits()))
     }
 }
 
@@ -13,6 +13,11 @@ impl WithLifetimes for ty::Type {
                 lifetimes,
                 ..
             })
+            | ty::Type::TraitObject(ty::TraitObject {
+                arguments,
+                lifetimes,
+                ..
+            })
             | ty::Type::Enum(ty::Enum {
                 arguments,
                 lifetimes,
@@ -174,7 +174,8 @@ impl Type {
             Self::Trusted(Trusted { arguments, .. })
             | Self::Struct(Struct { arguments, .. })
             | Self::Enum(Enum { arguments, .. })
-            | Self::Projection(Projection { arguments, .. }) => {
+            | Self::Projection(Projection { arguments, .. })
+            | Self::TraitObject(TraitObject { arguments, .. }) => {
                 arguments.iter().any(|arg| arg.is_type_var())
             }
             Self::Closure(_) => {
@@ -396,6 +396,9 @@ impl From<polymorphic::UnaryOpKind> for legacy::UnaryOpKind {
         match unary_op_kind {
             polymorphic::UnaryOpKind::Not => legacy::UnaryOpKind::Not,
             polymorphic::UnaryOpKind::Minus => legacy::UnaryOpKind::Minus,
+            polymorphic::UnaryOpKind::IsNaN => legacy::UnaryOpKind::IsNaN,
+            polymorphic::UnaryOpKind::IsInfinite => legacy::UnaryOpKind::IsInfinite,
+            polymorphic::UnaryOpKind::Abs => legacy::UnaryOpKind::Abs,
         }
     }
 }
@@ -444,6 +447,15 @@ impl From<polymorphic::CastKind> for legacy::CastKind {
         match container_op_kind {
             polymorphic::CastKind::BVIntoInt(size) => legacy::CastKind::BVIntoInt(size.into()),
             polymorphic::CastKind::IntIntoBV(size) => legacy::CastKind::IntIntoBV(size.into()),
+            polymorphic::CastKind::IntIntoFloat(size, float) => {
+                legacy::CastKind::IntIntoFloat(size.into(), float.into())
+            }
+            polymorphic::CastKind::FloatIntoInt(float, size) => {
+                legacy::CastKind::FloatIntoInt(float.into(), size.into())
+            }
+            polymorphic::CastKind::FloatIntoFloat(from, to) => {
+                legacy::CastKind::FloatIntoFloat(from.into(), to.into())
+            }
         }
     }
 }
@@ -89,6 +89,8 @@ pub enum UnaryOpKind {
     Not,
     Minus,
     IsNaN,
+    IsInfinite,
+    Abs,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -129,6 +131,9 @@ pub enum ContainerOpKind {
 pub enum CastKind {
     BVIntoInt(BitVector),
     IntIntoBV(BitVector),
+    IntIntoFloat(BitVector, Float),
+    FloatIntoInt(Float, BitVector),
+    FloatIntoFloat(Float, Float),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -316,6 +321,8 @@ impl fmt::Display for UnaryOpKind {
             UnaryOpKind::Not => write!(f, "!"),
             UnaryOpKind::Minus => write!(f, "-"),
             UnaryOpKind::IsNaN => write!(f, "isNaN"),
+            UnaryOpKind::IsInfinite => write!(f, "isInfinite"),
+            UnaryOpKind::Abs => write!(f, "abs"),
         }
     }
 }
@@ -1155,6 +1162,7 @@ impl Expr {
             | Expr::Variant(_, Field { ref typ, .. }, _)
             | Expr::Field(_, Field { ref typ, .. }, _)
             | Expr::AddrOf(_, ref typ, _) => typ,
+            Expr::UnaryOp(UnaryOpKind::IsNaN | UnaryOpKind::IsInfinite, _, _) => &Type::Bool,
             Expr::LabelledOld(_, box ref base, _)
             | Expr::Unfolding(_, _, box ref base, _, _, _)
             | Expr::UnaryOp(_, box ref base, _)
@@ -1291,6 +1299,13 @@ impl Expr {
                 CastKind::IntIntoBV(BitVector::Unsigned(BitVectorSize::BV128)) => {
                     &Type::BitVector(BitVector::Unsigned(BitVectorSize::BV128))
                 }
+                CastKind::FloatIntoInt(..) => &Type::Int,
+                CastKind::IntIntoFloat(_, float) | CastKind::FloatIntoFloat(_, float) => {
+                    match float {
+                        Float::F32 => &Type::Float(Float::F32),
+                        Float::F64 => &Type::Float(Float::F64),
+                    }
+                }
             },
         };
         Some(result)
@@ -1304,7 +1319,11 @@ impl Expr {
         } else {
             match self {
                 Expr::Const(Const::Bool(_), _)
-                | Expr::UnaryOp(UnaryOpKind::Not, _, _)
+                | Expr::UnaryOp(
+                    UnaryOpKind::Not | UnaryOpKind::IsNaN | UnaryOpKind::IsInfinite,
+                    _,
+                    _,
+                )
                 | Expr::FuncApp(_, _, _, Type::Bool, _)
                 | Expr::ForAll(..)
                 | Expr::Exists(..) => true,
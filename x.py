@@ -277,6 +277,7 @@ def package(mode: str, package_path: str):
         ("viper_tools", "."),
         (f"target/{mode}/prusti-driver*", "."),
         (f"target/{mode}/prusti-server*", "."),
+        (f"target/{mode}/prusti-cache-server*", "."),
         (f"target/{mode}/prusti-rustc*", "."),
         (f"target/{mode}/cargo-prusti*", "."),
         (f"target/verify/{mode}/libprusti_contracts.*", "."),